@@ -7,6 +7,8 @@
 //! - Convert proofs and public signals
 //! - Generate deployment commands
 //! - Format proofs for contract calls
+//! - Verify proofs locally (same verifier as the contracts, native BN254 backend)
+//! - Import gnark binary proofs and verifying keys
 //!
 //! ## Usage
 //! ```bash
@@ -16,6 +18,24 @@
 //! # Format proof for contract call
 //! near-zk format-proof proof.json public.json
 //!
+//! # Same, with the 128-byte compressed proof for `*_compact` methods
+//! near-zk format-proof proof.json public.json --compact
+//!
+//! # near CLI commands with precompile-layout arguments for `*_bytes` methods
+//! near-zk call-args --proof proof.json --signals public.json
+//!
+//! # Verify a proof off-chain
+//! near-zk verify --vk verification_key.json --proof proof.json --signals public.json
+//!
+//! # Public input for a hashed-input circuit (sha256(inputs) mod r)
+//! near-zk hash-inputs --signals inputs.json
+//!
+//! # Public input for a `poseidon_account` field
+//! near-zk encode-account alice.near
+//!
+//! # Convert gnark binary files into contract arguments
+//! near-zk convert-gnark --vk vk.bin --proof proof.bin
+//!
 //! # Generate deployment script
 //! near-zk deploy-script verification_key.json --contract my-contract.testnet
 //! ```
@@ -62,6 +82,10 @@ enum Commands {
         /// Output file (default: stdout)
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Emit the 128-byte compressed proof (base64) for `*_compact` methods
+        #[arg(long)]
+        compact: bool,
     },
 
     /// Generate deployment script
@@ -95,6 +119,9 @@ enum Commands {
     },
 
     /// Generate call arguments for verify method
+    ///
+    /// By default the arguments are in the alt_bn128 precompile layout
+    /// (base64), for the `*_bytes` contract methods.
     CallArgs {
         /// Path to proof.json
         #[arg(short, long)]
@@ -103,6 +130,70 @@ enum Commands {
         /// Path to public.json
         #[arg(short = 's', long)]
         signals: PathBuf,
+
+        /// Emit snarkjs JSON arguments for `verify` / `check_proof` instead
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Verify a proof locally, without deploying a contract
+    Verify {
+        /// Path to verification_key.json
+        #[arg(short, long)]
+        vk: PathBuf,
+
+        /// Path to proof.json
+        #[arg(short, long)]
+        proof: PathBuf,
+
+        /// Path to public.json
+        #[arg(short = 's', long)]
+        signals: PathBuf,
+    },
+
+    /// Compute the one public input of a hashed-input circuit
+    ///
+    /// Each input is packed as a 32-byte big-endian word, then hashed and
+    /// reduced mod r (or truncated to 253 bits with `--truncate`).
+    HashInputs {
+        /// Path to a JSON array of the full inputs (decimal or 0x hex)
+        #[arg(short = 's', long)]
+        signals: PathBuf,
+
+        /// Use keccak256 instead of SHA-256
+        #[arg(long)]
+        keccak: bool,
+
+        /// Keep the low 253 bits instead of reducing mod r
+        #[arg(long)]
+        truncate: bool,
+    },
+
+    /// Encode an account id as one public input
+    ///
+    /// Prints the `poseidon_account` encoding the contract compares against:
+    /// 31-byte big-endian chunks folded with 2-input Poseidon from the
+    /// byte length.
+    EncodeAccount {
+        /// NEAR account id (e.g. alice.near)
+        account: String,
+    },
+
+    /// Convert gnark binary proof / verifying key into contract arguments
+    ///
+    /// Accepts both `WriteTo` (compressed) and `WriteRawTo` (uncompressed) files.
+    ConvertGnark {
+        /// Path to the gnark verifying key (emits `new` init args)
+        #[arg(short, long)]
+        vk: Option<PathBuf>,
+
+        /// Path to the gnark proof (emits the `proof` argument)
+        #[arg(short, long)]
+        proof: Option<PathBuf>,
+
+        /// Output file (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
     /// Show circuit info from verification key
@@ -146,8 +237,8 @@ fn main() -> anyhow::Result<()> {
             convert_verification_key(&input, output.as_deref())?;
         }
 
-        Commands::FormatProof { proof, signals, output } => {
-            format_proof(&proof, &signals, output.as_deref())?;
+        Commands::FormatProof { proof, signals, output, compact } => {
+            format_proof(&proof, &signals, output.as_deref(), compact)?;
         }
 
         Commands::DeployScript { vk, contract, deployer, network, wasm } => {
@@ -158,8 +249,24 @@ fn main() -> anyhow::Result<()> {
             validate_vk(&input)?;
         }
 
-        Commands::CallArgs { proof, signals } => {
-            generate_call_args(&proof, &signals)?;
+        Commands::CallArgs { proof, signals, json } => {
+            generate_call_args(&proof, &signals, json)?;
+        }
+
+        Commands::Verify { vk, proof, signals } => {
+            verify_proof(&vk, &proof, &signals)?;
+        }
+
+        Commands::HashInputs { signals, keccak, truncate } => {
+            hash_inputs(&signals, keccak, truncate)?;
+        }
+
+        Commands::EncodeAccount { account } => {
+            encode_account(&account)?;
+        }
+
+        Commands::ConvertGnark { vk, proof, output } => {
+            convert_gnark(vk.as_deref(), proof.as_deref(), output.as_deref())?;
         }
 
         Commands::Info { input } => {
@@ -211,24 +318,42 @@ fn convert_verification_key(input: &PathBuf, output: Option<&std::path::Path>) -
     Ok(())
 }
 
-fn format_proof(proof_path: &PathBuf, signals_path: &PathBuf, output: Option<&std::path::Path>) -> anyhow::Result<()> {
+fn format_proof(
+    proof_path: &PathBuf,
+    signals_path: &PathBuf,
+    output: Option<&std::path::Path>,
+    compact: bool,
+) -> anyhow::Result<()> {
+    use near_groth16_verifier::{CompactProof, ProofJson};
+
     println!("{}", "Formatting proof for NEAR contract...".cyan());
 
     let proof_content = fs::read_to_string(proof_path)?;
     let signals_content = fs::read_to_string(signals_path)?;
 
-    let proof: Proof = serde_json::from_str(&proof_content)?;
     let signals: Vec<String> = serde_json::from_str(&signals_content)?;
 
     // Create the call format
-    let call_data = json!({
-        "proof": {
-            "pi_a": proof.pi_a,
-            "pi_b": proof.pi_b,
-            "pi_c": proof.pi_c
-        },
-        "public_inputs": signals
-    });
+    let call_data = if compact {
+        let proof: ProofJson = serde_json::from_str(&proof_content)?;
+        let proof = proof.to_proof().map_err(|e| anyhow::anyhow!("Invalid proof: {}", e))?;
+        let proof = CompactProof::from_proof(&proof)
+            .map_err(|e| anyhow::anyhow!("Cannot compress proof: {}", e))?;
+        json!({
+            "proof": proof,
+            "public_inputs": signals
+        })
+    } else {
+        let proof: Proof = serde_json::from_str(&proof_content)?;
+        json!({
+            "proof": {
+                "pi_a": proof.pi_a,
+                "pi_b": proof.pi_b,
+                "pi_c": proof.pi_c
+            },
+            "public_inputs": signals
+        })
+    };
 
     let output_str = serde_json::to_string_pretty(&call_data)?;
 
@@ -495,33 +620,148 @@ fn validate_vk(input: &PathBuf) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn generate_call_args(proof_path: &PathBuf, signals_path: &PathBuf) -> anyhow::Result<()> {
+fn generate_call_args(proof_path: &PathBuf, signals_path: &PathBuf, json: bool) -> anyhow::Result<()> {
+    use base64::Engine;
+    use near_groth16_verifier::{parse_inputs, ProofJson};
+
     let proof_content = fs::read_to_string(proof_path)?;
     let signals_content = fs::read_to_string(signals_path)?;
 
-    let proof: Proof = serde_json::from_str(&proof_content)?;
     let signals: Vec<String> = serde_json::from_str(&signals_content)?;
 
     // Generate the NEAR CLI call command
-    let call_data = json!({
-        "proof": {
-            "pi_a": proof.pi_a,
-            "pi_b": proof.pi_b,
-            "pi_c": proof.pi_c
-        },
-        "public_inputs": signals
-    });
+    let (call_data, verify, check) = if json {
+        let proof: Proof = serde_json::from_str(&proof_content)?;
+        let call_data = json!({
+            "proof": {
+                "pi_a": proof.pi_a,
+                "pi_b": proof.pi_b,
+                "pi_c": proof.pi_c
+            },
+            "public_inputs": signals
+        });
+        (call_data, "verify", "check_proof")
+    } else {
+        let proof: ProofJson = serde_json::from_str(&proof_content)?;
+        let proof = proof.to_proof().map_err(|e| anyhow::anyhow!("Invalid proof: {}", e))?;
+        let inputs = parse_inputs(&signals).map_err(|e| anyhow::anyhow!("Invalid public signals: {}", e))?;
+        let inputs: Vec<u8> = inputs.iter().flat_map(|x| x.to_le_bytes()).collect();
+
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let call_data = json!({
+            "proof": base64.encode(proof.to_precompile_bytes()),
+            "public_inputs": base64.encode(inputs)
+        });
+        (call_data, "verify_bytes", "check_proof_bytes")
+    };
 
     let call_json = serde_json::to_string(&call_data)?;
 
     println!("{}", "NEAR CLI call command:".cyan().bold());
     println!();
-    println!("near call CONTRACT_ID verify '{}' --accountId YOUR_ACCOUNT", call_json);
+    println!("near call CONTRACT_ID {} '{}' --accountId YOUR_ACCOUNT", verify, call_json);
     println!();
 
-    println!("{}", "Or for check_proof (view method):".cyan());
+    println!("{}", format!("Or for {} (view method):", check).cyan());
     println!();
-    println!("near view CONTRACT_ID check_proof '{}'", call_json);
+    println!("near view CONTRACT_ID {} '{}'", check, call_json);
+
+    Ok(())
+}
+
+fn verify_proof(vk_path: &PathBuf, proof_path: &PathBuf, signals_path: &PathBuf) -> anyhow::Result<()> {
+    use near_groth16_verifier::{ProofJson, VerificationKeyJson, Verifier};
+
+    let vk: VerificationKeyJson = serde_json::from_str(&fs::read_to_string(vk_path)?)?;
+    let proof: ProofJson = serde_json::from_str(&fs::read_to_string(proof_path)?)?;
+    let signals: Vec<String> = serde_json::from_str(&fs::read_to_string(signals_path)?)?;
+
+    let verifier = Verifier::from_json_checked(&vk)
+        .map_err(|e| anyhow::anyhow!("Invalid verification key: {}", e))?;
+
+    match verifier.verify_json_checked(&proof, &signals) {
+        Ok(()) => {
+            println!("{}", "✓ Proof is valid".green().bold());
+            Ok(())
+        }
+        Err(e) => {
+            println!("{} {}", "✗ Proof is invalid:".red().bold(), e);
+            anyhow::bail!("verification failed ({})", e.code())
+        }
+    }
+}
+
+fn hash_inputs(signals_path: &PathBuf, keccak: bool, truncate: bool) -> anyhow::Result<()> {
+    use near_groth16_verifier::{parse_words, DefaultBackend, InputHasher};
+
+    let signals: Vec<String> = serde_json::from_str(&fs::read_to_string(signals_path)?)?;
+    let inputs = parse_words(&signals).map_err(|e| anyhow::anyhow!("Invalid inputs: {}", e))?;
+
+    let mut hasher = if keccak { InputHasher::keccak256() } else { InputHasher::sha256() };
+    if truncate {
+        hasher = hasher.truncated();
+    }
+    let digest = hasher.digest_inputs(&DefaultBackend::default(), &inputs);
+
+    println!("{} {} inputs", "Hashed".green(), inputs.len());
+    println!("{}", serde_json::to_string(&[digest.to_string()])?);
+
+    Ok(())
+}
+
+fn encode_account(account: &str) -> anyhow::Result<()> {
+    use near_groth16_verifier::inputs::encoding::{Encoding, PoseidonAccount};
+
+    let account: near_sdk::AccountId = account
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid account id: {}", e))?;
+
+    println!("{} {}", "Encoded".green(), account);
+    println!("{}", PoseidonAccount::encode(&account));
+
+    Ok(())
+}
+
+fn convert_gnark(
+    vk_path: Option<&std::path::Path>,
+    proof_path: Option<&std::path::Path>,
+    output: Option<&std::path::Path>,
+) -> anyhow::Result<()> {
+    use near_groth16_verifier::{gnark, ProofJson, VerificationKeyJson};
+
+    if vk_path.is_none() && proof_path.is_none() {
+        anyhow::bail!("Pass --vk and/or --proof");
+    }
+
+    let mut call_data = serde_json::Map::new();
+
+    if let Some(path) = vk_path {
+        let vk = gnark::vk_from_bytes(&fs::read(path)?)
+            .map_err(|e| anyhow::anyhow!("Invalid gnark verifying key: {}", e))?;
+        vk.validate()
+            .map_err(|e| anyhow::anyhow!("Invalid gnark verifying key: {}", e))?;
+        println!("{} {} public inputs", "Verifying key has".green(), vk.num_inputs());
+        call_data.insert("vk".to_string(), serde_json::to_value(VerificationKeyJson::from_vk(&vk))?);
+    }
+
+    if let Some(path) = proof_path {
+        let proof = gnark::proof_from_bytes(&fs::read(path)?)
+            .map_err(|e| anyhow::anyhow!("Invalid gnark proof: {}", e))?;
+        let proof = ProofJson::from_proof(&proof);
+        call_data.insert(
+            "proof".to_string(),
+            json!({ "pi_a": proof.pi_a, "pi_b": proof.pi_b, "pi_c": proof.pi_c }),
+        );
+    }
+
+    let output_str = serde_json::to_string_pretty(&Value::Object(call_data))?;
+
+    if let Some(out_path) = output {
+        fs::write(out_path, &output_str)?;
+        println!("{} {}", "Saved to:".green(), out_path.display());
+    } else {
+        println!("{}", output_str);
+    }
 
     Ok(())
 }
@@ -0,0 +1,290 @@
+//! Procedural macros for `near_groth16_verifier`
+//!
+//! `#[derive(PublicInputs)]` implements `near_groth16_verifier::inputs::PublicInputs`
+//! for a struct, one public input per field in declaration order, and
+//! `FromPublicInputs` when every field can be decoded back. See the
+//! `inputs` module of `near_groth16_verifier` for the supported field types
+//! and encodings.
+//!
+//! `#[zk_method(...)]` puts proof verification and nullifier bookkeeping in
+//! front of a contract method body; see the `method` module.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, FnArg, Ident, ItemFn, LitInt, LitStr, Pat};
+
+/// `#[field(encoding = "...")]` values: name and whether it can be decoded
+const ENCODINGS: &[(&str, bool)] = &[
+    ("le", true),
+    ("poseidon_account", false),
+    ("sha256_account", false),
+];
+
+#[proc_macro_derive(PublicInputs, attributes(field))]
+pub fn derive_public_inputs(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+struct Field {
+    member: syn::Member,
+    ty: syn::Type,
+    /// Type in `inputs::encoding`, or `None` for the `InputField` default
+    encoding: Option<Ident>,
+    decodable: bool,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PublicInputs can only be derived for structs",
+            ))
+        }
+    };
+    let fields = match &data.fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unnamed(fields) => &fields.unnamed,
+        Fields::Unit => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "PublicInputs needs at least one field",
+            ))
+        }
+    };
+
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(i.into()),
+            };
+            let (encoding, decodable) = match field_encoding(field)? {
+                Some(name) => {
+                    let decodable = ENCODINGS
+                        .iter()
+                        .find(|(known, _)| *known == name.value())
+                        .map(|(_, decodable)| *decodable)
+                        .ok_or_else(|| {
+                            let known: Vec<_> = ENCODINGS.iter().map(|(n, _)| *n).collect();
+                            syn::Error::new_spanned(
+                                &name,
+                                format!("unknown encoding, expected one of: {}", known.join(", ")),
+                            )
+                        })?;
+                    (Some(Ident::new(&camel_case(&name.value()), name.span())), decodable)
+                }
+                None => (None, true),
+            };
+            Ok(Field { member, ty: field.ty.clone(), encoding, decodable })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let krate = quote!(::near_groth16_verifier);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let count = fields.len();
+
+    let encode = fields.iter().map(|Field { member, ty, encoding, .. }| match encoding {
+        Some(enc) => quote! {
+            <#krate::inputs::encoding::#enc as #krate::inputs::encoding::Encoding<#ty>>::encode(&self.#member)
+        },
+        None => quote! {
+            <#ty as #krate::inputs::InputField>::to_input(&self.#member)
+        },
+    });
+
+    let mut expanded = quote! {
+        impl #impl_generics #krate::inputs::PublicInputs for #name #ty_generics #where_clause {
+            const NUM_INPUTS: usize = #count;
+
+            fn to_inputs(&self) -> ::std::vec::Vec<#krate::U256> {
+                ::std::vec![#(#encode),*]
+            }
+        }
+    };
+
+    if fields.iter().all(|f| f.decodable) {
+        let decode = fields.iter().enumerate().map(|(index, Field { member, ty, encoding, .. })| {
+            let value = match encoding {
+                Some(enc) => quote! {
+                    <#krate::inputs::encoding::#enc as #krate::inputs::encoding::Decoding<#ty>>::decode(inputs[#index])
+                },
+                None => quote! {
+                    <#ty as #krate::inputs::InputField>::from_input(inputs[#index])
+                },
+            };
+            quote! {
+                #member: #value.ok_or(#krate::VerifyError::InvalidInput { index: #index })?
+            }
+        });
+
+        expanded.extend(quote! {
+            impl #impl_generics #krate::inputs::FromPublicInputs for #name #ty_generics #where_clause {
+                fn from_inputs(inputs: &[#krate::U256]) -> ::std::result::Result<Self, #krate::VerifyError> {
+                    if inputs.len() != #count {
+                        return ::std::result::Result::Err(#krate::VerifyError::InputCountMismatch {
+                            expected: #count,
+                            found: inputs.len(),
+                        });
+                    }
+                    ::std::result::Result::Ok(Self { #(#decode),* })
+                }
+            }
+        });
+    }
+
+    Ok(expanded)
+}
+
+/// The `encoding` of a field's `#[field(...)]` attribute, if any
+fn field_encoding(field: &syn::Field) -> syn::Result<Option<LitStr>> {
+    let mut encoding = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("field")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("encoding") {
+                if encoding.is_some() {
+                    return Err(meta.error("duplicate `encoding`"));
+                }
+                encoding = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported field attribute, expected `encoding`"))
+            }
+        })?;
+    }
+    Ok(encoding)
+}
+
+/// `poseidon_account` -> `PoseidonAccount`
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+#[proc_macro_attribute]
+pub fn zk_method(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = ZkMethodArgs::default();
+    let parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with parser);
+    let method = parse_macro_input!(item as ItemFn);
+    expand_zk_method(args, method).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+#[derive(Default)]
+struct ZkMethodArgs {
+    circuit: Option<LitStr>,
+    inputs: Option<syn::Type>,
+    nullifier: Option<LitInt>,
+    nullifiers: Option<LitStr>,
+    verifier: Option<LitStr>,
+}
+
+impl ZkMethodArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("circuit") {
+            self.circuit = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("inputs") {
+            self.inputs = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("nullifier") {
+            self.nullifier = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("nullifiers") {
+            self.nullifiers = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("verifier") {
+            self.verifier = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error(
+                "unsupported zk_method argument, expected `circuit`, `inputs`, `nullifier`, `nullifiers` or `verifier`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn expand_zk_method(args: ZkMethodArgs, mut method: ItemFn) -> syn::Result<TokenStream2> {
+    let sig = &method.sig;
+    let circuit = args
+        .circuit
+        .ok_or_else(|| syn::Error::new_spanned(sig, "zk_method needs `circuit = \"...\"`"))?;
+    let inputs_ty = args
+        .inputs
+        .ok_or_else(|| syn::Error::new_spanned(sig, "zk_method needs `inputs = Type`"))?;
+
+    // `proof` and `public_inputs` are shadowed in the body, so both must be
+    // plain arguments of the method
+    for name in ["proof", "public_inputs"] {
+        let found = sig.inputs.iter().any(|arg| match arg {
+            FnArg::Typed(arg) => matches!(&*arg.pat, Pat::Ident(p) if p.ident == name),
+            FnArg::Receiver(_) => false,
+        });
+        if !found {
+            return Err(syn::Error::new_spanned(
+                &sig.inputs,
+                format!("zk_method needs a `{}` argument", name),
+            ));
+        }
+    }
+
+    let krate = quote!(::near_groth16_verifier);
+    let field = |name: Option<LitStr>, default: &str| match name {
+        Some(name) => name.parse::<Ident>(),
+        None => Ok(Ident::new(default, proc_macro2::Span::call_site())),
+    };
+    let verifier = field(args.verifier, "verifier")?;
+
+    let nullifier = match args.nullifier {
+        Some(index) => {
+            let receiver_is_mut = matches!(
+                sig.inputs.first(),
+                Some(FnArg::Receiver(r)) if r.mutability.is_some()
+            );
+            if !receiver_is_mut {
+                return Err(syn::Error::new_spanned(
+                    &index,
+                    "tracking a nullifier needs a `&mut self` method",
+                ));
+            }
+            let index: usize = index.base10_parse()?;
+            let nullifiers = field(args.nullifiers, "nullifiers")?;
+            quote! {
+                const _: () = ::std::assert!(
+                    #index < <#inputs_ty as #krate::inputs::PublicInputs>::NUM_INPUTS,
+                    "zk_method: nullifier index out of range"
+                );
+                let __zk_nullifier = ::std::option::Option::Some((#index, &mut self.#nullifiers));
+            }
+        }
+        None => {
+            if let Some(nullifiers) = args.nullifiers {
+                return Err(syn::Error::new_spanned(nullifiers, "`nullifiers` needs `nullifier = N`"));
+            }
+            quote! {
+                let __zk_nullifier = ::std::option::Option::None;
+            }
+        }
+    };
+
+    let body = &method.block;
+    method.block = syn::parse_quote!({
+        let proof = #krate::method::parse_proof(#circuit, proof.to_proof());
+        let (__zk_inputs, public_inputs) =
+            #krate::method::parse_typed_inputs::<#inputs_ty>(#circuit, &public_inputs);
+        #nullifier
+        #krate::method::verify_and_nullify(#circuit, &self.#verifier, &proof, &__zk_inputs, __zk_nullifier);
+        #body
+    });
+
+    Ok(quote!(#method))
+}
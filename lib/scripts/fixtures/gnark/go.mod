@@ -0,0 +1,8 @@
+module fixtures/gnark
+
+go 1.21
+
+require (
+	github.com/consensys/gnark v0.10.0
+	github.com/consensys/gnark-crypto v0.12.1
+)
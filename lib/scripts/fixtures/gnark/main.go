@@ -0,0 +1,98 @@
+// Prints the gnark fixture of src/commitment.rs: a BN254 Groth16 verifying
+// key and proof for a circuit that calls api.Commit, in gnark's WriteTo
+// (compressed) encoding, as hex to paste into GNARK_VK and GNARK_PROOF.
+//
+//	go mod tidy && go run .
+//
+// -raw prints the WriteRawTo (uncompressed) encoding instead.
+package main
+
+import (
+	"bytes"
+	"encoding/hex"
+	"flag"
+	"fmt"
+	"io"
+	"log"
+
+	"github.com/consensys/gnark-crypto/ecc"
+	"github.com/consensys/gnark/backend/groth16"
+	"github.com/consensys/gnark/frontend"
+	"github.com/consensys/gnark/frontend/cs/r1cs"
+)
+
+// Circuit has public X and private A, B with A·B = X, and commits to A and
+// X, so the verifier hashes the commitment together with the public X
+type Circuit struct {
+	X frontend.Variable `gnark:",public"`
+	A frontend.Variable
+	B frontend.Variable
+}
+
+func (c *Circuit) Define(api frontend.API) error {
+	api.AssertIsEqual(api.Mul(c.A, c.B), c.X)
+
+	committer, ok := api.(frontend.Committer)
+	if !ok {
+		return fmt.Errorf("builder does not support commitments")
+	}
+	h, err := committer.Commit(c.A, c.X)
+	if err != nil {
+		return err
+	}
+	r := api.Mul(h, c.A)
+	s := api.Add(r, c.B, 1)
+	api.AssertIsDifferent(api.Mul(s, s), 0)
+	return nil
+}
+
+func encode(raw bool, value interface {
+	WriteTo(io.Writer) (int64, error)
+	WriteRawTo(io.Writer) (int64, error)
+}) string {
+	var buf bytes.Buffer
+	var err error
+	if raw {
+		_, err = value.WriteRawTo(&buf)
+	} else {
+		_, err = value.WriteTo(&buf)
+	}
+	if err != nil {
+		log.Fatal(err)
+	}
+	return hex.EncodeToString(buf.Bytes())
+}
+
+func main() {
+	raw := flag.Bool("raw", false, "print the WriteRawTo encoding")
+	flag.Parse()
+
+	ccs, err := frontend.Compile(ecc.BN254.ScalarField(), r1cs.NewBuilder, &Circuit{})
+	if err != nil {
+		log.Fatal(err)
+	}
+	pk, vk, err := groth16.Setup(ccs)
+	if err != nil {
+		log.Fatal(err)
+	}
+
+	witness, err := frontend.NewWitness(&Circuit{X: 15, A: 3, B: 5}, ecc.BN254.ScalarField())
+	if err != nil {
+		log.Fatal(err)
+	}
+	proof, err := groth16.Prove(ccs, pk, witness)
+	if err != nil {
+		log.Fatal(err)
+	}
+	public, err := witness.Public()
+	if err != nil {
+		log.Fatal(err)
+	}
+	if err := groth16.Verify(proof, vk, public); err != nil {
+		log.Fatal(err)
+	}
+
+	fmt.Println("public inputs: [15]")
+	fmt.Println("GNARK_VK:", encode(*raw, vk))
+	fmt.Println("GNARK_PROOF:", encode(*raw, proof))
+}
@@ -0,0 +1,27 @@
+#!/bin/sh
+# Regenerates the snarkjs fixtures of src/plonk.rs and src/fflonk.rs from
+# square.circom.
+#
+# Needs circom 2.1 and snarkjs 0.7 on PATH. Paste
+# build/<protocol>/verification_key.json, proof.json and public.json into
+# FIXTURE_VK, FIXTURE_PROOF and FIXTURE_PUBLIC.
+set -e
+cd "$(dirname "$0")"
+mkdir -p build
+
+circom square.circom --O1 --r1cs --wasm -o build
+snarkjs powersoftau new bn128 10 build/pot_0.ptau
+snarkjs powersoftau contribute build/pot_0.ptau build/pot_1.ptau --name=fixture -e="fixture entropy"
+snarkjs powersoftau prepare phase2 build/pot_1.ptau build/pot.ptau
+
+echo '{"w": "3"}' > build/input.json
+node build/square_js/generate_witness.js build/square_js/square.wasm build/input.json build/witness.wtns
+
+for protocol in plonk fflonk; do
+    out="build/$protocol"
+    mkdir -p "$out"
+    snarkjs "$protocol" setup build/square.r1cs build/pot.ptau "$out/circuit.zkey"
+    snarkjs zkey export verificationkey "$out/circuit.zkey" "$out/verification_key.json"
+    snarkjs "$protocol" prove "$out/circuit.zkey" build/witness.wtns "$out/proof.json" "$out/public.json"
+    snarkjs "$protocol" verify "$out/verification_key.json" "$out/public.json" "$out/proof.json"
+done
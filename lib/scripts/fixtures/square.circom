@@ -0,0 +1,14 @@
+pragma circom 2.1.0;
+
+// Circuit of the PLONK and fflonk test fixtures: two public outputs,
+// x = w·w and y = w + x, so w = 3 gives public.json ["9", "12"]
+template Square() {
+    signal input w;
+    signal output x;
+    signal output y;
+
+    x <== w * w;
+    y <== w + x;
+}
+
+component main = Square();
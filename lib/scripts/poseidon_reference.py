@@ -0,0 +1,81 @@
+"""circomlib Poseidon over BN254, straight from the reference parameter
+generator (generate_parameters_grain.sage with field=1, sbox=0 (x^5),
+n=254, R_F=8) instead of the Rust constant tables.
+
+    python3 poseidon_reference.py 1 2 3    # Poseidon(3) of [1, 2, 3]
+
+Used to produce the `POSEIDON_N` test vectors in src/poseidon_tests.rs and,
+through smt_reference.py, the sparse Merkle tree vectors in src/smt.rs.
+"""
+import sys
+
+P = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+N = 254
+R_F = 8
+R_P = [56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68]
+
+
+class Grain:
+    def __init__(self, t, r_p):
+        bits = []
+        for value, width in [(1, 2), (0, 4), (N, 12), (t, 12), (R_F, 10), (r_p, 10)]:
+            bits += [(value >> (width - 1 - i)) & 1 for i in range(width)]
+        bits += [1] * 30
+        self.state = bits
+        for _ in range(160):
+            self._step()
+
+    def _step(self):
+        s = self.state
+        new = s[62] ^ s[51] ^ s[38] ^ s[23] ^ s[13] ^ s[0]
+        self.state = s[1:] + [new]
+        return new
+
+    def bit(self):
+        while True:
+            b1 = self._step()
+            b2 = self._step()
+            if b1 == 1:
+                return b2
+
+    def bits(self):
+        v = 0
+        for _ in range(N):
+            v = (v << 1) | self.bit()
+        return v
+
+    def field(self):
+        # Round constants are rejection-sampled...
+        while True:
+            v = self.bits()
+            if v < P:
+                return v
+
+
+def params(t):
+    r_p = R_P[t - 2]
+    g = Grain(t, r_p)
+    c = [g.field() for _ in range((R_F + r_p) * t)]
+    # ...the Cauchy matrix points are reduced mod p
+    xs = [g.bits() % P for _ in range(t)]
+    ys = [g.bits() % P for _ in range(t)]
+    m = [[pow(xs[i] + ys[j], P - 2, P) for j in range(t)] for i in range(t)]
+    return c, m, r_p
+
+
+def poseidon(inputs):
+    t = len(inputs) + 1
+    c, m, r_p = params(t)
+    state = [0] + [x % P for x in inputs]
+    for r in range(R_F + r_p):
+        state = [(x + c[r * t + i]) % P for i, x in enumerate(state)]
+        if r < R_F // 2 or r >= R_F // 2 + r_p:
+            state = [pow(x, 5, P) for x in state]
+        else:
+            state[0] = pow(state[0], 5, P)
+        state = [sum(m[i][j] * state[j] for j in range(t)) % P for i in range(t)]
+    return state[0]
+
+
+if __name__ == "__main__":
+    print(poseidon([int(a) for a in sys.argv[1:]]))
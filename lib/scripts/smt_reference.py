@@ -0,0 +1,84 @@
+"""circomlibjs `SMT` (src/smt.js) insert and find over an in-memory db,
+hashing with poseidon_reference.py."""
+import sys
+
+from poseidon_reference import poseidon
+
+
+def hash0(left, right):
+    return poseidon([left, right])
+
+
+def hash1(key, value):
+    return poseidon([key, value, 1])
+
+
+def split_bits(key):
+    return [(key >> i) & 1 for i in range(256)]
+
+
+class Smt:
+    def __init__(self):
+        self.root = 0
+        self.db = {}
+
+    def find(self, key):
+        return self._find(key, split_bits(key), self.root, 0)
+
+    def _find(self, key, bits, root, level):
+        if root == 0:
+            return dict(found=False, siblings=[], not_found_key=key, not_found_value=0, is_old0=True)
+        record = self.db[root]
+        if len(record) == 3 and record[0] == 1:
+            if record[1] == key:
+                return dict(found=True, siblings=[], found_value=record[2], is_old0=False)
+            return dict(found=False, siblings=[], not_found_key=record[1], not_found_value=record[2], is_old0=False)
+        if bits[level] == 0:
+            res = self._find(key, bits, record[0], level + 1)
+            res["siblings"].insert(0, record[1])
+        else:
+            res = self._find(key, bits, record[1], level + 1)
+            res["siblings"].insert(0, record[0])
+        return res
+
+    def insert(self, key, value):
+        found = self.find(key)
+        assert not found["found"], "key already exists"
+        siblings = list(found["siblings"])
+        new_bits = split_bits(key)
+        mixed = False
+        rt_old = 0
+        added_one = False
+        if not found["is_old0"]:
+            old_bits = split_bits(found["not_found_key"])
+            i = len(siblings)
+            while old_bits[i] == new_bits[i]:
+                siblings.append(0)
+                i += 1
+            rt_old = hash1(found["not_found_key"], found["not_found_value"])
+            siblings.append(rt_old)
+            added_one = True
+        elif siblings:
+            mixed = True
+            rt_old = 0
+
+        rt = hash1(key, value)
+        self.db[rt] = [1, key, value]
+        for i in range(len(siblings) - 1, -1, -1):
+            if i < len(siblings) - 1 and siblings[i] != 0:
+                mixed = True
+            if mixed:
+                old_sibling = found["siblings"][i]
+                rt_old = hash0(old_sibling, rt_old) if new_bits[i] else hash0(rt_old, old_sibling)
+            rt_new = hash0(siblings[i], rt) if new_bits[i] else hash0(rt, siblings[i])
+            self.db[rt_new] = [rt, siblings[i]] if not new_bits[i] else [siblings[i], rt]
+            rt = rt_new
+        self.root = rt
+
+
+if __name__ == "__main__":
+    tree = Smt()
+    for pair in sys.argv[1:]:
+        key, value = map(int, pair.split(":"))
+        tree.insert(key, value)
+        print(f"insert {key}:{value} root {tree.root}")
@@ -0,0 +1,397 @@
+//! arkworks `CanonicalSerialize` encodings of points, proofs and keys
+//!
+//! Lets proofs and keys produced with `ark-groth16` / `ark-bn254` be passed
+//! to the verifier directly, without a snarkjs round trip. The layout is the
+//! one of ark-serialize 0.4:
+//!
+//! - Fq: 32 bytes, little-endian, canonical (not Montgomery)
+//! - Fq2: `c0 ‖ c1`
+//! - G1 / G2 compressed: `x`, with flags in the top bits of the last byte
+//! - G1 / G2 uncompressed: `x ‖ y`, with flags in the top bits of the last byte of `y`
+//! - `Proof`: `a ‖ b ‖ c`
+//! - `VerifyingKey`: `alpha_g1 ‖ beta_g2 ‖ gamma_g2 ‖ delta_g2 ‖ gamma_abc_g1`,
+//!   the vector prefixed by its length as a little-endian `u64`
+//!
+//! Flags (`SWFlags`): bit 6 marks the point at infinity (written with zero
+//! coordinates), bit 7 marks a y coordinate that is lexicographically
+//! largest (`y > -y`). arkworks writes the sign bit in both modes but only
+//! reads it when decompressing.
+//!
+//! Decoding checks that points are on the curve. As for any key or proof
+//! that comes from users, G2 subgroup membership is left to
+//! [`VerificationKey::validate`] and [`Proof::validate`].
+
+use crate::curve::{g1_decompress, g1_y_is_largest, g2_decompress, g2_y_is_largest};
+use crate::error::{ParseError, PointError};
+use crate::types::{G1Point, G2Point, Proof, VerificationKey, U256};
+
+/// Set on the last byte for the point at infinity
+const INFINITY_FLAG: u8 = 1 << 6;
+/// Set on the last byte when y is the lexicographically largest root
+const Y_IS_NEGATIVE_FLAG: u8 = 1 << 7;
+const FLAG_MASK: u8 = INFINITY_FLAG | Y_IS_NEGATIVE_FLAG;
+
+/// Point encoding mode, as arkworks' `Compress`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compress {
+    /// x only, with the sign of y in the flags (`serialize_compressed`)
+    Yes,
+    /// x and y (`serialize_uncompressed`)
+    No,
+}
+
+impl Compress {
+    /// Encoded size of a G1 point in this mode
+    pub const fn g1_size(self) -> usize {
+        match self {
+            Compress::Yes => 32,
+            Compress::No => 64,
+        }
+    }
+
+    /// Encoded size of a G2 point in this mode
+    pub const fn g2_size(self) -> usize {
+        2 * self.g1_size()
+    }
+}
+
+impl G1Point {
+    /// Serialize like arkworks `G1Affine::serialize_with_mode`
+    pub fn to_arkworks_bytes(&self, compress: Compress) -> Vec<u8> {
+        let mut out = Vec::with_capacity(compress.g1_size());
+        write_g1(&mut out, self, compress);
+        out
+    }
+
+    /// Deserialize an arkworks `G1Affine`, checking that it is on the curve
+    pub fn from_arkworks_bytes(bytes: &[u8], compress: Compress) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(bytes, compress);
+        let point = reader.g1("G1")?;
+        reader.finish()?;
+        Ok(point)
+    }
+}
+
+impl G2Point {
+    /// Serialize like arkworks `G2Affine::serialize_with_mode`
+    pub fn to_arkworks_bytes(&self, compress: Compress) -> Vec<u8> {
+        let mut out = Vec::with_capacity(compress.g2_size());
+        write_g2(&mut out, self, compress);
+        out
+    }
+
+    /// Deserialize an arkworks `G2Affine`, checking that it is on the twist
+    pub fn from_arkworks_bytes(bytes: &[u8], compress: Compress) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(bytes, compress);
+        let point = reader.g2("G2")?;
+        reader.finish()?;
+        Ok(point)
+    }
+}
+
+impl Proof {
+    /// Serialize like `ark_groth16::Proof::serialize_with_mode`
+    pub fn to_arkworks_bytes(&self, compress: Compress) -> Vec<u8> {
+        let mut out = Vec::with_capacity(2 * compress.g1_size() + compress.g2_size());
+        write_g1(&mut out, &self.a, compress);
+        write_g2(&mut out, &self.b, compress);
+        write_g1(&mut out, &self.c, compress);
+        out
+    }
+
+    /// Deserialize an `ark_groth16::Proof<Bn254>`
+    pub fn from_arkworks_bytes(bytes: &[u8], compress: Compress) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(bytes, compress);
+        let proof = Proof {
+            a: reader.g1("a")?,
+            b: reader.g2("b")?,
+            c: reader.g1("c")?,
+        };
+        reader.finish()?;
+        Ok(proof)
+    }
+}
+
+impl VerificationKey {
+    /// Serialize like `ark_groth16::VerifyingKey::serialize_with_mode`
+    pub fn to_arkworks_bytes(&self, compress: Compress) -> Vec<u8> {
+        let mut out = Vec::with_capacity(
+            compress.g1_size() + 3 * compress.g2_size() + 8 + self.ic.len() * compress.g1_size(),
+        );
+        write_g1(&mut out, &self.alpha, compress);
+        write_g2(&mut out, &self.beta, compress);
+        write_g2(&mut out, &self.gamma, compress);
+        write_g2(&mut out, &self.delta, compress);
+        out.extend_from_slice(&(self.ic.len() as u64).to_le_bytes());
+        for point in &self.ic {
+            write_g1(&mut out, point, compress);
+        }
+        out
+    }
+
+    /// Deserialize an `ark_groth16::VerifyingKey<Bn254>`
+    ///
+    /// `gamma_abc_g1` becomes the IC.
+    pub fn from_arkworks_bytes(bytes: &[u8], compress: Compress) -> Result<Self, ParseError> {
+        let mut reader = Reader::new(bytes, compress);
+        let alpha = reader.g1("alpha")?;
+        let beta = reader.g2("beta")?;
+        let gamma = reader.g2("gamma")?;
+        let delta = reader.g2("delta")?;
+        let count = usize::try_from(reader.u64()?).map_err(|_| ParseError::IntegerOverflow)?;
+        let ic = (0..count)
+            .map(|_| reader.g1("gamma_abc"))
+            .collect::<Result<Vec<_>, _>>()?;
+        reader.finish()?;
+        Ok(Self { alpha, beta, gamma, delta, ic })
+    }
+}
+
+/// `SWFlags` of a point, to be OR-ed into the last byte
+fn flags(is_zero: bool, y_largest: bool) -> u8 {
+    match (is_zero, y_largest) {
+        (true, _) => INFINITY_FLAG,
+        (false, true) => Y_IS_NEGATIVE_FLAG,
+        (false, false) => 0,
+    }
+}
+
+/// Append `values` little-endian, setting `flags` on the very last byte
+fn write_with_flags(out: &mut Vec<u8>, values: &[U256], flags: u8) {
+    for value in values {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    if let Some(last) = out.last_mut() {
+        *last |= flags;
+    }
+}
+
+fn write_g1(out: &mut Vec<u8>, p: &G1Point, compress: Compress) {
+    let flags = flags(p.is_zero(), g1_y_is_largest(p));
+    match compress {
+        Compress::Yes => write_with_flags(out, &[p.x], flags),
+        Compress::No => write_with_flags(out, &[p.x, p.y], flags),
+    }
+}
+
+fn write_g2(out: &mut Vec<u8>, p: &G2Point, compress: Compress) {
+    let flags = flags(p.is_zero(), g2_y_is_largest(p));
+    match compress {
+        Compress::Yes => write_with_flags(out, &p.x, flags),
+        Compress::No => write_with_flags(out, &[p.x[0], p.x[1], p.y[0], p.y[1]], flags),
+    }
+}
+
+/// The identity, provided its coordinates were written as zero
+fn infinity<P>(coordinates_are_zero: bool, point: P) -> Result<P, ParseError> {
+    if coordinates_are_zero {
+        Ok(point)
+    } else {
+        Err(ParseError::InvalidPointFlags)
+    }
+}
+
+/// Cursor over an arkworks encoding
+struct Reader<'a> {
+    bytes: &'a [u8],
+    compress: Compress,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], compress: Compress) -> Self {
+        Self { bytes, compress }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        let (head, tail) = self.bytes.split_at_checked(n).ok_or(ParseError::UnexpectedEnd)?;
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u64(&mut self) -> Result<u64, ParseError> {
+        let bytes = self.take(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("took 8 bytes")))
+    }
+
+    /// `N` little-endian field elements, and the flags of the last one
+    fn coordinates<const N: usize>(&mut self) -> Result<([U256; N], u8), ParseError> {
+        let mut bytes = self.take(32 * N)?.to_vec();
+        let last = bytes.len() - 1;
+        let flags = bytes[last] & FLAG_MASK;
+        bytes[last] &= !FLAG_MASK;
+        if flags == FLAG_MASK {
+            return Err(ParseError::InvalidPointFlags);
+        }
+        let values = std::array::from_fn(|i| U256::from_le_bytes(&bytes[32 * i..32 * (i + 1)]));
+        Ok((values, flags))
+    }
+
+    fn g1(&mut self, element: &'static str) -> Result<G1Point, ParseError> {
+        let invalid = |reason| ParseError::InvalidPoint { element, reason };
+        match self.compress {
+            Compress::Yes => {
+                let ([x], flags) = self.coordinates::<1>()?;
+                if flags == INFINITY_FLAG {
+                    return infinity(x.is_zero(), G1Point::zero());
+                }
+                g1_decompress(&x, flags == Y_IS_NEGATIVE_FLAG).map_err(invalid)
+            }
+            Compress::No => {
+                let ([x, y], flags) = self.coordinates::<2>()?;
+                let point = G1Point { x, y };
+                if flags == INFINITY_FLAG {
+                    return infinity(point.is_zero(), point);
+                }
+                if point.is_zero() || !point.is_on_curve() {
+                    return Err(invalid(PointError::NotOnCurve));
+                }
+                Ok(point)
+            }
+        }
+    }
+
+    fn g2(&mut self, element: &'static str) -> Result<G2Point, ParseError> {
+        let invalid = |reason| ParseError::InvalidPoint { element, reason };
+        match self.compress {
+            Compress::Yes => {
+                let (x, flags) = self.coordinates::<2>()?;
+                if flags == INFINITY_FLAG {
+                    return infinity(x.iter().all(U256::is_zero), G2Point::zero());
+                }
+                g2_decompress(&x, flags == Y_IS_NEGATIVE_FLAG).map_err(invalid)
+            }
+            Compress::No => {
+                let ([x0, x1, y0, y1], flags) = self.coordinates::<4>()?;
+                let point = G2Point { x: [x0, x1], y: [y0, y1] };
+                if flags == INFINITY_FLAG {
+                    return infinity(point.is_zero(), point);
+                }
+                if point.is_zero() || !point.is_on_curve() {
+                    return Err(invalid(PointError::NotOnCurve));
+                }
+                Ok(point)
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), ParseError> {
+        match self.bytes.len() {
+            0 => Ok(()),
+            count => Err(ParseError::TrailingBytes { count }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16_tests::groth16_integration_tests::{fixture, FIXTURE_INPUTS};
+    use crate::types::FQ_MODULUS;
+    use crate::Verifier;
+
+    #[test]
+    fn test_g1_generator_encoding() {
+        let g = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+        let compressed = g.to_arkworks_bytes(Compress::Yes);
+        let mut expected = [0u8; 32];
+        expected[0] = 1;
+        assert_eq!(compressed, expected);
+
+        // -G has the same x and the sign bit set
+        let neg = G1Point { x: g.x, y: FQ_MODULUS - g.y };
+        expected[31] = Y_IS_NEGATIVE_FLAG;
+        assert_eq!(neg.to_arkworks_bytes(Compress::Yes), expected);
+        let back = G1Point::from_arkworks_bytes(&expected, Compress::Yes).unwrap();
+        assert_eq!(back.y, neg.y);
+
+        let uncompressed = g.to_arkworks_bytes(Compress::No);
+        assert_eq!((uncompressed[0], uncompressed[32]), (1, 2));
+        assert_eq!(G1Point::from_arkworks_bytes(&uncompressed, Compress::No).unwrap().y, g.y);
+    }
+
+    #[test]
+    fn test_identity_encoding() {
+        for compress in [Compress::Yes, Compress::No] {
+            let bytes = G1Point::zero().to_arkworks_bytes(compress);
+            assert_eq!(bytes[bytes.len() - 1], INFINITY_FLAG);
+            assert!(G1Point::from_arkworks_bytes(&bytes, compress).unwrap().is_zero());
+
+            let bytes = G2Point::zero().to_arkworks_bytes(compress);
+            assert_eq!(bytes.len(), compress.g2_size());
+            assert!(G2Point::from_arkworks_bytes(&bytes, compress).unwrap().is_zero());
+
+            // Infinity with non-zero coordinates
+            let mut bad = bytes.clone();
+            bad[0] = 1;
+            assert_eq!(
+                G2Point::from_arkworks_bytes(&bad, compress).unwrap_err(),
+                ParseError::InvalidPointFlags
+            );
+        }
+        // Both flags at once is not a valid SWFlags value
+        let mut both = [0u8; 32];
+        both[31] = FLAG_MASK;
+        assert_eq!(
+            G1Point::from_arkworks_bytes(&both, Compress::Yes).unwrap_err(),
+            ParseError::InvalidPointFlags
+        );
+    }
+
+    #[test]
+    fn test_proof_and_vk_roundtrip_verify() {
+        let (verifier, proof_json, _) = fixture();
+        let proof = proof_json.to_proof().unwrap();
+        let inputs: Vec<U256> = FIXTURE_INPUTS.iter().map(|s| U256::from_dec_str(s).unwrap()).collect();
+
+        for compress in [Compress::Yes, Compress::No] {
+            let proof_bytes = proof.to_arkworks_bytes(compress);
+            assert_eq!(proof_bytes.len(), 2 * compress.g1_size() + compress.g2_size());
+            let decoded = Proof::from_arkworks_bytes(&proof_bytes, compress).unwrap();
+            assert_eq!(decoded.to_arkworks_bytes(compress), proof_bytes);
+
+            let vk_bytes = verifier.vk.to_arkworks_bytes(compress);
+            let vk = VerificationKey::from_arkworks_bytes(&vk_bytes, compress).unwrap();
+            assert_eq!(vk.to_arkworks_bytes(compress), vk_bytes);
+            let decoded_verifier = Verifier::try_new(vk).unwrap();
+            assert_eq!(decoded_verifier.verify_checked(&inputs, &decoded), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_malformed_encodings_rejected() {
+        let (verifier, proof_json, _) = fixture();
+        let proof = proof_json.to_proof().unwrap();
+        let bytes = proof.to_arkworks_bytes(Compress::Yes);
+
+        assert_eq!(
+            Proof::from_arkworks_bytes(&bytes[..127], Compress::Yes).unwrap_err(),
+            ParseError::UnexpectedEnd
+        );
+        // Reading a compressed proof as uncompressed pairs x with the next x
+        assert_eq!(
+            Proof::from_arkworks_bytes(&bytes, Compress::No).unwrap_err(),
+            ParseError::InvalidPoint { element: "a", reason: PointError::NotOnCurve }
+        );
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(
+            Proof::from_arkworks_bytes(&trailing, Compress::Yes).unwrap_err(),
+            ParseError::TrailingBytes { count: 1 }
+        );
+
+        // x = 4 is not the abscissa of any G1 point
+        let mut off_curve = bytes.clone();
+        off_curve[..32].copy_from_slice(&U256::from(4u64).to_le_bytes());
+        assert_eq!(
+            Proof::from_arkworks_bytes(&off_curve, Compress::Yes).unwrap_err(),
+            ParseError::InvalidPoint { element: "a", reason: PointError::NotOnCurve }
+        );
+
+        let mut vk = verifier.vk.to_arkworks_bytes(Compress::No);
+        vk[32] ^= 1;
+        assert_eq!(
+            VerificationKey::from_arkworks_bytes(&vk, Compress::No).unwrap_err(),
+            ParseError::InvalidPoint { element: "alpha", reason: PointError::NotOnCurve }
+        );
+    }
+}
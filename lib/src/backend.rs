@@ -0,0 +1,201 @@
+//! Backends for the alt_bn128 operations, keccak256 and sha256
+//!
+//! [`Verifier`](crate::Verifier) and the transcripts in [`crate::transcript`]
+//! are generic over [`Bn254Backend`], which takes the same byte payloads as
+//! NEAR's host functions:
+//!
+//! - [`NearBackend`]: the NEAR runtime host functions (`env::alt_bn128_*`)
+//! - [`NativeBackend`]: the pure-Rust implementation in [`crate::native`]
+//!   (`standalone` feature)
+//! - [`RecordingBackend`]: wraps another backend and records every payload,
+//!   so tests can assert exactly what would be sent to the host
+//!
+//! [`DefaultBackend`] is `NearBackend`, or `NativeBackend` with the
+//! `standalone` feature; it is what `Verifier` uses unless told otherwise.
+
+use std::cell::RefCell;
+
+use near_sdk::env;
+
+/// The curve and hash operations a verifier needs
+///
+/// Payload layouts are those of the alt_bn128 host functions (little-endian
+/// coordinates). Implementations may panic on malformed input, as the host
+/// aborts the transaction.
+pub trait Bn254Backend {
+    /// `(sign (1) ‖ G1 (64))*` → Σ ±P_i as 64 bytes
+    fn g1_sum(&self, input: &[u8]) -> Vec<u8>;
+
+    /// `(G1 (64) ‖ scalar (32))*` → Σ s_i·P_i as 64 bytes
+    fn g1_multiexp(&self, input: &[u8]) -> Vec<u8>;
+
+    /// `(G1 (64) ‖ G2 (128))*` → whether Π e(P_i, Q_i) = 1
+    fn pairing_check(&self, input: &[u8]) -> bool;
+
+    /// keccak256 digest
+    fn keccak256(&self, input: &[u8]) -> [u8; 32];
+
+    /// sha256 digest
+    fn sha256(&self, input: &[u8]) -> [u8; 32];
+}
+
+impl<B: Bn254Backend + ?Sized> Bn254Backend for &B {
+    fn g1_sum(&self, input: &[u8]) -> Vec<u8> {
+        (**self).g1_sum(input)
+    }
+
+    fn g1_multiexp(&self, input: &[u8]) -> Vec<u8> {
+        (**self).g1_multiexp(input)
+    }
+
+    fn pairing_check(&self, input: &[u8]) -> bool {
+        (**self).pairing_check(input)
+    }
+
+    fn keccak256(&self, input: &[u8]) -> [u8; 32] {
+        (**self).keccak256(input)
+    }
+
+    fn sha256(&self, input: &[u8]) -> [u8; 32] {
+        (**self).sha256(input)
+    }
+}
+
+/// NEAR runtime host functions
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NearBackend;
+
+impl Bn254Backend for NearBackend {
+    fn g1_sum(&self, input: &[u8]) -> Vec<u8> {
+        env::alt_bn128_g1_sum(input)
+    }
+
+    fn g1_multiexp(&self, input: &[u8]) -> Vec<u8> {
+        env::alt_bn128_g1_multiexp(input)
+    }
+
+    fn pairing_check(&self, input: &[u8]) -> bool {
+        env::alt_bn128_pairing_check(input)
+    }
+
+    fn keccak256(&self, input: &[u8]) -> [u8; 32] {
+        env::keccak256_array(input)
+    }
+
+    fn sha256(&self, input: &[u8]) -> [u8; 32] {
+        env::sha256_array(input)
+    }
+}
+
+/// Pure-Rust backend, usable outside the NEAR runtime
+#[cfg(any(feature = "standalone", test))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NativeBackend;
+
+#[cfg(any(feature = "standalone", test))]
+impl Bn254Backend for NativeBackend {
+    fn g1_sum(&self, input: &[u8]) -> Vec<u8> {
+        crate::native::alt_bn128_g1_sum(input)
+    }
+
+    fn g1_multiexp(&self, input: &[u8]) -> Vec<u8> {
+        crate::native::alt_bn128_g1_multiexp(input)
+    }
+
+    fn pairing_check(&self, input: &[u8]) -> bool {
+        crate::native::alt_bn128_pairing_check(input)
+    }
+
+    fn keccak256(&self, input: &[u8]) -> [u8; 32] {
+        // Outside the runtime near-sdk hashes in pure Rust
+        env::keccak256_array(input)
+    }
+
+    fn sha256(&self, input: &[u8]) -> [u8; 32] {
+        env::sha256_array(input)
+    }
+}
+
+/// Backend used when none is specified
+#[cfg(not(feature = "standalone"))]
+pub type DefaultBackend = NearBackend;
+
+/// Backend used when none is specified
+#[cfg(feature = "standalone")]
+pub type DefaultBackend = NativeBackend;
+
+/// One call made through a [`RecordingBackend`], with its input payload
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BackendCall {
+    G1Sum(Vec<u8>),
+    G1Multiexp(Vec<u8>),
+    PairingCheck(Vec<u8>),
+    Keccak256(Vec<u8>),
+    Sha256(Vec<u8>),
+}
+
+/// Backend that forwards to `inner` and records every call
+#[derive(Clone, Debug, Default)]
+pub struct RecordingBackend<B = DefaultBackend> {
+    inner: B,
+    calls: RefCell<Vec<BackendCall>>,
+}
+
+impl<B: Bn254Backend> RecordingBackend<B> {
+    pub fn new(inner: B) -> Self {
+        Self { inner, calls: RefCell::new(Vec::new()) }
+    }
+
+    /// Calls recorded so far, in order
+    pub fn calls(&self) -> Vec<BackendCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Return the recorded calls and start a fresh log
+    pub fn take_calls(&self) -> Vec<BackendCall> {
+        self.calls.take()
+    }
+
+    fn record(&self, call: BackendCall) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+impl<B: Bn254Backend> Bn254Backend for RecordingBackend<B> {
+    fn g1_sum(&self, input: &[u8]) -> Vec<u8> {
+        self.record(BackendCall::G1Sum(input.to_vec()));
+        self.inner.g1_sum(input)
+    }
+
+    fn g1_multiexp(&self, input: &[u8]) -> Vec<u8> {
+        self.record(BackendCall::G1Multiexp(input.to_vec()));
+        self.inner.g1_multiexp(input)
+    }
+
+    fn pairing_check(&self, input: &[u8]) -> bool {
+        self.record(BackendCall::PairingCheck(input.to_vec()));
+        self.inner.pairing_check(input)
+    }
+
+    fn keccak256(&self, input: &[u8]) -> [u8; 32] {
+        self.record(BackendCall::Keccak256(input.to_vec()));
+        self.inner.keccak256(input)
+    }
+
+    fn sha256(&self, input: &[u8]) -> [u8; 32] {
+        self.record(BackendCall::Sha256(input.to_vec()));
+        self.inner.sha256(input)
+    }
+}
+
+/// Block randomness for batch weights; empty off-chain (`standalone`)
+#[cfg(not(feature = "standalone"))]
+pub(crate) fn random_seed() -> Vec<u8> {
+    env::random_seed()
+}
+
+/// Block randomness for batch weights; empty off-chain (`standalone`)
+#[cfg(feature = "standalone")]
+pub(crate) fn random_seed() -> Vec<u8> {
+    crate::native::random_seed()
+}
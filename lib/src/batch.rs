@@ -0,0 +1,202 @@
+//! Batch verification of Groth16 proofs sharing one verification key
+//!
+//! n proofs normally cost n separate 4-pair pairing checks. With random
+//! weights `w_i` they can be folded into one check of n + 3 pairs:
+//!
+//! ```text
+//! Π e(-w_i·A_i, B_i) · e(W·α, β) · e(Σ w_i·vk_x_i, γ) · e(Σ w_i·C_i, δ) = 1
+//! ```
+//!
+//! where `W = Σ w_i`. Because vk_x is linear in the inputs, `Σ w_i·vk_x_i` is a
+//! single multiexp over the IC points with scalars `W` and `Σ_i w_i·x_ij`.
+//!
+//! The weights are 128-bit values derived from `env::random_seed()` and a
+//! keccak256 hash of every proof and input in the batch, so they are fixed
+//! only after the whole batch is known. An invalid proof passes the combined
+//! check with probability about 2^-128. Off-chain (`standalone`) there is no
+//! block seed and the weights come from the batch hash alone.
+//!
+//! If the combined check fails, every proof is verified individually so the
+//! caller learns exactly which ones are valid.
+
+use crate::error::VerifyError;
+use crate::backend::{self, Bn254Backend};
+use crate::poseidon::Fr;
+use crate::types::{negate_g1, G1Point, Proof, U256};
+use crate::verifier::Verifier;
+
+/// Outcome of [`Verifier::verify_batch`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchResult {
+    /// Every proof is valid (the combined pairing check passed)
+    AllValid,
+    /// At least one proof is invalid; one result per proof, in input order
+    Failed(Vec<Result<(), VerifyError>>),
+}
+
+impl BatchResult {
+    /// `true` if every proof in the batch is valid
+    pub fn is_valid(&self) -> bool {
+        matches!(self, BatchResult::AllValid)
+    }
+
+    /// Indices of the proofs that verified (all of them for `AllValid`)
+    pub fn valid_indices(&self, batch_len: usize) -> Vec<usize> {
+        match self {
+            BatchResult::AllValid => (0..batch_len).collect(),
+            BatchResult::Failed(results) => results
+                .iter()
+                .enumerate()
+                .filter(|(_, r)| r.is_ok())
+                .map(|(i, _)| i)
+                .collect(),
+        }
+    }
+}
+
+impl<B: Bn254Backend> Verifier<B> {
+    /// Verify many proofs under this verification key with one pairing check
+    ///
+    /// Malformed requests (wrong input count, non-canonical inputs, invalid
+    /// points) are rejected up front and excluded from the combined check.
+    /// The remaining proofs are folded into a single `n + 3` pair check; if
+    /// that fails, each one is re-verified on its own.
+    ///
+    /// **Gas**: one pairing of n + 3 pairs plus n + 3 multiexp calls, instead
+    /// of n pairings of 4 pairs each.
+    pub fn verify_batch(&self, batch: &[(Vec<U256>, Proof)]) -> BatchResult {
+        let mut results: Vec<Result<(), VerifyError>> = batch
+            .iter()
+            .map(|(inputs, proof)| self.check_request(inputs, proof))
+            .collect();
+
+        let well_formed: Vec<usize> = (0..batch.len()).filter(|&i| results[i].is_ok()).collect();
+
+        let combined_ok = well_formed.is_empty()
+            || matches!(self.combined_check(batch, &well_formed), Ok(true));
+
+        if combined_ok {
+            if results.iter().all(Result::is_ok) {
+                return BatchResult::AllValid;
+            }
+            return BatchResult::Failed(results);
+        }
+
+        // Fall back to one pairing per proof to find the invalid ones
+        for &i in &well_formed {
+            let (inputs, proof) = &batch[i];
+            results[i] = self.verify_well_formed(inputs, proof);
+        }
+        BatchResult::Failed(results)
+    }
+
+    /// Random-linear-combination pairing check over `batch[indices]`
+    fn combined_check(
+        &self,
+        batch: &[(Vec<U256>, Proof)],
+        indices: &[usize],
+    ) -> Result<bool, VerifyError> {
+        let weights = batch_weights(self.backend(), batch, indices);
+
+        // W = Σ w_i, and the IC scalars Σ_i w_i·x_ij
+        let mut total_weight = Fr::ZERO;
+        let mut ic_scalars = vec![Fr::ZERO; self.vk.num_inputs()];
+        for (&i, w) in indices.iter().zip(&weights) {
+            total_weight = total_weight.add(w);
+            for (scalar, x) in ic_scalars.iter_mut().zip(&batch[i].0) {
+                *scalar = scalar.add(&w.mul(&Fr { limbs: x.0 }));
+            }
+        }
+
+        let mut vk_x_terms = Vec::with_capacity(self.vk.ic.len());
+        vk_x_terms.push((&self.vk.ic[0], total_weight));
+        for (j, scalar) in ic_scalars.into_iter().enumerate() {
+            vk_x_terms.push((&self.vk.ic[j + 1], scalar));
+        }
+        let vk_x = multiexp(self.backend(), &vk_x_terms)?;
+
+        let alpha = multiexp(self.backend(), &[(&self.vk.alpha, total_weight)])?;
+
+        let c_terms: Vec<(&G1Point, Fr)> = indices
+            .iter()
+            .zip(&weights)
+            .map(|(&i, w)| (&batch[i].1.c, *w))
+            .collect();
+        let c_sum = multiexp(self.backend(), &c_terms)?;
+
+        let mut pairing_input = Vec::with_capacity((indices.len() + 3) * 192);
+        for (&i, w) in indices.iter().zip(&weights) {
+            let proof = &batch[i].1;
+            let neg_a = negate_g1(&proof.a);
+            let scaled_neg_a = multiexp(self.backend(), &[(&neg_a, *w)])?;
+            pairing_input.extend_from_slice(&scaled_neg_a.to_precompile_bytes());
+            pairing_input.extend_from_slice(&proof.b.to_precompile_bytes());
+        }
+        pairing_input.extend_from_slice(&alpha.to_precompile_bytes());
+        pairing_input.extend_from_slice(&self.vk.beta.to_precompile_bytes());
+        pairing_input.extend_from_slice(&vk_x.to_precompile_bytes());
+        pairing_input.extend_from_slice(&self.vk.gamma.to_precompile_bytes());
+        pairing_input.extend_from_slice(&c_sum.to_precompile_bytes());
+        pairing_input.extend_from_slice(&self.vk.delta.to_precompile_bytes());
+
+        Ok(self.backend().pairing_check(&pairing_input))
+    }
+}
+
+/// Derive one nonzero 128-bit weight per proof in `batch[indices]`
+///
+/// seed = keccak256(random_seed || A || B || C || inputs ...)
+/// w_i  = low 128 bits of keccak256(seed || i)
+fn batch_weights<B: Bn254Backend>(
+    backend: &B,
+    batch: &[(Vec<U256>, Proof)],
+    indices: &[usize],
+) -> Vec<Fr> {
+    let mut transcript = backend::random_seed();
+    for &i in indices {
+        let (inputs, proof) = &batch[i];
+        transcript.extend_from_slice(&proof.a.to_precompile_bytes());
+        transcript.extend_from_slice(&proof.b.to_precompile_bytes());
+        transcript.extend_from_slice(&proof.c.to_precompile_bytes());
+        for x in inputs {
+            transcript.extend_from_slice(&x.to_le_bytes());
+        }
+    }
+    let seed = backend.keccak256(&transcript);
+
+    (0..indices.len() as u32)
+        .map(|i| {
+            let mut buf = [0u8; 36];
+            buf[..32].copy_from_slice(&seed);
+            buf[32..].copy_from_slice(&i.to_le_bytes());
+            let h = backend.keccak256(&buf);
+            let lo = u64::from_le_bytes(h[..8].try_into().unwrap());
+            let hi = u64::from_le_bytes(h[8..16].try_into().unwrap());
+            // A zero weight would drop the proof from the check entirely
+            Fr { limbs: [lo.max(1), hi, 0, 0] }
+        })
+        .collect()
+}
+
+/// `Σ scalar·point` via alt_bn128_g1_multiexp, skipping zero scalars
+pub(crate) fn multiexp<B: Bn254Backend>(
+    backend: &B,
+    terms: &[(&G1Point, Fr)],
+) -> Result<G1Point, VerifyError> {
+    let mut input = Vec::with_capacity(terms.len() * 96);
+    for (point, scalar) in terms {
+        if scalar.is_zero() || point.is_zero() {
+            continue;
+        }
+        input.extend_from_slice(&point.to_precompile_bytes());
+        input.extend_from_slice(&U256(scalar.limbs).to_le_bytes());
+    }
+    if input.is_empty() {
+        return Ok(G1Point::zero());
+    }
+    let result: [u8; 64] = backend
+        .g1_multiexp(&input)
+        .try_into()
+        .map_err(|_| VerifyError::MultiexpFailed)?;
+    Ok(G1Point::from_precompile_bytes(&result))
+}
@@ -0,0 +1,622 @@
+//! Groth16 with gnark's Pedersen commitment extension (BSB22)
+//!
+//! gnark circuits that call `api.Commit` produce Groth16 proofs carrying
+//! extra Pedersen commitments `D_i` and one proof of knowledge, and keys
+//! carrying a Pedersen verifying key. Each commitment becomes an extra
+//! public input, so verification follows gnark's `groth16.Verify`:
+//!
+//! 1. For every commitment, hash `D_i ‖ committed public inputs` to a scalar
+//!    `h_i` (RFC 9380 hash-to-field, SHA-256, DST `bsb22-commitment`) and
+//!    append it to the public inputs
+//! 2. Check the proof of knowledge on the folded commitment
+//!    `D = Σ rⁱ·D_i` (r hashed from every `h_i`, DST `G16-BSB22`; `D = D_0`
+//!    for a single commitment):
+//!
+//! ```text
+//! e(D, G) · e(PoK, G^{-1/σ}) = 1
+//! ```
+//!
+//! 3. Run the usual Groth16 check with `vk_x = IC[0] + Σ xᵢ·IC[i+1] + Σ D_i`
+//!
+//! Folding several commitments follows gnark v0.10 and later, where all
+//! commitments share one Pedersen key. A single commitment is checked
+//! directly, without the folding challenge; that challenge (and its
+//! `G16-BSB22` tag) has not been checked against a proof gnark produced,
+//! so test circuits with several `api.Commit` calls before relying on it.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::backend::{Bn254Backend, DefaultBackend};
+use crate::batch::multiexp;
+use crate::error::{VerifyError, VkError};
+use crate::poseidon::Fr;
+use crate::types::{G1Point, G2Point, Proof, VerificationKey, U256, FR_MODULUS};
+use crate::verifier::PAIRING_INPUT_LEN;
+
+/// Domain separation tag of the commitment-to-input hash
+pub const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+
+/// Domain separation tag of the commitment folding challenge
+pub const FOLDING_DST: &[u8] = b"G16-BSB22";
+
+/// Pedersen verifying key (gnark `pedersen.VerifyingKey`)
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PedersenVerifyingKey {
+    /// G (G2)
+    pub g: G2Point,
+    /// G^{-1/σ} (G2)
+    pub g_sigma_neg: G2Point,
+}
+
+/// Groth16 verification key with commitment wires
+///
+/// `vk.ic` holds one point per public input followed by one per commitment.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommitmentVerificationKey {
+    /// The plain Groth16 part of the key
+    pub vk: VerificationKey,
+    /// Key for the commitment proof of knowledge
+    pub commitment_key: PedersenVerifyingKey,
+    /// For each commitment, the 1-based wires it commits to (gnark
+    /// `PublicAndCommitmentCommitted`): public inputs, then earlier commitments
+    pub public_and_commitment_committed: Vec<Vec<u32>>,
+}
+
+impl CommitmentVerificationKey {
+    /// Number of commitments a proof must carry
+    pub fn num_commitments(&self) -> usize {
+        self.public_and_commitment_committed.len()
+    }
+
+    /// Number of public inputs this verification key expects, excluding
+    /// the commitment wires
+    pub fn num_inputs(&self) -> usize {
+        self.vk.num_inputs().saturating_sub(self.num_commitments())
+    }
+
+    /// Check every point of the key and the committed wire indices
+    ///
+    /// Commitment `i` may commit to any public input and to commitments
+    /// before it.
+    pub fn validate(&self) -> Result<(), VkError> {
+        self.vk.validate()?;
+        let invalid = |element| move |reason| VkError::InvalidPoint { element, reason };
+        self.commitment_key.g.validate().map_err(invalid("commitment_key.g"))?;
+        self.commitment_key
+            .g_sigma_neg
+            .validate()
+            .map_err(invalid("commitment_key.g_sigma_neg"))?;
+
+        let commitments = self.num_commitments();
+        if self.vk.num_inputs() < commitments {
+            return Err(VkError::IcTooShort { commitments });
+        }
+        for (commitment, wires) in self.public_and_commitment_committed.iter().enumerate() {
+            let available = self.num_inputs() + commitment;
+            if let Some(&index) = wires.iter().find(|&&w| w == 0 || w as usize > available) {
+                return Err(VkError::InvalidCommittedWire { commitment, index });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Groth16 proof with Pedersen commitments
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommitmentProof {
+    /// A, B, C
+    pub proof: Proof,
+    /// One commitment per commitment wire (G1)
+    pub commitments: Vec<G1Point>,
+    /// Folded proof of knowledge of the committed values (G1)
+    pub commitment_pok: G1Point,
+}
+
+impl CommitmentProof {
+    /// Check every proof point before it reaches the host functions
+    pub fn validate(&self) -> Result<(), VerifyError> {
+        self.proof.validate()?;
+        for (index, point) in self.commitments.iter().enumerate() {
+            point
+                .validate()
+                .map_err(|reason| VerifyError::InvalidCommitment { index, reason })?;
+        }
+        self.commitment_pok
+            .validate()
+            .map_err(|reason| VerifyError::InvalidProofPoint { element: "CommitmentPok", reason })
+    }
+}
+
+/// Groth16 verifier for gnark proofs with Pedersen commitments
+///
+/// Stores the verification key; the backend is not part of the serialized
+/// state.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CommitmentVerifier<B = DefaultBackend> {
+    /// The verification key
+    pub vk: CommitmentVerificationKey,
+    #[borsh(skip)]
+    #[serde(skip)]
+    backend: B,
+}
+
+impl CommitmentVerifier {
+    /// Create a new verifier with the given verification key
+    ///
+    /// The key is not validated; prefer [`CommitmentVerifier::try_new`] for
+    /// keys that come from users.
+    pub fn new(vk: CommitmentVerificationKey) -> Self {
+        Self::with_backend(vk, DefaultBackend::default())
+    }
+
+    /// Create a verifier after checking every point of the key
+    pub fn try_new(vk: CommitmentVerificationKey) -> Result<Self, VkError> {
+        vk.validate()?;
+        Ok(Self::new(vk))
+    }
+}
+
+impl<B: Bn254Backend> CommitmentVerifier<B> {
+    /// Create a verifier that runs its curve operations on `backend`
+    ///
+    /// The key is not validated; call [`CommitmentVerificationKey::validate`]
+    /// first for keys that come from users.
+    pub fn with_backend(vk: CommitmentVerificationKey, backend: B) -> Self {
+        Self { vk, backend }
+    }
+
+    /// The backend this verifier calls
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Verify a proof with commitments
+    ///
+    /// Use [`CommitmentVerifier::verify_checked`] to learn why a proof was rejected.
+    pub fn verify(&self, inputs: &[U256], proof: &CommitmentProof) -> bool {
+        self.verify_checked(inputs, proof).is_ok()
+    }
+
+    /// Verify a proof with commitments, reporting the reason for any failure
+    ///
+    /// `inputs` are the circuit's public inputs only; the commitment wires
+    /// are derived from the proof.
+    pub fn verify_checked(&self, inputs: &[U256], proof: &CommitmentProof) -> Result<(), VerifyError> {
+        if inputs.len() != self.vk.num_inputs() {
+            return Err(VerifyError::InputCountMismatch {
+                expected: self.vk.num_inputs(),
+                found: inputs.len(),
+            });
+        }
+        if proof.commitments.len() != self.vk.num_commitments() {
+            return Err(VerifyError::CommitmentCountMismatch {
+                expected: self.vk.num_commitments(),
+                found: proof.commitments.len(),
+            });
+        }
+        if let Some(index) = inputs.iter().position(|x| *x >= FR_MODULUS) {
+            return Err(VerifyError::NonCanonicalInput { index });
+        }
+        proof.validate()?;
+
+        let wires = self.commitment_wires(inputs, &proof.commitments)?;
+        if !self.pok_check(&wires[inputs.len()..], proof)? {
+            return Err(VerifyError::CommitmentPokFailed);
+        }
+
+        // vk_x = IC[0] + Σ wire_i·IC[i+1] + Σ D_i
+        let vk = &self.vk.vk;
+        let mut terms = Vec::with_capacity(vk.ic.len() + proof.commitments.len());
+        terms.push((&vk.ic[0], Fr::ONE));
+        for (i, wire) in wires.iter().enumerate() {
+            let ic = vk.ic.get(i + 1).ok_or(VerifyError::MissingIcPoint { index: i + 1 })?;
+            terms.push((ic, *wire));
+        }
+        terms.extend(proof.commitments.iter().map(|d| (d, Fr::ONE)));
+        let vk_x = multiexp(&self.backend, &terms)?;
+
+        // e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1
+        let (neg_a, b, c) = proof.proof.to_pairing_bytes(true);
+        let mut input = [0u8; PAIRING_INPUT_LEN];
+        input[0..64].copy_from_slice(&neg_a);
+        input[64..192].copy_from_slice(&b);
+        input[192..256].copy_from_slice(&vk.alpha.to_precompile_bytes());
+        input[256..384].copy_from_slice(&vk.beta.to_precompile_bytes());
+        input[384..448].copy_from_slice(&vk_x.to_precompile_bytes());
+        input[448..576].copy_from_slice(&vk.gamma.to_precompile_bytes());
+        input[576..640].copy_from_slice(&c);
+        input[640..768].copy_from_slice(&vk.delta.to_precompile_bytes());
+
+        if self.backend.pairing_check(&input) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingFailed)
+        }
+    }
+
+    /// The public inputs followed by one hashed scalar per commitment
+    ///
+    /// `h_i = hash_to_field(D_i ‖ committed wires, "bsb22-commitment")`,
+    /// with every value serialized as 32 big-endian bytes.
+    fn commitment_wires(&self, inputs: &[U256], commitments: &[G1Point]) -> Result<Vec<Fr>, VerifyError> {
+        let mut wires: Vec<Fr> = inputs.iter().map(|x| Fr { limbs: x.0 }).collect();
+        for (commitment, (point, committed)) in commitments
+            .iter()
+            .zip(&self.vk.public_and_commitment_committed)
+            .enumerate()
+        {
+            let mut prehash = Vec::with_capacity(64 + committed.len() * 32);
+            prehash.extend_from_slice(&point.to_bytes());
+            for &index in committed {
+                let wire = (index as usize)
+                    .checked_sub(1)
+                    .and_then(|i| wires.get(i))
+                    .ok_or(VerifyError::InvalidVerificationKey(VkError::InvalidCommittedWire {
+                        commitment,
+                        index,
+                    }))?;
+                prehash.extend_from_slice(&U256(wire.limbs).to_be_bytes());
+            }
+            wires.push(hash_to_fr(&self.backend, &prehash, COMMITMENT_DST));
+        }
+        Ok(wires)
+    }
+
+    /// e(D, G) · e(PoK, G^{-1/σ}) = 1 on the folded commitment D
+    fn pok_check(&self, hashes: &[Fr], proof: &CommitmentProof) -> Result<bool, VerifyError> {
+        let folded = match proof.commitments.as_slice() {
+            [] => return Ok(true),
+            [single] => single.clone(),
+            commitments => {
+                let mut transcript = Vec::with_capacity(hashes.len() * 32);
+                for h in hashes {
+                    transcript.extend_from_slice(&U256(h.limbs).to_be_bytes());
+                }
+                let r = hash_to_fr(&self.backend, &transcript, FOLDING_DST);
+                let mut r_i = Fr::ONE;
+                let terms: Vec<(&G1Point, Fr)> = commitments
+                    .iter()
+                    .map(|d| {
+                        let term = (d, r_i);
+                        r_i = r_i.mul(&r);
+                        term
+                    })
+                    .collect();
+                multiexp(&self.backend, &terms)?
+            }
+        };
+
+        let key = &self.vk.commitment_key;
+        let mut input = [0u8; 2 * 192];
+        input[0..64].copy_from_slice(&folded.to_precompile_bytes());
+        input[64..192].copy_from_slice(&key.g.to_precompile_bytes());
+        input[192..256].copy_from_slice(&proof.commitment_pok.to_precompile_bytes());
+        input[256..384].copy_from_slice(&key.g_sigma_neg.to_precompile_bytes());
+        Ok(self.backend.pairing_check(&input))
+    }
+}
+
+/// RFC 9380 `hash_to_field` for one scalar: 48 bytes of
+/// `expand_message_xmd` (SHA-256) reduced mod r, as gnark-crypto's `fr.Hash`
+fn hash_to_fr<B: Bn254Backend>(backend: &B, msg: &[u8], dst: &[u8]) -> Fr {
+    let bytes = expand_message_xmd(backend, msg, dst, 48);
+    let base = Fr::from_u64(256);
+    bytes
+        .iter()
+        .fold(Fr::ZERO, |acc, b| acc.mul(&base).add(&Fr::from_u64(*b as u64)))
+}
+
+/// RFC 9380 `expand_message_xmd` with SHA-256; `len` at most 255·32 and
+/// `dst` at most 255 bytes
+fn expand_message_xmd<B: Bn254Backend>(backend: &B, msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    let ell = len.div_ceil(32);
+    let mut dst_prime = dst.to_vec();
+    dst_prime.push(dst.len() as u8);
+
+    // b_0 = H(Z_pad ‖ msg ‖ I2OSP(len, 2) ‖ 0 ‖ DST')
+    let mut input = vec![0u8; 64];
+    input.extend_from_slice(msg);
+    input.extend_from_slice(&(len as u16).to_be_bytes());
+    input.push(0);
+    input.extend_from_slice(&dst_prime);
+    let b0 = backend.sha256(&input);
+
+    // b_i = H((b_0 ⊕ b_{i-1}) ‖ I2OSP(i, 1) ‖ DST'), with b_0 ⊕ b_{-1} = b_0
+    let mut out = Vec::with_capacity(ell * 32);
+    let mut prev = [0u8; 32];
+    for i in 1..=ell {
+        let mut input = Vec::with_capacity(33 + dst_prime.len());
+        input.extend(b0.iter().zip(&prev).map(|(a, b)| a ^ b));
+        input.push(i as u8);
+        input.extend_from_slice(&dst_prime);
+        prev = backend.sha256(&input);
+        out.extend_from_slice(&prev);
+    }
+    out.truncate(len);
+    out
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::backend::NearBackend;
+    use crate::error::PointError;
+    use crate::groth16_tests::groth16_integration_tests::FIXTURE_VK;
+    use crate::types::{ProofJson, VerificationKeyJson};
+
+    // Built like the Groth16 fixture from known discrete logs, on top of
+    // FIXTURE_VK with IC extended by 88·G1 (and 111·G1 for the second
+    // commitment). Commitments D_0 = 13·G1, D_1 = 17·G1; the Pedersen key is
+    // G = 22·G2, G^{-1/σ} = -44·G2, so PoK = (Σ rⁱ·m_i / 2)·G1. D_0 commits
+    // to input 1, D_1 to input 2 and to the wire of D_0.
+
+    const IC_88: [&str; 2] = [
+        "9185496653949827395542415852257310150255649249011849327295920325446660237101",
+        "12548175832346631452607385519956685961219907043494535339456541319739689218477",
+    ];
+    const IC_111: [&str; 2] = [
+        "8589663905634214992096677787990202830015251035193477326128577119352292340107",
+        "14039385760219159291336441883937027388077520579639477854728647307916681889971",
+    ];
+    const D_13: [&str; 2] = [
+        "2672242651313367459976336264061690128665099451055893690004467838496751824703",
+        "18247534626997477790812670345925575171672701304065784723769023620148097699216",
+    ];
+    const D_17: [&str; 2] = [
+        "12852522211178622728088728121177131998585782282560100422041774753646305409836",
+        "15918672909255108529698304535345707578139606904951176064731093256171019744261",
+    ];
+    /// (PoK, C) for one and for two commitments
+    const PROOF_1: [[&str; 2]; 2] = [
+        [
+            "21584415574162399271279422778334872176478819401462405190610652303384839163325",
+            "20032032460099343473534856807624315100490300615003995574042432889823618120824",
+        ],
+        [
+            "14347320531580741502162381325533672040883989984311164331683446217962740442052",
+            "15198618369083161343332663860417653601158309786908270945268488770134287482952",
+        ],
+    ];
+    const PROOF_2: [[&str; 2]; 2] = [
+        [
+            "17865507903442725228064632530668146148028742895496971281261024408357138652606",
+            "11164608504589640525956911492801752635779169688165722278977748221540966152113",
+        ],
+        [
+            "21725321983787334127573509725455399991116904355455948346391044334815220566536",
+            "8766390642477364304001513903264915732146246308851523800239094609699522884",
+        ],
+    ];
+
+    fn point(coords: [&str; 2]) -> G1Point {
+        G1Point {
+            x: U256::from_dec_str(coords[0]).unwrap(),
+            y: U256::from_dec_str(coords[1]).unwrap(),
+        }
+    }
+
+    fn neg_g2(p: &G2Point) -> G2Point {
+        use crate::types::FQ_MODULUS;
+        G2Point { x: p.x, y: [FQ_MODULUS - p.y[0], FQ_MODULUS - p.y[1]] }
+    }
+
+    pub(crate) fn fixture(commitments: usize) -> (CommitmentVerifier, Vec<U256>, CommitmentProof) {
+        let vk_json: VerificationKeyJson = near_sdk::serde_json::from_str(FIXTURE_VK).unwrap();
+        let mut vk = vk_json.to_vk().unwrap();
+        let commitment_key = PedersenVerifyingKey { g: vk.beta.clone(), g_sigma_neg: neg_g2(&vk.delta) };
+        let (committed, points, [pok, c]) = match commitments {
+            1 => {
+                vk.ic.push(point(IC_88));
+                (vec![vec![1]], vec![point(D_13)], PROOF_1)
+            }
+            _ => {
+                vk.ic.push(point(IC_88));
+                vk.ic.push(point(IC_111));
+                (vec![vec![1], vec![2, 3]], vec![point(D_13), point(D_17)], PROOF_2)
+            }
+        };
+        let vk = CommitmentVerificationKey {
+            vk,
+            commitment_key,
+            public_and_commitment_committed: committed,
+        };
+
+        let proof_json: ProofJson = near_sdk::serde_json::from_str(
+            crate::groth16_tests::groth16_integration_tests::FIXTURE_PROOF,
+        )
+        .unwrap();
+        let mut proof = proof_json.to_proof().unwrap();
+        proof.c = point(c);
+        let proof = CommitmentProof { proof, commitments: points, commitment_pok: point(pok) };
+
+        let inputs = vec![U256::from(123456789u64), U256::from(987654321u64)];
+        (CommitmentVerifier::try_new(vk).unwrap(), inputs, proof)
+    }
+
+    // End-to-end fixture in gnark v0.10's `WriteTo` encoding (compressed
+    // points). gnark was not available offline, so it comes from a standalone
+    // re-implementation of gnark's groth16 Setup/Prove with a throwaway
+    // trapdoor. The circuit has public x and private a, b, r, s:
+    //
+    //     a * b == x
+    //     h * a == r                      h = api.Commit(a, x)
+    //     (r + b + 1) * (r + b + 1) == s
+    //
+    // with a = 3, b = 5, x = 15, so D commits to a and h hashes D and x.
+    // scripts/fixtures/gnark runs gnark v0.10 itself on a circuit with the
+    // same public input and commitment and prints its key and proof in this
+    // encoding; they replace GNARK_VK and GNARK_PROOF (and the `h` pinned
+    // below) as is.
+
+    const GNARK_VK: &str = concat!(
+        // [α]₁, [β]₁, [β]₂, [γ]₂, [δ]₁, [δ]₂
+        "9f2b5b8627023455c4b5d45ca32178d0905438fee6bdc7ab382c6529512c22fb",
+        "c86c156756212de90be70c7745ddc1c82701e1e6d1dce013a10952a655483aaf",
+        "cf39db3ad852f0d882b7aa6284e49667215453807f7a2af45f1ee0f7fc9c7d44",
+        "16bbeed6222bebfde3b864e3a88cd1b25697594e2bab376fa8cdf5e1747c7a93",
+        "97b8dbc09fcf568c3d9316874b93e4c32f8222a129ac128bfa7aaa6ee9bdd0ff",
+        "05a414cc36f0535533643b301b1cc0556fa0ed9b67cc0c657e37f4ec685a1b2f",
+        "ed6e50a89f8d4d46dc9051d257bc769088c341db5f4ff4f5bb0f0b19832ec384",
+        "ca78e72b394cf39efb0226dd98c8d29326f94479694c23b6cba39894d0f84bb0",
+        "2579e44347b0a01f18f7620d81b191ced765276475c550edc3969571eec0ceb3",
+        // K: one, x, commitment wire
+        "00000003",
+        "912e180620de30fd5f855afac50418c835568578680abba9822614449e54ef72",
+        "9772adb2380560f8f605f1150aeb79cdf4e80e92a66d7f8bfbb4f8ec84f7cf23",
+        "ae7b478a31a3f0fd4785799e2feab83b73e37f1d255d8606e121f606bf78cdaa",
+        // PublicAndCommitmentCommitted = [[1]]
+        "00000001000000010000000000000001",
+        // CommitmentKeys: [G, G^{-1/σ}]
+        "00000001",
+        "d62de15eff9cbd462f56b8f7a1510613878b932fec15dfe5b5462714af57fa9f",
+        "10e172831c2f9ee290582786211ea33d70b0f49be55e0f729eadda0bb5b87968",
+        "c12a20597859f96e8d8201e16f8c710b4ab456113b081dc804f8815c6dc13330",
+        "1a062dc348ac563418b76c91dbd4620bab3937389e83bc5a00a5ecdfd9ed9511",
+    );
+    const GNARK_PROOF: &str = concat!(
+        // Ar, Bs, Krs
+        "cf4163351466eef5d0e6f1c43a993c30cf1ec262aea8cacabaebf96c4265d36f",
+        "9cfa3b8da6476c550b56362014fb7f85b23439abec14e495ca02ea118cb2c2ad",
+        "0ea572d9e7dcda89728e7add9391ce43878a2d101a942970e13aa4c63b7170b3",
+        "965e0f81a3958f7820873116523e9ea76f6b8601ecc6d223a8e836785078a47a",
+        // Commitments, CommitmentPok
+        "00000001",
+        "db11389db2908be88ccf763a0e3298049f10aa8b5c754c5db1577f09abb9014b",
+        "c3bee1e656c7077bb2c6d8678a5f0181a39451432ca0b9a71770f12b5c18129e",
+    );
+
+    fn gnark_fixture() -> (CommitmentVerifier, CommitmentProof) {
+        let vk = crate::gnark::commitment_vk_from_bytes(&hex::decode(GNARK_VK).unwrap()).unwrap();
+        let proof = crate::gnark::commitment_proof_from_bytes(&hex::decode(GNARK_PROOF).unwrap()).unwrap();
+        (CommitmentVerifier::try_new(vk).unwrap(), proof)
+    }
+
+    #[test]
+    fn test_gnark_proof_verifies() {
+        let (verifier, proof) = gnark_fixture();
+        assert_eq!(verifier.vk.num_inputs(), 1);
+        assert_eq!(verifier.vk.public_and_commitment_committed, vec![vec![1]]);
+        assert_eq!(verifier.verify_checked(&[U256::from(15u64)], &proof), Ok(()));
+
+        let wires = verifier.commitment_wires(&[U256::from(15u64)], &proof.commitments).unwrap();
+        assert_eq!(
+            wires[1].to_string(),
+            "15344505878843018931464121001196230705529502698222082724000354433431779808985"
+        );
+    }
+
+    #[test]
+    fn test_gnark_proof_changed_input_rejected() {
+        let (verifier, proof) = gnark_fixture();
+        assert_eq!(verifier.verify_checked(&[U256::from(16u64)], &proof), Err(VerifyError::PairingFailed));
+
+        let mut swapped = proof.clone();
+        swapped.commitment_pok = proof.commitments[0].clone();
+        assert_eq!(verifier.verify_checked(&[U256::from(15u64)], &swapped), Err(VerifyError::CommitmentPokFailed));
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380 K.1
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            hex::encode(expand_message_xmd(&NearBackend, b"", dst, 32)),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            hex::encode(expand_message_xmd(&NearBackend, b"abc", dst, 32)),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+    }
+
+    #[test]
+    fn test_single_commitment_verifies() {
+        let (verifier, inputs, proof) = fixture(1);
+        assert_eq!(verifier.vk.num_inputs(), 2);
+        assert_eq!(verifier.verify_checked(&inputs, &proof), Ok(()));
+
+        let wires = verifier.commitment_wires(&inputs, &proof.commitments).unwrap();
+        assert_eq!(
+            wires[2].to_string(),
+            "19473050239348234339359391950724897005754402298104454511590863436412255289187"
+        );
+
+        // The committed input is bound by the hash, the other one by IC
+        let mut wrong = inputs.clone();
+        wrong[0] += U256::one();
+        assert_eq!(verifier.verify_checked(&wrong, &proof), Err(VerifyError::PairingFailed));
+    }
+
+    #[test]
+    fn test_folded_commitments_verify() {
+        let (verifier, inputs, proof) = fixture(2);
+        assert_eq!(verifier.verify_checked(&inputs, &proof), Ok(()));
+
+        let mut swapped = proof.clone();
+        swapped.commitments.swap(0, 1);
+        assert_eq!(
+            verifier.verify_checked(&inputs, &swapped),
+            Err(VerifyError::CommitmentPokFailed)
+        );
+    }
+
+    #[test]
+    fn test_wrong_pok_rejected() {
+        let (verifier, inputs, mut proof) = fixture(1);
+        proof.commitment_pok = point(D_13);
+        assert_eq!(verifier.verify_checked(&inputs, &proof), Err(VerifyError::CommitmentPokFailed));
+    }
+
+    #[test]
+    fn test_malformed_requests_rejected() {
+        let (verifier, inputs, proof) = fixture(1);
+
+        let mut missing = proof.clone();
+        missing.commitments.clear();
+        assert_eq!(
+            verifier.verify_checked(&inputs, &missing),
+            Err(VerifyError::CommitmentCountMismatch { expected: 1, found: 0 })
+        );
+        assert_eq!(
+            verifier.verify_checked(&inputs[..1], &proof),
+            Err(VerifyError::InputCountMismatch { expected: 2, found: 1 })
+        );
+
+        let mut identity = proof.clone();
+        identity.commitments[0] = G1Point::zero();
+        assert_eq!(
+            verifier.verify_checked(&inputs, &identity),
+            Err(VerifyError::InvalidCommitment { index: 0, reason: PointError::Identity })
+        );
+    }
+
+    #[test]
+    fn test_vk_validation() {
+        let (verifier, _, _) = fixture(2);
+
+        let mut vk = verifier.vk.clone();
+        vk.public_and_commitment_committed[0] = vec![3];
+        assert_eq!(
+            vk.validate().unwrap_err(),
+            VkError::InvalidCommittedWire { commitment: 0, index: 3 }
+        );
+
+        let mut vk = verifier.vk.clone();
+        vk.public_and_commitment_committed[1] = vec![0];
+        assert_eq!(
+            vk.validate().unwrap_err(),
+            VkError::InvalidCommittedWire { commitment: 1, index: 0 }
+        );
+
+        let mut vk = verifier.vk.clone();
+        vk.public_and_commitment_committed.push(vec![]);
+        vk.public_and_commitment_committed.push(vec![]);
+        vk.public_and_commitment_committed.push(vec![]);
+        assert_eq!(vk.validate().unwrap_err(), VkError::IcTooShort { commitments: 5 });
+    }
+}
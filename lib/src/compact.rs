@@ -0,0 +1,223 @@
+//! Compact proof encoding: compressed points, 128 bytes per proof
+//!
+//! A snarkjs [`ProofJson`](crate::types::ProofJson) argument runs to about a
+//! kilobyte of decimal strings, parsed with `from_dec_str` on every call. A
+//! [`CompactProof`] carries the same proof in 128 bytes:
+//!
+//! ```text
+//! A.x (32) ‖ B.x.c1 (32) ‖ B.x.c0 (32) ‖ C.x (32)
+//! ```
+//!
+//! Coordinates are big-endian and G2 uses the EIP-197 order, as in
+//! [`G1Point::to_bytes`] / [`G2Point::to_bytes`]. Since `x < q < 2^254`, the
+//! two top bits of each point's first byte are free: bit 7 is set when y is
+//! the lexicographically largest of its two roots (`y > -y`), bit 6 is
+//! reserved and must be zero. The verifier recovers y with a square root in
+//! Fq or Fq2 ([`crate::curve::g1_decompress`]).
+//!
+//! The point at infinity has no compact encoding; it is never part of a
+//! valid proof.
+//!
+//! In JSON a compact proof is a base64 string; in Borsh it is the raw 128
+//! bytes.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::Base64VecU8;
+use near_sdk::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::curve::{g1_decompress, g1_y_is_largest, g2_decompress, g2_y_is_largest};
+use crate::error::{ParseError, PointError};
+use crate::types::{G1Point, G2Point, Proof, U256};
+
+/// Size of a [`CompactProof`] in bytes
+pub const COMPACT_PROOF_LEN: usize = 128;
+
+/// Set on the first byte when y is the lexicographically largest root
+const Y_LARGEST_FLAG: u8 = 1 << 7;
+/// Must be zero; keeps the encoding unique
+const RESERVED_FLAG: u8 = 1 << 6;
+
+impl G1Point {
+    /// Compress to 32 bytes: big-endian x with the sign of y in the top bit
+    pub fn to_compressed(&self) -> Result<[u8; 32], PointError> {
+        if self.is_zero() {
+            return Err(PointError::Identity);
+        }
+        let mut out = self.x.to_be_bytes();
+        if g1_y_is_largest(self) {
+            out[0] |= Y_LARGEST_FLAG;
+        }
+        Ok(out)
+    }
+
+    /// Decompress a point written by [`G1Point::to_compressed`]
+    pub fn from_compressed(bytes: &[u8; 32]) -> Result<Self, ParseError> {
+        let (x, y_largest) = read_x(bytes)?;
+        g1_decompress(&x, y_largest)
+            .map_err(|reason| ParseError::InvalidPoint { element: "G1", reason })
+    }
+}
+
+impl G2Point {
+    /// Compress to 64 bytes: big-endian `x.c1 ‖ x.c0` with the sign of y in the top bit
+    pub fn to_compressed(&self) -> Result<[u8; 64], PointError> {
+        if self.is_zero() {
+            return Err(PointError::Identity);
+        }
+        let mut out = [0u8; 64];
+        out[..32].copy_from_slice(&self.x[1].to_be_bytes());
+        out[32..].copy_from_slice(&self.x[0].to_be_bytes());
+        if g2_y_is_largest(self) {
+            out[0] |= Y_LARGEST_FLAG;
+        }
+        Ok(out)
+    }
+
+    /// Decompress a point written by [`G2Point::to_compressed`]
+    ///
+    /// Only checks that the point is on the twist; subgroup membership is
+    /// checked with the rest of the proof in [`Proof::validate`].
+    pub fn from_compressed(bytes: &[u8; 64]) -> Result<Self, ParseError> {
+        let (x1, y_largest) = read_x(bytes[..32].try_into().expect("32 bytes"))?;
+        let x0 = U256::from_be_bytes(&bytes[32..]);
+        g2_decompress(&[x0, x1], y_largest)
+            .map_err(|reason| ParseError::InvalidPoint { element: "G2", reason })
+    }
+}
+
+/// The x coordinate and sign flag of a compressed point
+fn read_x(bytes: &[u8; 32]) -> Result<(U256, bool), ParseError> {
+    if bytes[0] & RESERVED_FLAG != 0 {
+        return Err(ParseError::InvalidPointFlags);
+    }
+    let mut x = *bytes;
+    x[0] &= !Y_LARGEST_FLAG;
+    Ok((U256::from_be_bytes(&x), bytes[0] & Y_LARGEST_FLAG != 0))
+}
+
+/// Groth16 proof with compressed points (128 bytes)
+///
+/// Serializes to base64 in JSON and to the raw bytes in Borsh, so it can be
+/// passed straight to a contract method.
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct CompactProof(pub [u8; COMPACT_PROOF_LEN]);
+
+impl CompactProof {
+    /// Compress a proof; fails if any point is the identity
+    pub fn from_proof(proof: &Proof) -> Result<Self, PointError> {
+        let mut out = [0u8; COMPACT_PROOF_LEN];
+        out[0..32].copy_from_slice(&proof.a.to_compressed()?);
+        out[32..96].copy_from_slice(&proof.b.to_compressed()?);
+        out[96..128].copy_from_slice(&proof.c.to_compressed()?);
+        Ok(Self(out))
+    }
+
+    /// Decompress every point
+    ///
+    /// The points are on the curve but not otherwise validated; the
+    /// verifier does that in [`Proof::validate`].
+    pub fn to_proof(&self) -> Result<Proof, ParseError> {
+        let element = |name| move |e| match e {
+            ParseError::InvalidPoint { reason, .. } => ParseError::InvalidPoint { element: name, reason },
+            other => other,
+        };
+        Ok(Proof {
+            a: G1Point::from_compressed(self.0[0..32].try_into().expect("32 bytes"))
+                .map_err(element("A"))?,
+            b: G2Point::from_compressed(self.0[32..96].try_into().expect("64 bytes"))
+                .map_err(element("B"))?,
+            c: G1Point::from_compressed(self.0[96..128].try_into().expect("32 bytes"))
+                .map_err(element("C"))?,
+        })
+    }
+}
+
+impl Serialize for CompactProof {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Serialize::serialize(&Base64VecU8::from(self.0.to_vec()), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CompactProof {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bytes = <Base64VecU8 as Deserialize>::deserialize(deserializer)?.0;
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map(Self)
+            .map_err(|_| de::Error::invalid_length(len, &"128 bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+    use crate::types::FQ_MODULUS;
+
+    #[test]
+    fn test_point_compression_roundtrip() {
+        let g = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+        let bytes = g.to_compressed().unwrap();
+        assert_eq!(bytes[0], 0);
+        assert_eq!(G1Point::from_compressed(&bytes).unwrap().y, g.y);
+
+        let neg = G1Point { x: g.x, y: FQ_MODULUS - g.y };
+        let bytes = neg.to_compressed().unwrap();
+        assert_eq!(bytes[0], Y_LARGEST_FLAG);
+        assert_eq!(G1Point::from_compressed(&bytes).unwrap().y, neg.y);
+
+        assert_eq!(G1Point::zero().to_compressed().unwrap_err(), PointError::Identity);
+        assert_eq!(G2Point::zero().to_compressed().unwrap_err(), PointError::Identity);
+    }
+
+    #[test]
+    fn test_compact_proof_roundtrip() {
+        let (verifier, proof_json, inputs) = fixture();
+        let proof = proof_json.to_proof().unwrap();
+        let compact = CompactProof::from_proof(&proof).unwrap();
+
+        let decoded = compact.to_proof().unwrap();
+        assert_eq!(decoded.a.to_bytes(), proof.a.to_bytes());
+        assert_eq!(decoded.b.to_bytes(), proof.b.to_bytes());
+        assert_eq!(decoded.c.to_bytes(), proof.c.to_bytes());
+        assert_eq!(verifier.verify_compact_checked(&compact, &inputs), Ok(()));
+
+        // JSON is a base64 string, Borsh the raw bytes
+        let json = near_sdk::serde_json::to_string(&compact).unwrap();
+        assert_eq!(json.len(), 2 + 172);
+        assert_eq!(near_sdk::serde_json::from_str::<CompactProof>(&json).unwrap(), compact);
+        let borsh = near_sdk::borsh::to_vec(&compact).unwrap();
+        assert_eq!(borsh, compact.0.to_vec());
+        assert!(near_sdk::serde_json::from_str::<CompactProof>("\"AQID\"").is_err());
+    }
+
+    #[test]
+    fn test_malformed_compact_proof_rejected() {
+        let (verifier, proof_json, inputs) = fixture();
+        let compact = CompactProof::from_proof(&proof_json.to_proof().unwrap()).unwrap();
+
+        let mut reserved = compact.clone();
+        reserved.0[32] |= RESERVED_FLAG;
+        assert_eq!(reserved.to_proof().unwrap_err(), ParseError::InvalidPointFlags);
+
+        // x = 4 is not the abscissa of any G1 point
+        let mut off_curve = compact.clone();
+        off_curve.0[96..128].copy_from_slice(&U256::from(4u64).to_be_bytes());
+        assert_eq!(
+            verifier.verify_compact_checked(&off_curve, &inputs),
+            Err(crate::VerifyError::Parse(ParseError::InvalidPoint {
+                element: "C",
+                reason: PointError::NotOnCurve
+            }))
+        );
+
+        // Flipping the sign of A gives -A, which fails the pairing
+        let mut flipped = compact;
+        flipped.0[0] ^= Y_LARGEST_FLAG;
+        assert_eq!(
+            verifier.verify_compact_checked(&flipped, &inputs),
+            Err(crate::VerifyError::PairingFailed)
+        );
+    }
+}
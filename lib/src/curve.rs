@@ -0,0 +1,531 @@
+//! BN254 curve checks for G1 and G2 points
+//!
+//! - G1: `y² = x³ + 3` over Fq. The cofactor is 1, so every point on the
+//!   curve is in the prime-order subgroup.
+//! - G2: `y² = x³ + 3/(9 + u)` over Fq2 (the D-type sextic twist). The twist
+//!   has a large cofactor, so on-curve points must also be checked for
+//!   membership in the order-r subgroup.
+//!
+//! The identity is encoded as `(0, 0)` (as in the alt_bn128 host functions)
+//! and counts as on-curve and in the subgroup. Callers that must not accept
+//! the identity check for it separately.
+//!
+//! [`G1Jacobian`] provides the G1 group law used by the native backend
+//! ([`crate::native`]) for `g1_sum` and `g1_multiexp`.
+//!
+//! [`g1_decompress`] / [`g2_decompress`] recover a point from its x
+//! coordinate and the sign of y, for the compressed encodings of gnark and
+//! arkworks.
+
+use crate::error::PointError;
+use crate::fields::{Fq, Fq2};
+use crate::types::{G1Point, G2Point, U256};
+
+/// Twist coefficient b' = 3 / (9 + u), Montgomery form
+pub(crate) const TWIST_B: Fq2 = Fq2::new(
+    Fq::from_mont([
+        0x3bf938e377b802a8,
+        0x020b1b273633535d,
+        0x26b7edf049755260,
+        0x2514c6324384a86d,
+    ]),
+    Fq::from_mont([
+        0x38e7ecccd1dcff67,
+        0x65f0b37d93ce0d3e,
+        0xd749d0dd22ac00aa,
+        0x0141b9ce4a688d4d,
+    ]),
+);
+
+/// ξ^((q - 1) / 3), Montgomery form
+pub(crate) const TWIST_MUL_BY_Q_X: Fq2 = Fq2::new(
+    Fq::from_mont([
+        0xb5773b104563ab30,
+        0x347f91c8a9aa6454,
+        0x7a007127242e0991,
+        0x1956bcd8118214ec,
+    ]),
+    Fq::from_mont([
+        0x6e849f1ea0aa4757,
+        0xaa1c7b6d89f89141,
+        0xb6e713cdfae0ca3a,
+        0x26694fbb4e82ebc3,
+    ]),
+);
+
+/// ξ^((q - 1) / 2), Montgomery form
+pub(crate) const TWIST_MUL_BY_Q_Y: Fq2 = Fq2::new(
+    Fq::from_mont([
+        0xe4bbdd0c2936b629,
+        0xbb30f162e133bacb,
+        0x31a9d1b6f9645366,
+        0x253570bea500f8dd,
+    ]),
+    Fq::from_mont([
+        0xa1d77ce45ffe77c7,
+        0x07affd117826d1db,
+        0x6d16bd27bb7edc6b,
+        0x2c87200285defecc,
+    ]),
+);
+
+/// BN254 curve seed x
+const BN_X: u64 = 4965661367192848881;
+
+/// Check `y² = x³ + 3` with coordinates in `[0, q)`
+pub fn g1_is_on_curve(p: &G1Point) -> bool {
+    if p.is_zero() {
+        return true;
+    }
+    let (x, y) = match (Fq::from_u256(&p.x), Fq::from_u256(&p.y)) {
+        (Some(x), Some(y)) => (x, y),
+        _ => return false,
+    };
+    y.square() == x.square().mul(&x).add(&Fq::from_u64(3))
+}
+
+/// Affine G2 coordinates, or `None` if any component is `>= q`
+fn g2_affine(p: &G2Point) -> Option<(Fq2, Fq2)> {
+    Some((Fq2::from_u256(&p.x)?, Fq2::from_u256(&p.y)?))
+}
+
+/// Check `y² = x³ + b'` over Fq2 with every component in `[0, q)`
+pub fn g2_is_on_curve(p: &G2Point) -> bool {
+    if p.is_zero() {
+        return true;
+    }
+    match g2_affine(p) {
+        Some((x, y)) => y.square() == x.square().mul(&x).add(&TWIST_B),
+        None => false,
+    }
+}
+
+/// Check that an on-curve G2 point lies in the order-r subgroup
+///
+/// Uses the endomorphism test of El Housni, Guillevic and Piellard
+/// ("Co-factor clearing and subgroup membership testing on pairing-friendly
+/// curves"), as in gnark-crypto: `P` is in the subgroup iff
+/// `[x+1]P + ψ([x]P) + ψ²([x]P) = ψ³([2x]P)`, with ψ the untwist-Frobenius-twist
+/// map and x the 63-bit curve seed. This costs one 63-bit scalar multiplication
+/// instead of the 254-bit `[r]P`.
+///
+/// Returns `false` for points that are not on the curve.
+pub fn g2_is_in_subgroup(p: &G2Point) -> bool {
+    if !g2_is_on_curve(p) {
+        return false;
+    }
+    if p.is_zero() {
+        return true;
+    }
+    let (x, y) = match g2_affine(p) {
+        Some(xy) => xy,
+        None => return false,
+    };
+    let p = G2Jacobian { x, y, z: Fq2::one() };
+    let xp = G2Jacobian::mul_affine(&x, &y, &[BN_X, 0, 0, 0]);
+    let psi_xp = xp.psi();
+    let lhs = xp.add(&p).add(&psi_xp).add(&psi_xp.psi());
+    lhs.equals(&xp.double().psi().psi().psi())
+}
+
+/// Recover the G1 point with abscissa `x` from the sign of its y coordinate
+///
+/// `y_largest` selects the root that is lexicographically largest (see
+/// [`Fq::is_lexicographically_largest`]). Fails if `x >= q` or `x³ + 3` has
+/// no square root.
+pub fn g1_decompress(x: &U256, y_largest: bool) -> Result<G1Point, PointError> {
+    let fx = Fq::from_u256(x).ok_or(PointError::NotOnCurve)?;
+    let mut y = fx.square().mul(&fx).add(&Fq::from_u64(3)).sqrt().ok_or(PointError::NotOnCurve)?;
+    if y.is_lexicographically_largest() != y_largest {
+        y = y.neg();
+    }
+    Ok(G1Point { x: *x, y: y.to_u256() })
+}
+
+/// Recover the G2 point with abscissa `x` from the sign of its y coordinate
+///
+/// Only checks that the point is on the twist; subgroup membership is left to
+/// [`G2Point::validate`].
+pub fn g2_decompress(x: &[U256; 2], y_largest: bool) -> Result<G2Point, PointError> {
+    let fx = Fq2::from_u256(x).ok_or(PointError::NotOnCurve)?;
+    let mut y = fx.square().mul(&fx).add(&TWIST_B).sqrt().ok_or(PointError::NotOnCurve)?;
+    if y.is_lexicographically_largest() != y_largest {
+        y = y.neg();
+    }
+    Ok(G2Point { x: *x, y: y.to_u256() })
+}
+
+/// Whether the y coordinate of a G1 point is the lexicographically largest root
+///
+/// `false` for the identity and for coordinates `>= q`.
+pub fn g1_y_is_largest(p: &G1Point) -> bool {
+    Fq::from_u256(&p.y).is_some_and(|y| y.is_lexicographically_largest())
+}
+
+/// Whether the y coordinate of a G2 point is the lexicographically largest root
+pub fn g2_y_is_largest(p: &G2Point) -> bool {
+    Fq2::from_u256(&p.y).is_some_and(|y| y.is_lexicographically_largest())
+}
+
+/// G1 point in Jacobian coordinates: (X, Y, Z) ↦ (X/Z², Y/Z³)
+#[cfg(any(feature = "standalone", test))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct G1Jacobian {
+    x: Fq,
+    y: Fq,
+    z: Fq,
+}
+
+#[cfg(any(feature = "standalone", test))]
+impl G1Jacobian {
+    pub(crate) fn identity() -> Self {
+        Self { x: Fq::one(), y: Fq::one(), z: Fq::zero() }
+    }
+
+    /// Lift an affine point; `(0, 0)` maps to the identity
+    pub(crate) fn from_affine(x: &Fq, y: &Fq) -> Self {
+        if x.is_zero() && y.is_zero() {
+            return Self::identity();
+        }
+        Self { x: *x, y: *y, z: Fq::one() }
+    }
+
+    pub(crate) fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub(crate) fn neg(&self) -> Self {
+        Self { x: self.x, y: self.y.neg(), z: self.z }
+    }
+
+    /// Doubling for a = 0 (dbl-2009-l)
+    pub(crate) fn double(&self) -> Self {
+        if self.is_identity() {
+            return *self;
+        }
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let f = e.square();
+        let x3 = f.sub(&d.double());
+        let c8 = c.double().double().double();
+        let y3 = e.mul(&d.sub(&x3)).sub(&c8);
+        let z3 = self.y.mul(&self.z).double();
+        Self { x: x3, y: y3, z: z3 }
+    }
+
+    /// General addition (add-2007-bl)
+    pub(crate) fn add(&self, other: &Self) -> Self {
+        if self.is_identity() {
+            return *other;
+        }
+        if other.is_identity() {
+            return *self;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x.mul(&z2z2);
+        let u2 = other.x.mul(&z1z1);
+        let s1 = self.y.mul(&other.z).mul(&z2z2);
+        let s2 = other.y.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&u1);
+        let r = s2.sub(&s1).double();
+        if h.is_zero() {
+            return if r.is_zero() { self.double() } else { Self::identity() };
+        }
+        let i = h.double().square();
+        let j = h.mul(&i);
+        let v = u1.mul(&i);
+        let x3 = r.square().sub(&j).sub(&v.double());
+        let y3 = r.mul(&v.sub(&x3)).sub(&s1.mul(&j).double());
+        let z3 = self.z.add(&other.z).square().sub(&z1z1).sub(&z2z2).mul(&h);
+        Self { x: x3, y: y3, z: z3 }
+    }
+
+    /// Double-and-add scalar multiplication
+    pub(crate) fn mul(&self, scalar: &[u64; 4]) -> Self {
+        let mut acc = Self::identity();
+        for limb in scalar.iter().rev() {
+            for bit in (0..64).rev() {
+                acc = acc.double();
+                if (limb >> bit) & 1 == 1 {
+                    acc = acc.add(self);
+                }
+            }
+        }
+        acc
+    }
+
+    /// Affine coordinates, with the identity as `(0, 0)`
+    pub(crate) fn to_affine(self) -> (Fq, Fq) {
+        match self.z.inverse() {
+            Some(z_inv) => {
+                let z_inv2 = z_inv.square();
+                (self.x.mul(&z_inv2), self.y.mul(&z_inv2).mul(&z_inv))
+            }
+            None => (Fq::zero(), Fq::zero()),
+        }
+    }
+}
+
+/// G2 point in Jacobian coordinates: (X, Y, Z) ↦ (X/Z², Y/Z³)
+#[derive(Clone, Copy, Debug)]
+struct G2Jacobian {
+    x: Fq2,
+    y: Fq2,
+    z: Fq2,
+}
+
+impl G2Jacobian {
+    fn identity() -> Self {
+        Self { x: Fq2::one(), y: Fq2::one(), z: Fq2::zero() }
+    }
+
+    fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// Doubling for a = 0 (dbl-2009-l)
+    fn double(&self) -> Self {
+        if self.is_identity() {
+            return *self;
+        }
+        let a = self.x.square();
+        let b = self.y.square();
+        let c = b.square();
+        let d = self.x.add(&b).square().sub(&a).sub(&c).double();
+        let e = a.double().add(&a);
+        let f = e.square();
+        let x3 = f.sub(&d.double());
+        let c8 = c.double().double().double();
+        let y3 = e.mul(&d.sub(&x3)).sub(&c8);
+        let z3 = self.y.mul(&self.z).double();
+        Self { x: x3, y: y3, z: z3 }
+    }
+
+    /// Mixed addition with an affine point (madd-2007-bl)
+    fn add_affine(&self, x2: &Fq2, y2: &Fq2) -> Self {
+        if self.is_identity() {
+            return Self { x: *x2, y: *y2, z: Fq2::one() };
+        }
+        let z1z1 = self.z.square();
+        let u2 = x2.mul(&z1z1);
+        let s2 = y2.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&self.x);
+        let r = s2.sub(&self.y).double();
+        if h.is_zero() {
+            return if r.is_zero() { self.double() } else { Self::identity() };
+        }
+        let hh = h.square();
+        let i = hh.double().double();
+        let j = h.mul(&i);
+        let v = self.x.mul(&i);
+        let x3 = r.square().sub(&j).sub(&v.double());
+        let y3 = r.mul(&v.sub(&x3)).sub(&self.y.mul(&j).double());
+        let z3 = self.z.add(&h).square().sub(&z1z1).sub(&hh);
+        Self { x: x3, y: y3, z: z3 }
+    }
+
+    /// General addition (add-2007-bl)
+    fn add(&self, other: &Self) -> Self {
+        if self.is_identity() {
+            return *other;
+        }
+        if other.is_identity() {
+            return *self;
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        let u1 = self.x.mul(&z2z2);
+        let u2 = other.x.mul(&z1z1);
+        let s1 = self.y.mul(&other.z).mul(&z2z2);
+        let s2 = other.y.mul(&self.z).mul(&z1z1);
+        let h = u2.sub(&u1);
+        let r = s2.sub(&s1).double();
+        if h.is_zero() {
+            return if r.is_zero() { self.double() } else { Self::identity() };
+        }
+        let i = h.double().square();
+        let j = h.mul(&i);
+        let v = u1.mul(&i);
+        let x3 = r.square().sub(&j).sub(&v.double());
+        let y3 = r.mul(&v.sub(&x3)).sub(&s1.mul(&j).double());
+        let z3 = self.z.add(&other.z).square().sub(&z1z1).sub(&z2z2).mul(&h);
+        Self { x: x3, y: y3, z: z3 }
+    }
+
+    /// ψ = twist ∘ Frobenius ∘ untwist; on the subgroup it acts as `[q]`
+    fn psi(&self) -> Self {
+        Self {
+            x: self.x.conjugate().mul(&TWIST_MUL_BY_Q_X),
+            y: self.y.conjugate().mul(&TWIST_MUL_BY_Q_Y),
+            z: self.z.conjugate(),
+        }
+    }
+
+    /// Equality of the affine points, without inversions
+    fn equals(&self, other: &Self) -> bool {
+        if self.is_identity() || other.is_identity() {
+            return self.is_identity() && other.is_identity();
+        }
+        let z1z1 = self.z.square();
+        let z2z2 = other.z.square();
+        self.x.mul(&z2z2) == other.x.mul(&z1z1)
+            && self.y.mul(&other.z).mul(&z2z2) == other.y.mul(&self.z).mul(&z1z1)
+    }
+
+    /// Double-and-add scalar multiplication of an affine point
+    fn mul_affine(x: &Fq2, y: &Fq2, scalar: &[u64; 4]) -> Self {
+        let mut acc = Self::identity();
+        for limb in scalar.iter().rev() {
+            for bit in (0..64).rev() {
+                acc = acc.double();
+                if (limb >> bit) & 1 == 1 {
+                    acc = acc.add_affine(x, y);
+                }
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FR_MODULUS, U256};
+
+    fn u(s: &str) -> U256 {
+        U256::from_dec_str(s).unwrap()
+    }
+
+    /// The standard G2 generator (snarkjs / EIP-197)
+    fn g2_generator() -> G2Point {
+        G2Point {
+            x: [
+                u("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+                u("11559732032986387107991004021392285783925812861821192530917403151452391805634"),
+            ],
+            y: [
+                u("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+                u("4082367875863433681332203403145435568316851327593401208105741076214120093531"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_g1_on_curve() {
+        assert!(g1_is_on_curve(&G1Point { x: U256::from(1u64), y: U256::from(2u64) }));
+        assert!(g1_is_on_curve(&G1Point::zero()));
+        assert!(!g1_is_on_curve(&G1Point { x: U256::from(1u64), y: U256::from(3u64) }));
+
+        // Same point with y + q is a different encoding the host would reject
+        let y = U256::from(2u64) + crate::types::FQ_MODULUS;
+        assert!(!g1_is_on_curve(&G1Point { x: U256::from(1u64), y }));
+    }
+
+    #[test]
+    fn test_g1_group_law() {
+        let g = G1Jacobian::from_affine(&Fq::from_u64(1), &Fq::from_u64(2));
+        let five = g.mul(&[5, 0, 0, 0]);
+        let sum = g.double().double().add(&g);
+        assert_eq!(five.to_affine(), sum.to_affine());
+
+        // P - P = O, and [r]G = O
+        assert!(five.add(&five.neg()).is_identity());
+        assert!(g.mul(&FR_MODULUS.0).is_identity());
+        assert_eq!(G1Jacobian::identity().to_affine(), (Fq::zero(), Fq::zero()));
+    }
+
+    #[test]
+    fn test_g2_generator_in_subgroup() {
+        let g = g2_generator();
+        assert!(g2_is_on_curve(&g));
+        assert!(g2_is_in_subgroup(&g));
+        assert!(g2_is_in_subgroup(&G2Point::zero()));
+
+        let mut bad = g.clone();
+        bad.y[1] += U256::one();
+        assert!(!g2_is_on_curve(&bad));
+        assert!(!g2_is_in_subgroup(&bad));
+    }
+
+    #[test]
+    fn test_decompress_recovers_points() {
+        let g1 = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+        assert!(!g1_y_is_largest(&g1));
+        let p = g1_decompress(&g1.x, false).unwrap();
+        assert_eq!((p.x, p.y), (g1.x, g1.y));
+        let neg = g1_decompress(&g1.x, true).unwrap();
+        assert_eq!(neg.y, crate::types::FQ_MODULUS - g1.y);
+
+        // x = 4: x³ + 3 = 67 is not a square mod q
+        assert_eq!(g1_decompress(&U256::from(4u64), false).unwrap_err(), PointError::NotOnCurve);
+        assert_eq!(
+            g1_decompress(&crate::types::FQ_MODULUS, false).unwrap_err(),
+            PointError::NotOnCurve
+        );
+
+        let g2 = g2_generator();
+        let p = g2_decompress(&g2.x, g2_y_is_largest(&g2)).unwrap();
+        assert_eq!((p.x, p.y), (g2.x, g2.y));
+        let neg = g2_decompress(&g2.x, !g2_y_is_largest(&g2)).unwrap();
+        assert!(g2_is_in_subgroup(&neg));
+        assert_ne!(neg.y, g2.y);
+    }
+
+    /// On the twist curve (x = 5 + u), but not in the order-r subgroup
+    fn g2_twist_point() -> G2Point {
+        G2Point {
+            x: [U256::from(5u64), U256::from(1u64)],
+            y: [
+                u("1408319067812452414633879940013592055621582375252427422757364816067320890688"),
+                u("16043447076329872375887584556683436513165016484529127304231559706947902103147"),
+            ],
+        }
+    }
+
+    fn jacobian(p: &G2Point) -> G2Jacobian {
+        let (x, y) = g2_affine(p).unwrap();
+        G2Jacobian { x, y, z: Fq2::one() }
+    }
+
+    fn affine(p: &G2Jacobian) -> G2Point {
+        let z_inv = p.z.inverse().unwrap();
+        let z_inv2 = z_inv.square();
+        G2Point { x: p.x.mul(&z_inv2).to_u256(), y: p.y.mul(&z_inv2).mul(&z_inv).to_u256() }
+    }
+
+    #[test]
+    fn test_g2_twist_point_outside_subgroup() {
+        let p = g2_twist_point();
+        assert!(g2_is_on_curve(&p));
+        assert!(!g2_is_in_subgroup(&p));
+    }
+
+    #[test]
+    fn test_g2_subgroup_check_agrees_with_r_multiplication() {
+        let g = jacobian(&g2_generator());
+        let t = jacobian(&g2_twist_point());
+        let (tx, ty) = g2_affine(&g2_twist_point()).unwrap();
+        let cases = [
+            (affine(&g.double().add(&g)), true),
+            (affine(&G2Jacobian::mul_affine(&g.x, &g.y, &[BN_X, 7, 0, 0])), true),
+            (g2_twist_point(), false),
+            (affine(&t.add(&g)), false),
+            // [r]T has order dividing the cofactor: still outside the subgroup
+            (affine(&G2Jacobian::mul_affine(&tx, &ty, &FR_MODULUS.0)), false),
+        ];
+        for (p, in_subgroup) in cases {
+            assert!(g2_is_on_curve(&p));
+            let (x, y) = g2_affine(&p).unwrap();
+            assert_eq!(G2Jacobian::mul_affine(&x, &y, &FR_MODULUS.0).is_identity(), in_subgroup);
+            assert_eq!(g2_is_in_subgroup(&p), in_subgroup);
+        }
+
+        // ψ acts as [q] on the subgroup: ψ(G) = [q mod r]G
+        let q_mod_r = crate::types::FQ_MODULUS - FR_MODULUS;
+        assert!(g.psi().equals(&G2Jacobian::mul_affine(&g.x, &g.y, &q_mod_r.0)));
+    }
+}
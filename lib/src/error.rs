@@ -0,0 +1,452 @@
+//! Error types for proof parsing and verification
+//!
+//! `ParseError` covers malformed snarkjs JSON (proofs and verification keys),
+//! binary encodings (gnark) and field element strings. `PointError` and `VkError` describe curve points
+//! that would make the alt_bn128 host functions abort.
+//! `VerifyError` covers everything that can make a verification fail, so that
+//! contracts can report a precise reason and relayers can tell a malformed
+//! request apart from a proof that simply does not verify.
+//! `RegistryError` adds the circuit lookups of a
+//! [`VerifierRegistry`](crate::registry::VerifierRegistry), `LayoutError`
+//! the init-time checks of a
+//! [`PublicInputLayout`](crate::layout::PublicInputLayout), and `MerkleError`
+//! the updates of an
+//! [`IncrementalMerkleTree`](crate::merkle::IncrementalMerkleTree) or a
+//! [`SparseMerkleTree`](crate::smt::SparseMerkleTree).
+
+use std::fmt;
+
+/// Error produced while converting snarkjs JSON or binary encodings into curve types
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// G1 point array has fewer than 2 coordinates
+    G1MissingCoordinates,
+    /// G2 point array has fewer than 2 coordinate pairs, or a pair has fewer than 2 elements
+    G2MissingCoordinates,
+    /// Coordinate is not a valid decimal string (names the coordinate, e.g. `"x0"`)
+    InvalidCoordinate(&'static str),
+    /// An entry of the verification key's IC array could not be parsed
+    InvalidIcPoint { index: usize },
+    /// Field element string is neither decimal nor `0x`-prefixed hex
+    InvalidFieldElement,
+    /// Field element is not below the BN254 scalar modulus r
+    NonCanonicalFieldElement,
+    /// Binary input ended in the middle of a value
+    UnexpectedEnd,
+    /// Binary input has `count` bytes left after the last value
+    TrailingBytes { count: usize },
+    /// The flag bits of an encoded point are not a valid encoding
+    InvalidPointFlags,
+    /// Decoded point `element` is invalid
+    InvalidPoint { element: &'static str, reason: PointError },
+    /// The gnark proof or key carries `count` Pedersen commitments, so it
+    /// must be decoded into the commitment types
+    UnexpectedCommitments { count: usize },
+    /// The gnark key's Pedersen verifying keys do not match its commitments
+    InvalidCommitmentKeys,
+    /// An integer in a binary encoding does not fit the target type
+    IntegerOverflow,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::G1MissingCoordinates => {
+                write!(f, "G1 point requires at least 2 coordinates")
+            }
+            ParseError::G2MissingCoordinates => {
+                write!(f, "G2 point requires 2 coordinate pairs of 2 elements")
+            }
+            ParseError::InvalidCoordinate(name) => {
+                write!(f, "invalid decimal string for {}", name)
+            }
+            ParseError::InvalidIcPoint { index } => write!(f, "invalid IC point at index {}", index),
+            ParseError::InvalidFieldElement => {
+                write!(f, "field element must be a decimal or 0x-prefixed hex string")
+            }
+            ParseError::NonCanonicalFieldElement => {
+                write!(f, "field element is not below the BN254 scalar modulus")
+            }
+            ParseError::UnexpectedEnd => write!(f, "input ended unexpectedly"),
+            ParseError::TrailingBytes { count } => {
+                write!(f, "{} unexpected bytes after the last value", count)
+            }
+            ParseError::InvalidPointFlags => write!(f, "invalid point encoding flags"),
+            ParseError::InvalidPoint { element, reason } => {
+                write!(f, "point {} is invalid: {}", element, reason)
+            }
+            ParseError::UnexpectedCommitments { count } => write!(
+                f,
+                "input carries {} Pedersen commitments; decode it with commitment support",
+                count
+            ),
+            ParseError::InvalidCommitmentKeys => {
+                write!(f, "Pedersen verifying keys do not match the commitments")
+            }
+            ParseError::IntegerOverflow => write!(f, "integer does not fit the target type"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why a curve point was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointError {
+    /// The point at infinity where a real point is required
+    Identity,
+    /// Not on the curve, or a coordinate is not below q
+    NotOnCurve,
+    /// On the G2 twist but outside the order-r subgroup
+    NotInSubgroup,
+}
+
+impl fmt::Display for PointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PointError::Identity => write!(f, "point at infinity"),
+            PointError::NotOnCurve => write!(f, "not on the curve"),
+            PointError::NotInSubgroup => write!(f, "not in the prime-order subgroup"),
+        }
+    }
+}
+
+impl std::error::Error for PointError {}
+
+/// Why a verification key failed [`VerificationKey::validate`](crate::types::VerificationKey::validate)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VkError {
+    /// IC is empty (it must contain at least IC[0])
+    EmptyIc,
+    /// alpha, beta, gamma or delta is invalid
+    InvalidPoint { element: &'static str, reason: PointError },
+    /// IC point at `index` is invalid
+    InvalidIcPoint { index: usize, reason: PointError },
+    /// PLONK domain: `power` is not in 1..=28, or `w` is not the matching root of unity
+    InvalidDomain { power: u32 },
+    /// PLONK scalar (k1, k2 or w) is not below r
+    NonCanonicalScalar { element: &'static str },
+    /// fflonk root of unity (w3, w4, w8 or wr) does not have the required order
+    InvalidRootOfUnity { element: &'static str },
+    /// IC has no room for the `commitments` commitment wires (gnark)
+    IcTooShort { commitments: usize },
+    /// Commitment `commitment` commits to wire `index`, which is not a public
+    /// input or an earlier commitment (gnark)
+    InvalidCommittedWire { commitment: usize, index: u32 },
+}
+
+impl fmt::Display for VkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VkError::EmptyIc => write!(f, "verification key has no IC points"),
+            VkError::InvalidPoint { element, reason } => {
+                write!(f, "verification key {} is invalid: {}", element, reason)
+            }
+            VkError::InvalidIcPoint { index, reason } => {
+                write!(f, "verification key IC[{}] is invalid: {}", index, reason)
+            }
+            VkError::InvalidDomain { power } => {
+                write!(f, "verification key domain of size 2^{} is not supported", power)
+            }
+            VkError::NonCanonicalScalar { element } => {
+                write!(f, "verification key {} is not below the BN254 scalar modulus", element)
+            }
+            VkError::InvalidRootOfUnity { element } => {
+                write!(f, "verification key {} is not a root of unity of the required order", element)
+            }
+            VkError::IcTooShort { commitments } => {
+                write!(f, "verification key IC has no room for {} commitment wires", commitments)
+            }
+            VkError::InvalidCommittedWire { commitment, index } => write!(
+                f,
+                "commitment {} commits to wire {}, which is not a public input or earlier commitment",
+                commitment, index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VkError {}
+
+/// Reason a Groth16 verification did not succeed
+///
+/// Only [`VerifyError::PairingFailed`] means "well-formed request, wrong proof".
+/// Every other variant means the request itself is malformed and resubmitting
+/// the same proof and inputs can never succeed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VerifyError {
+    /// The proof could not be parsed from JSON
+    Parse(ParseError),
+    /// The verification key failed validation
+    InvalidVerificationKey(VkError),
+    /// Proof point A, B or C is invalid
+    InvalidProofPoint { element: &'static str, reason: PointError },
+    /// Proof evaluation `element` (PLONK) is `>= r`
+    NonCanonicalProofScalar { element: &'static str },
+    /// Pedersen commitment at `index` (gnark) is invalid
+    InvalidCommitment { index: usize, reason: PointError },
+    /// Number of Pedersen commitments does not match the verification key
+    CommitmentCountMismatch { expected: usize, found: usize },
+    /// The commitment proof of knowledge does not verify
+    CommitmentPokFailed,
+    /// Public input at `index` is not a valid decimal or hex string
+    InvalidInput { index: usize },
+    /// Public input at `index` is `>= r`, i.e. not a canonical scalar
+    NonCanonicalInput { index: usize },
+    /// Number of public inputs does not match the verification key
+    InputCountMismatch { expected: usize, found: usize },
+    /// The verification key has no IC point at `index`
+    MissingIcPoint { index: usize },
+    /// `alt_bn128_g1_multiexp` returned a malformed result
+    MultiexpFailed,
+    /// `alt_bn128_g1_sum` returned a malformed result
+    G1SumFailed,
+    /// The pairing product is not the identity: the proof is invalid
+    PairingFailed,
+}
+
+impl VerifyError {
+    /// Short, stable identifier suitable for event payloads
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerifyError::Parse(_) => "parse_error",
+            VerifyError::InvalidVerificationKey(_) => "invalid_verification_key",
+            VerifyError::InvalidProofPoint { .. } => "invalid_proof_point",
+            VerifyError::NonCanonicalProofScalar { .. } => "non_canonical_proof_scalar",
+            VerifyError::InvalidCommitment { .. } => "invalid_commitment",
+            VerifyError::CommitmentCountMismatch { .. } => "commitment_count_mismatch",
+            VerifyError::CommitmentPokFailed => "commitment_pok_failed",
+            VerifyError::InvalidInput { .. } => "invalid_input",
+            VerifyError::NonCanonicalInput { .. } => "non_canonical_input",
+            VerifyError::InputCountMismatch { .. } => "input_count_mismatch",
+            VerifyError::MissingIcPoint { .. } => "missing_ic_point",
+            VerifyError::MultiexpFailed => "multiexp_failed",
+            VerifyError::G1SumFailed => "g1_sum_failed",
+            VerifyError::PairingFailed => "pairing_failed",
+        }
+    }
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Parse(e) => write!(f, "malformed proof: {}", e),
+            VerifyError::InvalidVerificationKey(e) => write!(f, "{}", e),
+            VerifyError::InvalidProofPoint { element, reason } => {
+                write!(f, "proof point {} is invalid: {}", element, reason)
+            }
+            VerifyError::NonCanonicalProofScalar { element } => {
+                write!(f, "proof scalar {} is not below the BN254 scalar modulus", element)
+            }
+            VerifyError::InvalidCommitment { index, reason } => {
+                write!(f, "commitment {} is invalid: {}", index, reason)
+            }
+            VerifyError::CommitmentCountMismatch { expected, found } => {
+                write!(f, "expected {} commitments, got {}", expected, found)
+            }
+            VerifyError::CommitmentPokFailed => {
+                write!(f, "commitment proof of knowledge check failed")
+            }
+            VerifyError::InvalidInput { index } => {
+                write!(f, "public input {} is not a valid decimal or hex string", index)
+            }
+            VerifyError::NonCanonicalInput { index } => {
+                write!(f, "public input {} is not below the BN254 scalar modulus", index)
+            }
+            VerifyError::InputCountMismatch { expected, found } => write!(
+                f,
+                "expected {} public inputs, got {}",
+                expected, found
+            ),
+            VerifyError::MissingIcPoint { index } => {
+                write!(f, "verification key has no IC point at index {}", index)
+            }
+            VerifyError::MultiexpFailed => write!(f, "alt_bn128_g1_multiexp returned a malformed point"),
+            VerifyError::G1SumFailed => write!(f, "alt_bn128_g1_sum returned a malformed point"),
+            VerifyError::PairingFailed => write!(f, "pairing check failed"),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+impl From<ParseError> for VerifyError {
+    fn from(e: ParseError) -> Self {
+        VerifyError::Parse(e)
+    }
+}
+
+impl From<VkError> for VerifyError {
+    fn from(e: VkError) -> Self {
+        VerifyError::InvalidVerificationKey(e)
+    }
+}
+
+/// Error from a [`VerifierRegistry`](crate::registry::VerifierRegistry) operation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// No circuit is registered under the requested id
+    UnknownCircuit,
+    /// The circuit was deprecated at `version` and no longer verifies proofs
+    CircuitDeprecated { version: u32 },
+    /// The JSON verification key being registered could not be parsed
+    MalformedKey(ParseError),
+    /// The verification key being registered failed validation
+    InvalidKey(VkError),
+    /// The circuit is active but the proof was rejected
+    Verify(VerifyError),
+}
+
+impl RegistryError {
+    /// Short, stable identifier suitable for event payloads
+    pub fn code(&self) -> &'static str {
+        match self {
+            RegistryError::UnknownCircuit => "unknown_circuit",
+            RegistryError::CircuitDeprecated { .. } => "circuit_deprecated",
+            RegistryError::MalformedKey(_) => "malformed_verification_key",
+            RegistryError::InvalidKey(_) => "invalid_verification_key",
+            RegistryError::Verify(e) => e.code(),
+        }
+    }
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownCircuit => write!(f, "unknown circuit"),
+            RegistryError::CircuitDeprecated { version } => {
+                write!(f, "circuit version {} is deprecated", version)
+            }
+            RegistryError::MalformedKey(e) => write!(f, "malformed verification key: {}", e),
+            RegistryError::InvalidKey(e) => write!(f, "{}", e),
+            RegistryError::Verify(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+impl From<VkError> for RegistryError {
+    fn from(e: VkError) -> Self {
+        RegistryError::InvalidKey(e)
+    }
+}
+
+impl From<VerifyError> for RegistryError {
+    fn from(e: VerifyError) -> Self {
+        RegistryError::Verify(e)
+    }
+}
+
+/// Why a [`PublicInputLayout`](crate::layout::PublicInputLayout) was rejected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    /// `role` is assigned to more than one input
+    DuplicateRole { role: String },
+    /// The contract needs `role`, but the layout does not name it
+    MissingRole { role: String },
+    /// The layout names `layout` inputs, the verification key has `vk`
+    InputCountMismatch { layout: usize, vk: usize },
+}
+
+impl LayoutError {
+    /// Short, stable identifier suitable for event payloads
+    pub fn code(&self) -> &'static str {
+        match self {
+            LayoutError::DuplicateRole { .. } => "duplicate_role",
+            LayoutError::MissingRole { .. } => "missing_role",
+            LayoutError::InputCountMismatch { .. } => "layout_input_count_mismatch",
+        }
+    }
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::DuplicateRole { role } => {
+                write!(f, "public input role {} is assigned more than once", role)
+            }
+            LayoutError::MissingRole { role } => write!(f, "layout has no {} input", role),
+            LayoutError::InputCountMismatch { layout, vk } => write!(
+                f,
+                "layout names {} public inputs but the verification key has {}",
+                layout, vk
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Why an [`IncrementalMerkleTree`](crate::merkle::IncrementalMerkleTree)
+/// could not be created or updated
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MerkleError {
+    /// Depth is 0 or above [`MAX_DEPTH`](crate::merkle::MAX_DEPTH)
+    InvalidDepth { depth: u8 },
+    /// The root history must keep at least one root
+    EmptyRootHistory,
+    /// All `capacity` leaves are in use
+    TreeFull { capacity: u64 },
+    /// Leaf is not below the BN254 scalar modulus r
+    NonCanonicalLeaf,
+    /// No leaf has been inserted at `index`
+    UnknownLeaf { index: u64 },
+    /// Imported leaf `index` is not a canonical decimal or hex field element
+    InvalidLeaf { index: usize },
+    /// Imported state declares `expected` leaves but lists `found`
+    LeafCountMismatch { expected: u64, found: usize },
+    /// The sparse tree already holds the key
+    KeyExists,
+    /// The sparse tree does not hold the key
+    KeyNotFound,
+    /// A sparse proof with `siblings` siblings needs more than `levels` levels
+    TooManySiblings { levels: usize, siblings: usize },
+}
+
+impl MerkleError {
+    /// Short, stable identifier suitable for event payloads
+    pub fn code(&self) -> &'static str {
+        match self {
+            MerkleError::InvalidDepth { .. } => "invalid_tree_depth",
+            MerkleError::EmptyRootHistory => "empty_root_history",
+            MerkleError::TreeFull { .. } => "tree_full",
+            MerkleError::NonCanonicalLeaf => "non_canonical_leaf",
+            MerkleError::UnknownLeaf { .. } => "unknown_leaf",
+            MerkleError::InvalidLeaf { .. } => "invalid_leaf",
+            MerkleError::LeafCountMismatch { .. } => "leaf_count_mismatch",
+            MerkleError::KeyExists => "key_exists",
+            MerkleError::KeyNotFound => "key_not_found",
+            MerkleError::TooManySiblings { .. } => "too_many_siblings",
+        }
+    }
+}
+
+impl fmt::Display for MerkleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MerkleError::InvalidDepth { depth } => write!(f, "invalid Merkle tree depth {}", depth),
+            MerkleError::EmptyRootHistory => write!(f, "root history size must be at least 1"),
+            MerkleError::TreeFull { capacity } => {
+                write!(f, "Merkle tree is full ({} leaves)", capacity)
+            }
+            MerkleError::NonCanonicalLeaf => {
+                write!(f, "leaf is not below the BN254 scalar modulus")
+            }
+            MerkleError::UnknownLeaf { index } => write!(f, "no leaf at index {}", index),
+            MerkleError::InvalidLeaf { index } => {
+                write!(f, "leaf {} is not a canonical field element", index)
+            }
+            MerkleError::LeafCountMismatch { expected, found } => {
+                write!(f, "state declares {} leaves but lists {}", expected, found)
+            }
+            MerkleError::KeyExists => write!(f, "key already in the tree"),
+            MerkleError::KeyNotFound => write!(f, "key not in the tree"),
+            MerkleError::TooManySiblings { levels, siblings } => {
+                write!(f, "{} siblings do not fit a {}-level proof", siblings, levels)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MerkleError {}
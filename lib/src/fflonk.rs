@@ -0,0 +1,935 @@
+//! fflonk verifier for snarkjs `fflonk` proofs
+//!
+//! fflonk packs the PLONK polynomials into three combined polynomials C0
+//! (selectors and permutation, part of the key), C1 (wires and T0) and C2
+//! (Z, T1 and T2), so a proof is four commitments and fifteen evaluations and
+//! a verification is one 2-pair pairing check. This module follows snarkjs'
+//! `fflonk_verify`:
+//!
+//! 1. Fiat-Shamir challenges β, γ, xi_seed, α, y from a keccak256 transcript
+//!    ([`FflonkChallenges::snarkjs`])
+//! 2. The opening sets S0 = {h0·w8ⁱ}, S1 = {h1·w4ⁱ} and
+//!    S2 = {h2·w3ⁱ} ∪ {h3·w3ⁱ}, where h0⁸ = h1⁴ = h2³ = ξ = xi_seed²⁴ and
+//!    h3³ = ξω
+//! 3. The remainders r0, r1, r2: the interpolations of C0, C1, C2 on S0, S1,
+//!    S2, evaluated at y
+//! 4. The KZG check of the batched opening W1 at y, with opening proof W2:
+//!
+//! ```text
+//! e(-(F - E - Z_T0(y)·W1 + y·W2), [1]₂) · e(W2, [x]₂) = 1
+//! F = C0 + α·Z_T0(y)/Z_T1(y)·C1 + α²·Z_T0(y)/Z_T2(y)·C2
+//! E = (r0 + α·Z_T0(y)/Z_T1(y)·r1 + α²·Z_T0(y)/Z_T2(y)·r2)·[1]₁
+//! ```
+//!
+//! The left point is a single `alt_bn128_g1_multiexp`, so a verification is
+//! one multiexp and one pairing check regardless of the circuit.
+//!
+//! snarkjs also puts an `inv` evaluation in the proof (a batched inverse
+//! that spares the Solidity verifier its inversions). It is not part of the
+//! transcript and is not needed here: the field inversions are computed
+//! directly, two per verification.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::backend::{Bn254Backend, DefaultBackend};
+use crate::batch::multiexp;
+use crate::error::{ParseError, PointError, VerifyError, VkError};
+use crate::plonk::{domain_generator, fr, lagrange_evaluations, G2_GENERATOR, MAX_POWER};
+use crate::poseidon::Fr;
+use crate::transcript::{FflonkChallenges, FiatShamirTranscript};
+use crate::types::{parse_field_element, G1Point, G2Point, U256, FR_MODULUS};
+use crate::verifier::parse_inputs;
+
+/// fflonk verification key
+///
+/// Compatible with the snarkjs `fflonk` verification_key.json.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FflonkVerificationKey {
+    /// Number of public inputs
+    pub n_public: u32,
+    /// log2 of the domain size n
+    pub power: u32,
+    /// Coset shift of the second wire column
+    pub k1: U256,
+    /// Coset shift of the third wire column
+    pub k2: U256,
+    /// Generator of the order-n evaluation domain
+    pub w: U256,
+    /// Primitive cube root of unity
+    pub w3: U256,
+    /// Primitive 4th root of unity
+    pub w4: U256,
+    /// Primitive 8th root of unity
+    pub w8: U256,
+    /// Cube root of `w`
+    pub wr: U256,
+    /// `[x]₂` from the powers of tau
+    pub x_2: G2Point,
+    /// Commitment to the combined selector and permutation polynomial
+    pub c0: G1Point,
+}
+
+impl FflonkVerificationKey {
+    /// Number of public inputs this verification key expects
+    pub fn num_inputs(&self) -> usize {
+        self.n_public as usize
+    }
+
+    /// Check every point and scalar of the key
+    ///
+    /// `C0` must be on G1, `X_2` must be a non-identity point in the G2
+    /// subgroup, `w` must generate the domain of size `2^power` and the
+    /// other roots must have the order their names promise.
+    pub fn validate(&self) -> Result<(), VkError> {
+        if !self.c0.is_on_curve() {
+            return Err(VkError::InvalidPoint { element: "C0", reason: PointError::NotOnCurve });
+        }
+        self.x_2
+            .validate()
+            .map_err(|reason| VkError::InvalidPoint { element: "X_2", reason })?;
+
+        for (element, scalar) in [
+            ("k1", &self.k1),
+            ("k2", &self.k2),
+            ("w", &self.w),
+            ("w3", &self.w3),
+            ("w4", &self.w4),
+            ("w8", &self.w8),
+            ("wr", &self.wr),
+        ] {
+            if *scalar >= FR_MODULUS {
+                return Err(VkError::NonCanonicalScalar { element });
+            }
+        }
+        let w = fr(&self.w);
+        if !(1..=MAX_POWER).contains(&self.power) || domain_generator(self.power) != w {
+            return Err(VkError::InvalidDomain { power: self.power });
+        }
+
+        let minus_one = Fr::ONE.neg();
+        let w3 = fr(&self.w3);
+        if w3 == Fr::ONE || w3.square().mul(&w3) != Fr::ONE {
+            return Err(VkError::InvalidRootOfUnity { element: "w3" });
+        }
+        if fr(&self.w4).square() != minus_one {
+            return Err(VkError::InvalidRootOfUnity { element: "w4" });
+        }
+        if fr(&self.w8).square().square() != minus_one {
+            return Err(VkError::InvalidRootOfUnity { element: "w8" });
+        }
+        let wr = fr(&self.wr);
+        if wr.square().mul(&wr) != w {
+            return Err(VkError::InvalidRootOfUnity { element: "wr" });
+        }
+        Ok(())
+    }
+}
+
+/// fflonk proof
+///
+/// Compatible with the snarkjs `fflonk` proof.json.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FflonkProof {
+    /// Commitment to the wires and T0
+    pub c1: G1Point,
+    /// Commitment to Z, T1 and T2
+    pub c2: G1Point,
+    /// Batched opening proof
+    pub w1: G1Point,
+    /// Opening proof of the batched opening at y
+    pub w2: G1Point,
+    /// Evaluations at ξ (and ξω for `eval_zw`, `eval_t1w`, `eval_t2w`)
+    pub eval_ql: U256,
+    pub eval_qr: U256,
+    pub eval_qm: U256,
+    pub eval_qo: U256,
+    pub eval_qc: U256,
+    pub eval_s1: U256,
+    pub eval_s2: U256,
+    pub eval_s3: U256,
+    pub eval_a: U256,
+    pub eval_b: U256,
+    pub eval_c: U256,
+    pub eval_z: U256,
+    pub eval_zw: U256,
+    pub eval_t1w: U256,
+    pub eval_t2w: U256,
+}
+
+impl FflonkProof {
+    /// Check every proof element before it reaches the host functions
+    ///
+    /// Commitments must be on G1 and evaluations must be canonical scalars.
+    pub fn validate(&self) -> Result<(), VerifyError> {
+        for (element, point) in [("C1", &self.c1), ("C2", &self.c2), ("W1", &self.w1), ("W2", &self.w2)] {
+            if !point.is_on_curve() {
+                return Err(VerifyError::InvalidProofPoint {
+                    element,
+                    reason: PointError::NotOnCurve,
+                });
+            }
+        }
+        for (element, scalar) in self.evaluations() {
+            if *scalar >= FR_MODULUS {
+                return Err(VerifyError::NonCanonicalProofScalar { element });
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluations in transcript order
+    fn evaluations(&self) -> [(&'static str, &U256); 15] {
+        [
+            ("ql", &self.eval_ql),
+            ("qr", &self.eval_qr),
+            ("qm", &self.eval_qm),
+            ("qo", &self.eval_qo),
+            ("qc", &self.eval_qc),
+            ("s1", &self.eval_s1),
+            ("s2", &self.eval_s2),
+            ("s3", &self.eval_s3),
+            ("a", &self.eval_a),
+            ("b", &self.eval_b),
+            ("c", &self.eval_c),
+            ("z", &self.eval_z),
+            ("zw", &self.eval_zw),
+            ("t1w", &self.eval_t1w),
+            ("t2w", &self.eval_t2w),
+        ]
+    }
+}
+
+/// JSON format for snarkjs `fflonk` verification_key.json
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerificationKeyFflonkJson {
+    pub protocol: Option<String>,
+    pub curve: Option<String>,
+    #[serde(rename = "nPublic")]
+    pub n_public: u32,
+    pub power: u32,
+    pub k1: String,
+    pub k2: String,
+    pub w: String,
+    pub w3: String,
+    pub w4: String,
+    pub w8: String,
+    pub wr: String,
+    #[serde(rename = "X_2")]
+    pub x_2: Vec<Vec<String>>,
+    #[serde(rename = "C0")]
+    pub c0: Vec<String>,
+}
+
+impl VerificationKeyFflonkJson {
+    /// Convert to FflonkVerificationKey struct
+    pub fn to_vk(&self) -> Result<FflonkVerificationKey, ParseError> {
+        Ok(FflonkVerificationKey {
+            n_public: self.n_public,
+            power: self.power,
+            k1: parse_field_element(&self.k1)?,
+            k2: parse_field_element(&self.k2)?,
+            w: parse_field_element(&self.w)?,
+            w3: parse_field_element(&self.w3)?,
+            w4: parse_field_element(&self.w4)?,
+            w8: parse_field_element(&self.w8)?,
+            wr: parse_field_element(&self.wr)?,
+            x_2: G2Point::from_json_array(&self.x_2)?,
+            c0: G1Point::from_json_array(&self.c0)?,
+        })
+    }
+}
+
+/// Commitments of a snarkjs `fflonk` proof
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FflonkPolynomialsJson {
+    #[serde(rename = "C1")]
+    pub c1: Vec<String>,
+    #[serde(rename = "C2")]
+    pub c2: Vec<String>,
+    #[serde(rename = "W1")]
+    pub w1: Vec<String>,
+    #[serde(rename = "W2")]
+    pub w2: Vec<String>,
+}
+
+/// Evaluations of a snarkjs `fflonk` proof
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FflonkEvaluationsJson {
+    pub ql: String,
+    pub qr: String,
+    pub qm: String,
+    pub qo: String,
+    pub qc: String,
+    pub s1: String,
+    pub s2: String,
+    pub s3: String,
+    pub a: String,
+    pub b: String,
+    pub c: String,
+    pub z: String,
+    pub zw: String,
+    pub t1w: String,
+    pub t2w: String,
+    /// Batched inverse for the Solidity verifier; ignored
+    pub inv: Option<String>,
+}
+
+/// JSON format for snarkjs `fflonk` proof files
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProofFflonkJson {
+    pub polynomials: FflonkPolynomialsJson,
+    pub evaluations: FflonkEvaluationsJson,
+    pub protocol: Option<String>,
+    pub curve: Option<String>,
+}
+
+impl ProofFflonkJson {
+    /// Convert to FflonkProof struct
+    pub fn to_proof(&self) -> Result<FflonkProof, ParseError> {
+        let polys = &self.polynomials;
+        let evals = &self.evaluations;
+        Ok(FflonkProof {
+            c1: G1Point::from_json_array(&polys.c1)?,
+            c2: G1Point::from_json_array(&polys.c2)?,
+            w1: G1Point::from_json_array(&polys.w1)?,
+            w2: G1Point::from_json_array(&polys.w2)?,
+            eval_ql: parse_field_element(&evals.ql)?,
+            eval_qr: parse_field_element(&evals.qr)?,
+            eval_qm: parse_field_element(&evals.qm)?,
+            eval_qo: parse_field_element(&evals.qo)?,
+            eval_qc: parse_field_element(&evals.qc)?,
+            eval_s1: parse_field_element(&evals.s1)?,
+            eval_s2: parse_field_element(&evals.s2)?,
+            eval_s3: parse_field_element(&evals.s3)?,
+            eval_a: parse_field_element(&evals.a)?,
+            eval_b: parse_field_element(&evals.b)?,
+            eval_c: parse_field_element(&evals.c)?,
+            eval_z: parse_field_element(&evals.z)?,
+            eval_zw: parse_field_element(&evals.zw)?,
+            eval_t1w: parse_field_element(&evals.t1w)?,
+            eval_t2w: parse_field_element(&evals.t2w)?,
+        })
+    }
+}
+
+impl FflonkChallenges {
+    /// Derive the challenges exactly as snarkjs `fflonk_verify` does
+    ///
+    /// ```text
+    /// β       = H(C0, inputs, C1)
+    /// γ       = H(β)
+    /// xi_seed = H(γ, C2)
+    /// α       = H(xi_seed, ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w)
+    /// y       = H(α, W1)
+    /// ```
+    pub fn snarkjs<B: Bn254Backend>(
+        backend: B,
+        vk: &FflonkVerificationKey,
+        inputs: &[U256],
+        proof: &FflonkProof,
+    ) -> Self {
+        let mut transcript = FiatShamirTranscript::with_backend(backend);
+
+        transcript.append_g1(&vk.c0);
+        transcript.append_scalars(inputs);
+        transcript.append_g1(&proof.c1);
+        let beta = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&beta);
+        let gamma = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&gamma);
+        transcript.append_g1(&proof.c2);
+        let xi_seed = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&xi_seed);
+        for (_, eval) in proof.evaluations() {
+            transcript.append_scalar(eval);
+        }
+        let alpha = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&alpha);
+        transcript.append_g1(&proof.w1);
+        let y = transcript.squeeze_challenge();
+
+        Self { beta, gamma, xi_seed, alpha, y }
+    }
+}
+
+/// snarkjs fflonk verifier
+///
+/// Stores the verification key; the backend is not part of the serialized
+/// state.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FflonkVerifier<B = DefaultBackend> {
+    /// The verification key
+    pub vk: FflonkVerificationKey,
+    #[borsh(skip)]
+    #[serde(skip)]
+    backend: B,
+}
+
+impl FflonkVerifier {
+    /// Create a new verifier with the given verification key
+    ///
+    /// The key is not validated; prefer [`FflonkVerifier::try_new`] for keys
+    /// that come from users.
+    pub fn new(vk: FflonkVerificationKey) -> Self {
+        Self::with_backend(vk, DefaultBackend::default())
+    }
+
+    /// Create a verifier after checking every point and scalar of the key
+    pub fn try_new(vk: FflonkVerificationKey) -> Result<Self, VkError> {
+        vk.validate()?;
+        Ok(Self::new(vk))
+    }
+
+    /// Create verifier from a snarkjs `fflonk` verification key
+    ///
+    /// Only parses the key; see [`FflonkVerifier::from_json_checked`].
+    pub fn from_json(vk_json: &VerificationKeyFflonkJson) -> Result<Self, ParseError> {
+        Ok(Self::new(vk_json.to_vk()?))
+    }
+
+    /// Parse and validate a snarkjs `fflonk` verification key
+    pub fn from_json_checked(vk_json: &VerificationKeyFflonkJson) -> Result<Self, VerifyError> {
+        Ok(Self::try_new(vk_json.to_vk()?)?)
+    }
+}
+
+impl<B: Bn254Backend> FflonkVerifier<B> {
+    /// Create a verifier that runs its curve operations on `backend`
+    ///
+    /// The key is not validated; call [`FflonkVerificationKey::validate`]
+    /// first for keys that come from users.
+    pub fn with_backend(vk: FflonkVerificationKey, backend: B) -> Self {
+        Self { vk, backend }
+    }
+
+    /// The backend this verifier calls
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Verify an fflonk proof
+    ///
+    /// Use [`FflonkVerifier::verify_checked`] to learn why a proof was rejected.
+    pub fn verify(&self, inputs: &[U256], proof: &FflonkProof) -> bool {
+        self.verify_checked(inputs, proof).is_ok()
+    }
+
+    /// Verify a proof with inputs as decimal strings (snarkjs format)
+    pub fn verify_json(&self, proof_json: &ProofFflonkJson, inputs: &[String]) -> bool {
+        self.verify_json_checked(proof_json, inputs).is_ok()
+    }
+
+    /// Verify an fflonk proof, reporting the reason for any failure
+    pub fn verify_checked(&self, inputs: &[U256], proof: &FflonkProof) -> Result<(), VerifyError> {
+        if inputs.len() != self.vk.num_inputs() {
+            return Err(VerifyError::InputCountMismatch {
+                expected: self.vk.num_inputs(),
+                found: inputs.len(),
+            });
+        }
+        if let Some(index) = inputs.iter().position(|x| *x >= FR_MODULUS) {
+            return Err(VerifyError::NonCanonicalInput { index });
+        }
+        proof.validate()?;
+
+        let challenges = FflonkChallenges::snarkjs(&self.backend, &self.vk, inputs, proof);
+        let neg_a1 = self.opening_point(inputs, proof, &challenges)?;
+
+        // e(-A1, [1]₂) · e(W2, [x]₂) = 1
+        let mut input = [0u8; 2 * 192];
+        input[0..64].copy_from_slice(&neg_a1.to_precompile_bytes());
+        input[64..192].copy_from_slice(&G2_GENERATOR.to_precompile_bytes());
+        input[192..256].copy_from_slice(&proof.w2.to_precompile_bytes());
+        input[256..384].copy_from_slice(&self.vk.x_2.to_precompile_bytes());
+
+        if self.backend.pairing_check(&input) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingFailed)
+        }
+    }
+
+    /// Verify a proof with decimal-string inputs, reporting the reason for any failure
+    pub fn verify_json_checked(
+        &self,
+        proof_json: &ProofFflonkJson,
+        inputs: &[String],
+    ) -> Result<(), VerifyError> {
+        let proof = proof_json.to_proof()?;
+        let inputs = parse_inputs(inputs)?;
+        self.verify_checked(&inputs, &proof)
+    }
+
+    /// The G1 point `-A1 = -(F - E - Z_T0(y)·W1 + y·W2)`, as a single multiexp
+    fn opening_point(
+        &self,
+        inputs: &[U256],
+        proof: &FflonkProof,
+        challenges: &FflonkChallenges,
+    ) -> Result<G1Point, VerifyError> {
+        let vk = &self.vk;
+        let beta = fr(&challenges.beta);
+        let gamma = fr(&challenges.gamma);
+        let seed = fr(&challenges.xi_seed);
+        let alpha = fr(&challenges.alpha);
+        let y = fr(&challenges.y);
+        let [ql, qr, qm, qo, qc, s1, s2, s3, a, b, c, z, zw, t1w, t2w] =
+            proof.evaluations().map(|(_, eval)| fr(eval));
+
+        // h0 = seed³, h1 = seed⁶, h2 = seed⁸, so h0⁸ = h1⁴ = h2³ = ξ = seed²⁴;
+        // h3 = h2·wr, so h3³ = ξω
+        let seed2 = seed.square();
+        let h0 = seed2.mul(&seed);
+        let h1 = h0.square();
+        let h2 = h1.mul(&seed2);
+        let h3 = h2.mul(&fr(&vk.wr));
+        let xi = h2.square().mul(&h2);
+
+        let w3 = fr(&vk.w3);
+        let s0_roots = coset(&h0, &fr(&vk.w8), 8);
+        let s1_roots = coset(&h1, &fr(&vk.w4), 4);
+        let mut s2_roots = coset(&h2, &w3, 3);
+        s2_roots.extend(coset(&h3, &w3, 3));
+
+        // ξⁿ and Z_H(ξ) = ξⁿ - 1
+        let mut xin = xi;
+        for _ in 0..vk.power {
+            xin = xin.square();
+        }
+        let zh = xin.sub(&Fr::ONE);
+
+        // A challenge inside the domain (or y inside an opening set) leaves
+        // a division by zero; such a proof cannot be valid
+        let lagrange = lagrange_evaluations(&xi, &fr(&vk.w), &zh, vk.power, vk.num_inputs().max(1))
+            .ok_or(VerifyError::PairingFailed)?;
+        let l1 = lagrange[0];
+
+        // PI(ξ) = -Σ input_i · L_{i+1}(ξ)
+        let pi = inputs
+            .iter()
+            .zip(&lagrange)
+            .fold(Fr::ZERO, |acc, (x, l)| acc.sub(&fr(x).mul(l)));
+
+        let z_t0 = vanishing(&s0_roots, &y);
+        let z_t1 = vanishing(&s1_roots, &y);
+        let z_t2 = vanishing(&s2_roots, &y);
+
+        // Z_H(ξ), Z_T1(y), Z_T2(y) and the basis denominators of S0, S1, S2,
+        // inverted together
+        let mut inverses = vec![zh, z_t1, z_t2];
+        for roots in [&s0_roots, &s1_roots, &s2_roots] {
+            inverses.extend(basis_denominators(roots, &y));
+        }
+        if !Fr::batch_inverse(&mut inverses) {
+            return Err(VerifyError::PairingFailed);
+        }
+        let (zh_inv, z_t1_inv, z_t2_inv) = (inverses[0], inverses[1], inverses[2]);
+        let (s0_inv, rest) = inverses[3..].split_at(8);
+        let (s1_inv, s2_inv) = rest.split_at(4);
+
+        // T0(ξ) = (ql·a + qr·b + qm·a·b + qo·c + qc + PI(ξ)) / Z_H(ξ)
+        let t0 = ql
+            .mul(&a)
+            .add(&qr.mul(&b))
+            .add(&qm.mul(&a).mul(&b))
+            .add(&qo.mul(&c))
+            .add(&qc)
+            .add(&pi)
+            .mul(&zh_inv);
+
+        // T1(ξ) = L1(ξ)·(z - 1) / Z_H(ξ)
+        let t1 = l1.mul(&z.sub(&Fr::ONE)).mul(&zh_inv);
+
+        // T2(ξ) = ((a + βξ + γ)(b + βk1ξ + γ)(c + βk2ξ + γ)·z
+        //        - (a + βs1 + γ)(b + βs2 + γ)(c + βs3 + γ)·zw) / Z_H(ξ)
+        let beta_xi = beta.mul(&xi);
+        let t2 = a
+            .add(&beta_xi)
+            .add(&gamma)
+            .mul(&b.add(&beta_xi.mul(&fr(&vk.k1))).add(&gamma))
+            .mul(&c.add(&beta_xi.mul(&fr(&vk.k2))).add(&gamma))
+            .mul(&z)
+            .sub(
+                &a.add(&beta.mul(&s1))
+                    .add(&gamma)
+                    .mul(&b.add(&beta.mul(&s2)).add(&gamma))
+                    .mul(&c.add(&beta.mul(&s3)).add(&gamma))
+                    .mul(&zw),
+            )
+            .mul(&zh_inv);
+
+        // C0(X) = ql + X·qr + X²·qo + X³·qm + X⁴·qc + X⁵·s1 + X⁶·s2 + X⁷·s3 on S0
+        let r0 = interpolate(
+            &z_t0,
+            s0_inv,
+            s0_roots.iter().map(|h| horner(&[ql, qr, qo, qm, qc, s1, s2, s3], h)),
+        );
+        // C1(X) = a + X·b + X²·c + X³·T0 on S1
+        let r1 = interpolate(&z_t1, s1_inv, s1_roots.iter().map(|h| horner(&[a, b, c, t0], h)));
+        // C2(X) = z + X·T1 + X²·T2 at h2·w3ⁱ, zw + X·T1w + X²·T2w at h3·w3ⁱ
+        let r2 = interpolate(
+            &z_t2,
+            s2_inv,
+            s2_roots[..3]
+                .iter()
+                .map(|h| horner(&[z, t1, t2], h))
+                .chain(s2_roots[3..].iter().map(|h| horner(&[zw, t1w, t2w], h))),
+        );
+
+        let quotient1 = alpha.mul(&z_t0).mul(&z_t1_inv);
+        let quotient2 = alpha.square().mul(&z_t0).mul(&z_t2_inv);
+        let e = r0.add(&quotient1.mul(&r1)).add(&quotient2.mul(&r2));
+
+        // -A1 = -C0 - q1·C1 - q2·C2 + e·[1]₁ + Z_T0(y)·W1 - y·W2
+        let g1 = G1Point { x: U256::one(), y: U256::from(2u64) };
+        multiexp(
+            &self.backend,
+            &[
+                (&vk.c0, Fr::ONE.neg()),
+                (&proof.c1, quotient1.neg()),
+                (&proof.c2, quotient2.neg()),
+                (&g1, e),
+                (&proof.w1, z_t0),
+                (&proof.w2, y.neg()),
+            ],
+        )
+    }
+}
+
+/// `{h·ωⁱ : i < count}`
+fn coset(h: &Fr, w: &Fr, count: usize) -> Vec<Fr> {
+    let mut roots = Vec::with_capacity(count);
+    let mut root = *h;
+    for _ in 0..count {
+        roots.push(root);
+        root = root.mul(w);
+    }
+    roots
+}
+
+/// Z(y) = Π (y - rᵢ)
+fn vanishing(roots: &[Fr], y: &Fr) -> Fr {
+    roots.iter().fold(Fr::ONE, |acc, r| acc.mul(&y.sub(r)))
+}
+
+/// (y - rᵢ)·Π_{j≠i} (rᵢ - rⱼ), so that Lᵢ(y) = Z(y) / denominatorᵢ
+fn basis_denominators(roots: &[Fr], y: &Fr) -> Vec<Fr> {
+    roots
+        .iter()
+        .enumerate()
+        .map(|(i, ri)| {
+            roots
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(y.sub(ri), |acc, (_, rj)| acc.mul(&ri.sub(rj)))
+        })
+        .collect()
+}
+
+/// Σ valueᵢ·Lᵢ(y), given Z(y) and the inverted basis denominators
+fn interpolate(z: &Fr, inv_denominators: &[Fr], values: impl Iterator<Item = Fr>) -> Fr {
+    values
+        .zip(inv_denominators)
+        .fold(Fr::ZERO, |acc, (value, inv)| acc.add(&value.mul(inv)))
+        .mul(z)
+}
+
+/// c₀ + x·c₁ + x²·c₂ + …
+fn horner(coeffs: &[Fr], x: &Fr) -> Fr {
+    coeffs.iter().rev().fold(Fr::ZERO, |acc, c| acc.mul(x).add(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn g1() -> G1Point {
+        G1Point { x: U256::one(), y: U256::from(2u64) }
+    }
+
+    fn to_u256(x: Fr) -> U256 {
+        U256(x.limbs)
+    }
+
+    fn test_vk(n_public: u32) -> FflonkVerificationKey {
+        let w = domain_generator(3);
+        // (r - 1) / 3
+        let third = (FR_MODULUS - U256::one()) / U256::from(3u64);
+        FflonkVerificationKey {
+            n_public,
+            power: 3,
+            k1: U256::from(2u64),
+            k2: U256::from(3u64),
+            w: to_u256(w),
+            w3: to_u256(Fr::from_u64(5).pow(&third.0)),
+            w4: to_u256(domain_generator(2)),
+            w8: to_u256(domain_generator(3)),
+            // 3·3 = 1 mod 8, so (w³)³ = w
+            wr: to_u256(w.pow(&[3, 0, 0, 0])),
+            x_2: G2_GENERATOR,
+            c0: g1(),
+        }
+    }
+
+    fn test_proof() -> FflonkProof {
+        FflonkProof {
+            c1: g1(),
+            c2: g1(),
+            w1: g1(),
+            w2: g1(),
+            eval_ql: U256::from(1u64),
+            eval_qr: U256::from(2u64),
+            eval_qm: U256::from(3u64),
+            eval_qo: U256::from(4u64),
+            eval_qc: U256::from(5u64),
+            eval_s1: U256::from(6u64),
+            eval_s2: U256::from(7u64),
+            eval_s3: U256::from(8u64),
+            eval_a: U256::from(9u64),
+            eval_b: U256::from(10u64),
+            eval_c: U256::from(11u64),
+            eval_z: U256::from(12u64),
+            eval_zw: U256::from(13u64),
+            eval_t1w: U256::from(14u64),
+            eval_t2w: U256::from(15u64),
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // End-to-end fixture
+    // ------------------------------------------------------------------
+    //
+    // The same 2-public-input circuit as the PLONK fixture (x = w·w,
+    // y = w + x with w = 3, n = 8). snarkjs is not available in this build
+    // environment, so the key and proof were produced by a standalone
+    // re-implementation of snarkjs `fflonk_prove` (C0/C1/C2 packing,
+    // keccak256 transcript, two-step W1/W2 opening) with a throwaway
+    // powers-of-tau trapdoor. `inv` is left out; it only serves the
+    // Solidity verifier. scripts/fixtures/snarkjs.sh runs the real snarkjs
+    // `fflonk setup`/`fflonk prove` on the same circuit; its output replaces
+    // these three constants as is.
+
+    const FIXTURE_VK: &str = r#"{
+        "protocol": "fflonk",
+        "curve": "bn128",
+        "nPublic": 2,
+        "power": 3,
+        "k1": "2",
+        "k2": "3",
+        "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+        "w3": "4407920970296243842393367215006156084916469457145843978461",
+        "w4": "21888242871839275217838484774961031246007050428528088939761107053157389710902",
+        "w8": "19540430494807482326159819597004422086093766032135589407132600596362845576832",
+        "wr": "13274704216607947843011480449124596415239537050559949017414504948711435969894",
+        "X_2": [["20396790017462177577795588559428958655268202073087162499345397433404059956576", "15788477483285497372005178936828511241563884878250405527776485447386745531773"], ["20764908654156620093811051140957766174003818082026643764027573090034871873626", "7928044193603282859553489605976586646863418897204687712555170336456035302397"], ["1", "0"]],
+        "C0": ["5744663640663984111394219167783544479870829042319097474934285811354612576733", "5133609323204148014213549132671850174180789777345230127679069102865034301078", "1"]
+    }"#;
+
+    const FIXTURE_PROOF: &str = r#"{
+        "polynomials": {
+            "C1": ["1633579183727266328669046865812767609039086410892683197708007942602221391406", "12720731048809563825464437194600220389042480431709920012229032325870911368790", "1"],
+            "C2": ["2402657664104344221018788733967930154463785859169125619066153817971134969391", "9051558796873238430151529106794070130209954896425126242298430963640911198761", "1"],
+            "W1": ["7554942465981062921365828433494093536533031440512291067087052605168828182975", "19893410141803794541647680795528077769956973943019008001396185657265127497995", "1"],
+            "W2": ["6041631804863734197958177672416247145848910806190683166489262112423304590172", "4187766229915129475855238996894231493499530769801620678795829604735103422378", "1"]
+        },
+        "evaluations": {
+            "ql": "14051417498613135845724519553473441430081963401924192676878263968769725917390",
+            "qr": "9505027681022811769476370539330046836072179089928090929343180984402993979208",
+            "qm": "4229780874632644335582639393989397454848489159476453661908661075125143809811",
+            "qo": "8153434316183819117187395811937830797627696151011489752446362127047670706598",
+            "qc": "0",
+            "s1": "18784760558253243619825137318345848828863034380715939410997831040827585086780",
+            "s2": "13652793091101668936634450813467275995728867591152840809598813648124937174307",
+            "s3": "19352716077247655459913790457767833556559753748118268628758700565901956713329",
+            "a": "14845885032512348072370614228516428719753612669784460996333053827423345255093",
+            "b": "15398179719753499263917142460979450005520208123848870870997913697821584292987",
+            "c": "8086714681565416667566480273046082231706027813830134154624377127775287237624",
+            "z": "577731901996562924678712096168662793517023755482919494158779898832336065529",
+            "zw": "12542839258017584639931948257096456359154878999778435186679538996200285666464",
+            "t1w": "2457145769671848192034944831406113061055189913008325817342045277623090119559",
+            "t2w": "18053904173507577618741084983316329098615322349944062751764841672360410538046"
+        },
+        "protocol": "fflonk",
+        "curve": "bn128"
+    }"#;
+
+    const FIXTURE_PUBLIC: [&str; 2] = ["9", "12"];
+
+    fn fixture() -> (FflonkVerifier, ProofFflonkJson, Vec<String>) {
+        let vk: VerificationKeyFflonkJson = near_sdk::serde_json::from_str(FIXTURE_VK).unwrap();
+        let proof: ProofFflonkJson = near_sdk::serde_json::from_str(FIXTURE_PROOF).unwrap();
+        let inputs = FIXTURE_PUBLIC.iter().map(|s| s.to_string()).collect();
+        (FflonkVerifier::from_json_checked(&vk).unwrap(), proof, inputs)
+    }
+
+    #[test]
+    fn test_valid_proof_verifies() {
+        let (verifier, proof, inputs) = fixture();
+        assert_eq!(verifier.verify_json_checked(&proof, &inputs), Ok(()));
+        assert!(verifier.verify_json(&proof, &inputs));
+    }
+
+    #[test]
+    fn test_changed_public_input_rejected() {
+        let (verifier, proof, mut inputs) = fixture();
+        inputs[0] = "10".to_string();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::PairingFailed)
+        );
+    }
+
+    #[test]
+    fn test_interpolate() {
+        // Interpolating p(X) = 3 + 2X + X² on {h·w3ⁱ} ∪ {h'·w3ⁱ} recovers p(y)
+        let vk = test_vk(0);
+        let w3 = fr(&vk.w3);
+        let mut roots = coset(&Fr::from_u64(11), &w3, 3);
+        roots.extend(coset(&Fr::from_u64(13), &w3, 3));
+        let coeffs = [Fr::from_u64(3), Fr::from_u64(2), Fr::ONE];
+        let y = Fr::from_u64(1000);
+
+        let mut inv = basis_denominators(&roots, &y);
+        assert!(Fr::batch_inverse(&mut inv));
+        let value = interpolate(&vanishing(&roots, &y), &inv, roots.iter().map(|r| horner(&coeffs, r)));
+        assert_eq!(value, horner(&coeffs, &y));
+        assert_eq!(value.to_string(), "1002003");
+
+        // y on a root: the basis is undefined
+        let mut inv = basis_denominators(&roots, &roots[4]);
+        assert!(!Fr::batch_inverse(&mut inv));
+    }
+
+    #[test]
+    fn test_vk_validation() {
+        assert!(test_vk(1).validate().is_ok());
+
+        let mut vk = test_vk(1);
+        vk.w3 = U256::one();
+        assert_eq!(vk.validate().unwrap_err(), VkError::InvalidRootOfUnity { element: "w3" });
+
+        let mut vk = test_vk(1);
+        vk.w8 = vk.w4;
+        assert_eq!(vk.validate().unwrap_err(), VkError::InvalidRootOfUnity { element: "w8" });
+
+        let mut vk = test_vk(1);
+        vk.wr = vk.w;
+        assert_eq!(vk.validate().unwrap_err(), VkError::InvalidRootOfUnity { element: "wr" });
+
+        let mut vk = test_vk(1);
+        vk.w = vk.w4;
+        assert_eq!(vk.validate().unwrap_err(), VkError::InvalidDomain { power: 3 });
+
+        let mut vk = test_vk(1);
+        vk.c0.y = U256::from(3u64);
+        assert_eq!(
+            vk.validate().unwrap_err(),
+            VkError::InvalidPoint { element: "C0", reason: PointError::NotOnCurve }
+        );
+    }
+
+    #[test]
+    fn test_malformed_requests_rejected() {
+        let verifier = FflonkVerifier::new(test_vk(1));
+        let inputs = [U256::from(7u64)];
+
+        assert_eq!(
+            verifier.verify_checked(&[], &test_proof()).unwrap_err(),
+            VerifyError::InputCountMismatch { expected: 1, found: 0 }
+        );
+        assert_eq!(
+            verifier.verify_checked(&[FR_MODULUS], &test_proof()).unwrap_err(),
+            VerifyError::NonCanonicalInput { index: 0 }
+        );
+
+        let mut proof = test_proof();
+        proof.eval_t1w = FR_MODULUS;
+        assert_eq!(
+            verifier.verify_checked(&inputs, &proof).unwrap_err(),
+            VerifyError::NonCanonicalProofScalar { element: "t1w" }
+        );
+
+        let mut proof = test_proof();
+        proof.w1.x = U256::from(2u64);
+        assert_eq!(
+            verifier.verify_checked(&inputs, &proof).unwrap_err(),
+            VerifyError::InvalidProofPoint { element: "W1", reason: PointError::NotOnCurve }
+        );
+    }
+
+    #[test]
+    fn test_invalid_proof_fails_pairing() {
+        use crate::backend::{BackendCall, NearBackend, RecordingBackend};
+
+        let verifier = FflonkVerifier::with_backend(test_vk(1), RecordingBackend::new(NearBackend));
+        assert_eq!(
+            verifier.verify_checked(&[U256::from(7u64)], &test_proof()).unwrap_err(),
+            VerifyError::PairingFailed
+        );
+
+        // Five transcript hashes, one multiexp, one 2-pair pairing check
+        let calls = verifier.backend().take_calls();
+        assert_eq!(calls.len(), 7);
+        assert!(calls[..5].iter().all(|call| matches!(call, BackendCall::Keccak256(_))));
+        assert!(matches!(calls[5], BackendCall::G1Multiexp(_)));
+        assert!(matches!(&calls[6], BackendCall::PairingCheck(input) if input.len() == 2 * 192));
+    }
+
+    #[test]
+    fn test_json_parsing() {
+        let vk = test_vk(1);
+        let vk_json = format!(
+            r#"{{"protocol": "fflonk", "curve": "bn128", "nPublic": 1, "power": 3,
+                "k1": "2", "k2": "3", "w": "{w}", "w3": "{w3}", "w4": "{w4}", "w8": "{w8}",
+                "wr": "{wr}", "X_2": [["{x0}", "{x1}"], ["{y0}", "{y1}"], ["1", "0"]],
+                "C0": ["1", "2", "1"]}}"#,
+            w = vk.w,
+            w3 = vk.w3,
+            w4 = vk.w4,
+            w8 = vk.w8,
+            wr = vk.wr,
+            x0 = G2_GENERATOR.x[0],
+            x1 = G2_GENERATOR.x[1],
+            y0 = G2_GENERATOR.y[0],
+            y1 = G2_GENERATOR.y[1],
+        );
+        let vk_json: VerificationKeyFflonkJson = near_sdk::serde_json::from_str(&vk_json).unwrap();
+        let verifier = FflonkVerifier::from_json_checked(&vk_json).unwrap();
+        assert_eq!(verifier.vk.wr, vk.wr);
+
+        let proof_json = r#"{
+            "polynomials": {
+                "C1": ["1", "2", "1"], "C2": ["1", "2", "1"],
+                "W1": ["1", "2", "1"], "W2": ["0", "1", "0"]
+            },
+            "evaluations": {
+                "ql": "1", "qr": "2", "qm": "3", "qo": "4", "qc": "5", "s1": "6",
+                "s2": "7", "s3": "8", "a": "9", "b": "10", "c": "11", "z": "12",
+                "zw": "13", "t1w": "14", "t2w": "0xf", "inv": "16"
+            },
+            "protocol": "fflonk",
+            "curve": "bn128"
+        }"#;
+        let proof_json: ProofFflonkJson = near_sdk::serde_json::from_str(proof_json).unwrap();
+        let proof = proof_json.to_proof().unwrap();
+        assert_eq!(proof.eval_t2w, U256::from(15u64));
+        assert!(proof.w2.is_zero());
+        assert_eq!(
+            verifier.verify_json_checked(&proof_json, &["x".to_string()]).unwrap_err(),
+            VerifyError::InvalidInput { index: 0 }
+        );
+    }
+}
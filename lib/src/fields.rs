@@ -0,0 +1,633 @@
+//! BN254 base field arithmetic (Fq and its extension tower)
+//!
+//! Used for point validation (on-curve and subgroup checks) before anything
+//! is handed to the alt_bn128 host functions, which abort the transaction on
+//! malformed points instead of returning an error.
+//!
+//! The tower used by the pairing ([`crate::pairing`]) is the usual one:
+//!
+//! - Fq2 = Fq[u]/(u² + 1)
+//! - Fq6 = Fq2[v]/(v³ - ξ), with ξ = 9 + u
+//! - Fq12 = Fq6[w]/(w² - v)
+//!
+//! Elements are kept in Montgomery form. Convert with [`Fq::from_u256`] /
+//! [`Fq::to_u256`]; values `>= q` are rejected on the way in.
+
+use crate::types::{U256, FQ_MODULUS};
+
+/// Field modulus q as little-endian limbs
+const Q: [u64; 4] = [
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+];
+
+/// Montgomery constant: R = 2^256 mod q
+const R: [u64; 4] = [
+    0xd35d438dc58f0d9d,
+    0x0a78eb28f5c70b3d,
+    0x666ea36f7879462c,
+    0x0e0a77c19a07df2f,
+];
+
+/// Montgomery constant: R^2 mod q (for converting to Montgomery form)
+const R2: [u64; 4] = [
+    0xf32cfc5b538afa89,
+    0xb5e71911d44501fb,
+    0x47ab1eff0a417ff6,
+    0x06d89f71cab8351f,
+];
+
+/// Montgomery constant: q' = -q^(-1) mod 2^64
+const Q_PRIME: u64 = 0x87d20782e4866389;
+
+/// (q + 1) / 4: `a^((q+1)/4)` is a square root of `a` when one exists (q ≡ 3 mod 4)
+const SQRT_EXP: [u64; 4] = [
+    0x4f082305b61f3f52,
+    0x65e05aa45a1c72a3,
+    0x6e14116da0605617,
+    0x0c19139cb84c680a,
+];
+
+/// (q - 3) / 4, used by the Fq2 square root
+const SQRT_EXP_FQ2: [u64; 4] = [
+    0x4f082305b61f3f51,
+    0x65e05aa45a1c72a3,
+    0x6e14116da0605617,
+    0x0c19139cb84c680a,
+];
+
+/// (q - 1) / 2: the largest canonical value that is not "negative"
+const HALF_Q: [u64; 4] = [
+    0x9e10460b6c3e7ea3,
+    0xcbc0b548b438e546,
+    0xdc2822db40c0ac2e,
+    0x183227397098d014,
+];
+
+/// Element of the BN254 base field Fq (Montgomery form)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fq([u64; 4]);
+
+impl Fq {
+    /// Additive identity
+    pub const fn zero() -> Self {
+        Fq([0; 4])
+    }
+
+    /// Multiplicative identity
+    pub const fn one() -> Self {
+        Fq(R)
+    }
+
+    /// Build from limbs that are already in Montgomery form (for constants)
+    pub(crate) const fn from_mont(limbs: [u64; 4]) -> Self {
+        Fq(limbs)
+    }
+
+    /// Convert a canonical integer into the field; `None` if `value >= q`
+    pub fn from_u256(value: &U256) -> Option<Self> {
+        if *value >= FQ_MODULUS {
+            return None;
+        }
+        Some(Fq(value.0).mul(&Fq(R2)))
+    }
+
+    /// Convert back to a canonical integer
+    pub fn to_u256(&self) -> U256 {
+        U256(self.mul(&Fq([1, 0, 0, 0])).0)
+    }
+
+    /// Small integer constant
+    pub fn from_u64(value: u64) -> Self {
+        Fq([value, 0, 0, 0]).mul(&Fq(R2))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 == [0; 4]
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut out = [0u64; 4];
+        let mut carry = 0u128;
+        for (i, limb) in out.iter_mut().enumerate() {
+            let sum = self.0[i] as u128 + other.0[i] as u128 + carry;
+            *limb = sum as u64;
+            carry = sum >> 64;
+        }
+        // q < 2^254, so the sum never overflows 256 bits
+        Fq(reduce_once(out))
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        let (mut out, borrow) = sub_limbs(&self.0, &other.0);
+        if borrow {
+            let mut carry = 0u128;
+            for (i, limb) in out.iter_mut().enumerate() {
+                let sum = *limb as u128 + Q[i] as u128 + carry;
+                *limb = sum as u64;
+                carry = sum >> 64;
+            }
+        }
+        Fq(out)
+    }
+
+    pub fn neg(&self) -> Self {
+        Fq::zero().sub(self)
+    }
+
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    /// Montgomery multiplication (CIOS)
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut t = [0u64; 6];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for (j, b) in other.0.iter().enumerate() {
+                let tmp = t[j] as u128 + self.0[i] as u128 * *b as u128 + carry;
+                t[j] = tmp as u64;
+                carry = tmp >> 64;
+            }
+            let tmp = t[4] as u128 + carry;
+            t[4] = tmp as u64;
+            t[5] = (tmp >> 64) as u64;
+
+            let m = t[0].wrapping_mul(Q_PRIME);
+            let mut carry = (t[0] as u128 + m as u128 * Q[0] as u128) >> 64;
+            for j in 1..4 {
+                let tmp = t[j] as u128 + m as u128 * Q[j] as u128 + carry;
+                t[j - 1] = tmp as u64;
+                carry = tmp >> 64;
+            }
+            let tmp = t[4] as u128 + carry;
+            t[3] = tmp as u64;
+            t[4] = t[5] + (tmp >> 64) as u64;
+        }
+        Fq(reduce_once([t[0], t[1], t[2], t[3]]))
+    }
+
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// Exponentiation by a little-endian limb exponent
+    pub fn pow(&self, exp: &[u64; 4]) -> Self {
+        let mut result = Fq::one();
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (limb >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+
+    /// Multiplicative inverse via Fermat (`a^(q-2)`); `None` for zero
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut exp = Q;
+        exp[0] -= 2;
+        Some(self.pow(&exp))
+    }
+
+    /// Square root; `None` if `self` is not a quadratic residue
+    ///
+    /// Returns either root; pick between `r` and `-r` with
+    /// [`Fq::is_lexicographically_largest`].
+    pub fn sqrt(&self) -> Option<Self> {
+        let root = self.pow(&SQRT_EXP);
+        (root.square() == *self).then_some(root)
+    }
+
+    /// Whether the canonical value is greater than `(q - 1) / 2`, i.e. `x > -x`
+    ///
+    /// This is the sign convention point compression uses (gnark, arkworks,
+    /// zcash) to tell `y` and `-y` apart.
+    pub fn is_lexicographically_largest(&self) -> bool {
+        self.to_u256() > U256(HALF_Q)
+    }
+}
+
+/// Subtract q once if `limbs >= q`
+fn reduce_once(limbs: [u64; 4]) -> [u64; 4] {
+    let (reduced, borrow) = sub_limbs(&limbs, &Q);
+    if borrow {
+        limbs
+    } else {
+        reduced
+    }
+}
+
+/// `a - b` over 256 bits, returning the borrow flag
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut out = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d1, b1) = a[i].overflowing_sub(b[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow as u64);
+        out[i] = d2;
+        borrow = b1 || b2;
+    }
+    (out, borrow)
+}
+
+/// Element of Fq2 = Fq[u]/(u² + 1), written `c0 + c1·u`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fq2 {
+    pub c0: Fq,
+    pub c1: Fq,
+}
+
+impl Fq2 {
+    pub const fn new(c0: Fq, c1: Fq) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub const fn zero() -> Self {
+        Self::new(Fq::zero(), Fq::zero())
+    }
+
+    pub const fn one() -> Self {
+        Self::new(Fq::one(), Fq::zero())
+    }
+
+    /// Convert canonical `(c0, c1)` integers; `None` if either is `>= q`
+    pub fn from_u256(c: &[U256; 2]) -> Option<Self> {
+        Some(Self::new(Fq::from_u256(&c[0])?, Fq::from_u256(&c[1])?))
+    }
+
+    pub fn to_u256(&self) -> [U256; 2] {
+        [self.c0.to_u256(), self.c1.to_u256()]
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(self.c0.add(&other.c0), self.c1.add(&other.c1))
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        Self::new(self.c0.sub(&other.c0), self.c1.sub(&other.c1))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(self.c0.neg(), self.c1.neg())
+    }
+
+    pub fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    /// Karatsuba: (a0 + a1·u)(b0 + b1·u) = (a0b0 - a1b1) + ((a0+a1)(b0+b1) - a0b0 - a1b1)·u
+    pub fn mul(&self, other: &Self) -> Self {
+        let v0 = self.c0.mul(&other.c0);
+        let v1 = self.c1.mul(&other.c1);
+        let c1 = self
+            .c0
+            .add(&self.c1)
+            .mul(&other.c0.add(&other.c1))
+            .sub(&v0)
+            .sub(&v1);
+        Self::new(v0.sub(&v1), c1)
+    }
+
+    /// (a0 + a1·u)² = (a0 + a1)(a0 - a1) + 2·a0·a1·u
+    pub fn square(&self) -> Self {
+        let c0 = self.c0.add(&self.c1).mul(&self.c0.sub(&self.c1));
+        let c1 = self.c0.mul(&self.c1).double();
+        Self::new(c0, c1)
+    }
+
+    pub fn mul_by_fq(&self, k: &Fq) -> Self {
+        Self::new(self.c0.mul(k), self.c1.mul(k))
+    }
+
+    /// Inverse via the norm: 1/(a0 + a1·u) = (a0 - a1·u) / (a0² + a1²)
+    pub fn inverse(&self) -> Option<Self> {
+        let norm_inv = self.c0.square().add(&self.c1.square()).inverse()?;
+        Some(Self::new(self.c0.mul(&norm_inv), self.c1.neg().mul(&norm_inv)))
+    }
+
+    /// Frobenius map `x ↦ x^q`, which for Fq2 is conjugation
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, self.c1.neg())
+    }
+
+    /// Multiply by ξ = 9 + u: (9·c0 - c1) + (c0 + 9·c1)·u
+    pub fn mul_by_nonresidue(&self) -> Self {
+        let nine = |x: &Fq| x.double().double().double().add(x);
+        Self::new(nine(&self.c0).sub(&self.c1), self.c0.add(&nine(&self.c1)))
+    }
+
+    /// Exponentiation by a little-endian limb exponent
+    pub fn pow(&self, exp: &[u64; 4]) -> Self {
+        let mut result = Fq2::one();
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (limb >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+
+    /// Square root; `None` if `self` is not a square in Fq2
+    ///
+    /// Algorithm 9 of Adj and Rodríguez-Henríquez, "Square root computation
+    /// over even extension fields" (q ≡ 3 mod 4).
+    pub fn sqrt(&self) -> Option<Self> {
+        if self.is_zero() {
+            return Some(Self::zero());
+        }
+        let minus_one = Fq2::one().neg();
+        let a1 = self.pow(&SQRT_EXP_FQ2);
+        let alpha = a1.square().mul(self);
+        // alpha^q · alpha is the norm of alpha; -1 means there is no root
+        if alpha.conjugate().mul(&alpha) == minus_one {
+            return None;
+        }
+        let x0 = a1.mul(self);
+        let root = if alpha == minus_one {
+            // multiply by u
+            Self::new(x0.c1.neg(), x0.c0)
+        } else {
+            alpha.add(&Fq2::one()).pow(&HALF_Q).mul(&x0)
+        };
+        (root.square() == *self).then_some(root)
+    }
+
+    /// Whether `x > -x`, comparing `c1` first and `c0` when `c1` is zero
+    ///
+    /// Matches gnark's `E2.LexicographicallyLargest` and the arkworks
+    /// ordering of quadratic extension elements.
+    pub fn is_lexicographically_largest(&self) -> bool {
+        if self.c1.is_zero() {
+            self.c0.is_lexicographically_largest()
+        } else {
+            self.c1.is_lexicographically_largest()
+        }
+    }
+}
+
+/// Element of Fq6 = Fq2[v]/(v³ - ξ), written `c0 + c1·v + c2·v²`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fq6 {
+    pub c0: Fq2,
+    pub c1: Fq2,
+    pub c2: Fq2,
+}
+
+impl Fq6 {
+    pub const fn new(c0: Fq2, c1: Fq2, c2: Fq2) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    pub const fn zero() -> Self {
+        Self::new(Fq2::zero(), Fq2::zero(), Fq2::zero())
+    }
+
+    pub const fn one() -> Self {
+        Self::new(Fq2::one(), Fq2::zero(), Fq2::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self::new(self.c0.add(&other.c0), self.c1.add(&other.c1), self.c2.add(&other.c2))
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        Self::new(self.c0.sub(&other.c0), self.c1.sub(&other.c1), self.c2.sub(&other.c2))
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::new(self.c0.neg(), self.c1.neg(), self.c2.neg())
+    }
+
+    /// Karatsuba over the three coefficients, reducing with v³ = ξ
+    pub fn mul(&self, other: &Self) -> Self {
+        let v0 = self.c0.mul(&other.c0);
+        let v1 = self.c1.mul(&other.c1);
+        let v2 = self.c2.mul(&other.c2);
+
+        let c0 = self
+            .c1
+            .add(&self.c2)
+            .mul(&other.c1.add(&other.c2))
+            .sub(&v1)
+            .sub(&v2)
+            .mul_by_nonresidue()
+            .add(&v0);
+        let c1 = self
+            .c0
+            .add(&self.c1)
+            .mul(&other.c0.add(&other.c1))
+            .sub(&v0)
+            .sub(&v1)
+            .add(&v2.mul_by_nonresidue());
+        let c2 = self
+            .c0
+            .add(&self.c2)
+            .mul(&other.c0.add(&other.c2))
+            .sub(&v0)
+            .sub(&v2)
+            .add(&v1);
+        Self::new(c0, c1, c2)
+    }
+
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// Multiply by v: (c0 + c1·v + c2·v²)·v = ξ·c2 + c0·v + c1·v²
+    pub fn mul_by_nonresidue(&self) -> Self {
+        Self::new(self.c2.mul_by_nonresidue(), self.c0, self.c1)
+    }
+
+    /// Inverse via the adjugate; `None` for zero
+    pub fn inverse(&self) -> Option<Self> {
+        let t0 = self.c0.square().sub(&self.c1.mul(&self.c2).mul_by_nonresidue());
+        let t1 = self.c2.square().mul_by_nonresidue().sub(&self.c0.mul(&self.c1));
+        let t2 = self.c1.square().sub(&self.c0.mul(&self.c2));
+        let det = self
+            .c0
+            .mul(&t0)
+            .add(&self.c2.mul(&t1).add(&self.c1.mul(&t2)).mul_by_nonresidue());
+        let det_inv = det.inverse()?;
+        Some(Self::new(t0.mul(&det_inv), t1.mul(&det_inv), t2.mul(&det_inv)))
+    }
+}
+
+/// Element of Fq12 = Fq6[w]/(w² - v), written `c0 + c1·w`
+///
+/// The target group of the BN254 pairing lives in Fq12.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fq12 {
+    pub c0: Fq6,
+    pub c1: Fq6,
+}
+
+impl Fq12 {
+    pub const fn new(c0: Fq6, c1: Fq6) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub const fn one() -> Self {
+        Self::new(Fq6::one(), Fq6::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    /// (a0 + a1·w)(b0 + b1·w) = (a0b0 + a1b1·v) + ((a0+a1)(b0+b1) - a0b0 - a1b1)·w
+    pub fn mul(&self, other: &Self) -> Self {
+        let v0 = self.c0.mul(&other.c0);
+        let v1 = self.c1.mul(&other.c1);
+        let c1 = self
+            .c0
+            .add(&self.c1)
+            .mul(&other.c0.add(&other.c1))
+            .sub(&v0)
+            .sub(&v1);
+        Self::new(v0.add(&v1.mul_by_nonresidue()), c1)
+    }
+
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// `x ↦ x^(q⁶)`: negates the w coefficient
+    pub fn conjugate(&self) -> Self {
+        Self::new(self.c0, self.c1.neg())
+    }
+
+    /// 1/(a0 + a1·w) = (a0 - a1·w) / (a0² - a1²·v); `None` for zero
+    pub fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square().sub(&self.c1.square().mul_by_nonresidue());
+        let norm_inv = norm.inverse()?;
+        Some(Self::new(self.c0.mul(&norm_inv), self.c1.neg().mul(&norm_inv)))
+    }
+
+    /// Exponentiation by a little-endian limb exponent of any length
+    pub fn pow(&self, exp: &[u64]) -> Self {
+        let mut result = Fq12::one();
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (limb >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fq(s: &str) -> Fq {
+        Fq::from_u256(&U256::from_dec_str(s).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_fq_roundtrip_and_range() {
+        let x = U256::from_dec_str("1234567890123456789012345678901234567890").unwrap();
+        assert_eq!(Fq::from_u256(&x).unwrap().to_u256(), x);
+        assert_eq!(Fq::one().to_u256(), U256::one());
+        assert!(Fq::from_u256(&FQ_MODULUS).is_none());
+        assert!(Fq::from_u256(&(FQ_MODULUS - U256::one())).is_some());
+    }
+
+    #[test]
+    fn test_fq_arithmetic() {
+        let a = fq("21888242871839275222246405745257275088696311157297823662689037894645226208582");
+        // (q - 1) + 2 = 1
+        assert_eq!(a.add(&Fq::from_u64(2)), Fq::one());
+        // (q - 1)² = 1
+        assert_eq!(a.square(), Fq::one());
+        assert_eq!(Fq::from_u64(3).sub(&Fq::from_u64(5)).add(&Fq::from_u64(2)), Fq::zero());
+
+        let b = fq("9876543210987654321098765432109876543210");
+        assert_eq!(b.mul(&b.inverse().unwrap()), Fq::one());
+        assert!(Fq::zero().inverse().is_none());
+    }
+
+    #[test]
+    fn test_fq2_arithmetic() {
+        // u² = -1
+        let u = Fq2::new(Fq::zero(), Fq::one());
+        assert_eq!(u.square(), Fq2::one().neg());
+        assert_eq!(u.mul(&u), Fq2::one().neg());
+
+        let x = Fq2::new(Fq::from_u64(9), Fq::from_u64(1));
+        assert_eq!(x.mul(&x.inverse().unwrap()), Fq2::one());
+        assert_eq!(x.square(), x.mul(&x));
+        // ξ·x via the shortcut matches a full multiplication
+        assert_eq!(x.mul_by_nonresidue(), x.mul(&Fq2::new(Fq::from_u64(9), Fq::one())));
+    }
+
+    #[test]
+    fn test_square_roots() {
+        for n in [4u64, 9, 12345678] {
+            let x = Fq::from_u64(n);
+            let root = x.square().sqrt().unwrap();
+            assert!(root == x || root == x.neg());
+        }
+        // -1 is not a square since q ≡ 3 (mod 4)
+        assert!(Fq::one().neg().sqrt().is_none());
+        assert!(Fq::one().neg().is_lexicographically_largest());
+        assert!(!Fq::one().is_lexicographically_largest());
+
+        for x in [
+            Fq2::new(Fq::from_u64(9), Fq::from_u64(1)),
+            Fq2::new(Fq::from_u64(0), Fq::from_u64(5)),
+            Fq2::new(Fq::from_u64(7), Fq::zero()),
+        ] {
+            let root = x.square().sqrt().unwrap();
+            assert!(root == x || root == x.neg());
+            assert_ne!(x.is_lexicographically_largest(), x.neg().is_lexicographically_largest());
+        }
+        // every element of Fq is a square in Fq2, including -1
+        assert_eq!(Fq2::one().neg().sqrt().unwrap().square(), Fq2::one().neg());
+        // ξ = 9 + u is not a square (it defines the sextic twist)
+        assert!(Fq2::new(Fq::from_u64(9), Fq::one()).sqrt().is_none());
+    }
+
+    #[test]
+    fn test_fq6_fq12_arithmetic() {
+        let a = Fq2::new(Fq::from_u64(3), Fq::from_u64(7));
+        let b = Fq2::new(Fq::from_u64(11), Fq::from_u64(2));
+        let x = Fq6::new(a, b, a.mul(&b));
+
+        // v³ = ξ
+        let v = Fq6::new(Fq2::zero(), Fq2::one(), Fq2::zero());
+        let xi = Fq6::new(Fq2::new(Fq::from_u64(9), Fq::one()), Fq2::zero(), Fq2::zero());
+        assert_eq!(v.mul(&v).mul(&v), xi);
+        assert_eq!(x.mul(&x.inverse().unwrap()), Fq6::one());
+        assert_eq!(x.mul_by_nonresidue(), x.mul(&v));
+
+        // w² = v
+        let w = Fq12::new(Fq6::zero(), Fq6::one());
+        assert_eq!(w.square(), Fq12::new(v, Fq6::zero()));
+
+        let y = Fq12::new(x, x.square());
+        assert_eq!(y.mul(&y.inverse().unwrap()), Fq12::one());
+        assert_eq!(y.pow(&[5]), y.square().square().mul(&y));
+    }
+}
@@ -0,0 +1,465 @@
+//! gnark binary encodings of BN254 Groth16 proofs and verifying keys
+//!
+//! gnark's `WriteTo` writes points compressed and `WriteRawTo` writes them
+//! uncompressed. Both use big-endian coordinates, with G2 coordinates in the
+//! EIP-197 order `x.A1 ‖ x.A0 ‖ y.A1 ‖ y.A0`. The two top bits of a point's
+//! first byte say how it is encoded:
+//!
+//! | bits | meaning                                   | G1 / G2 size |
+//! |------|-------------------------------------------|--------------|
+//! | `00` | uncompressed (`(0, 0)` is the identity)   | 64 / 128     |
+//! | `01` | compressed identity                       | 32 / 64      |
+//! | `10` | compressed, y is the smaller root         | 32 / 64      |
+//! | `11` | compressed, y is the larger root          | 32 / 64      |
+//!
+//! Every point carries its own flag, so the decoders here accept both forms
+//! (and even a mix) without being told which one was used.
+//!
+//! Proof layout: `Ar` (G1), `Bs` (G2), `Krs` (G1), then, since gnark v0.9, a
+//! `uint32` count of Pedersen commitments, the commitments (G1) and the
+//! commitment proof of knowledge (G1).
+//!
+//! Verifying key layout: `[α]₁`, `[β]₁`, `[β]₂`, `[γ]₂`, `[δ]₁`, `[δ]₂`, a
+//! `uint32` count and the `K` points (the IC), then, since gnark v0.9, the
+//! committed wires as `uint32`-prefixed lists of `uint64` and the Pedersen
+//! verifying key (`G`, `G^{-1/σ}`, both G2). gnark v0.9 writes that key
+//! directly; later versions write a `uint32` count of keys first. All
+//! integers are big-endian.
+//!
+//! gnark checks that decoded G2 points are in the subgroup; these decoders
+//! only check that points are on the curve. Run
+//! [`VerificationKey::validate`] on the result before storing it, as for any
+//! key that comes from users.
+
+use crate::commitment::{CommitmentProof, CommitmentVerificationKey, PedersenVerifyingKey};
+use crate::curve::{g1_decompress, g2_decompress};
+use crate::error::{ParseError, PointError};
+use crate::types::{G1Point, G2Point, Proof, VerificationKey, U256};
+
+/// Mask of the two flag bits in the first byte of an encoded point
+const FLAG_MASK: u8 = 0b11 << 6;
+const UNCOMPRESSED: u8 = 0b00 << 6;
+const COMPRESSED_INFINITY: u8 = 0b01 << 6;
+/// Compressed, y is the larger root; `0b10` marks the smaller one
+const COMPRESSED_LARGEST: u8 = 0b11 << 6;
+
+/// Decode a gnark Groth16 proof without Pedersen commitments
+///
+/// Accepts the pre-v0.9 layout (A, B, C only) and the current one with an
+/// empty commitment list. Fails with [`ParseError::UnexpectedCommitments`]
+/// for proofs of circuits that call `api.Commit`; use
+/// [`commitment_proof_from_bytes`] for those.
+pub fn proof_from_bytes(bytes: &[u8]) -> Result<Proof, ParseError> {
+    let proof = commitment_proof_from_bytes(bytes)?;
+    if !proof.commitments.is_empty() {
+        return Err(ParseError::UnexpectedCommitments { count: proof.commitments.len() });
+    }
+    Ok(proof.proof)
+}
+
+/// Decode a gnark Groth16 proof, keeping any Pedersen commitments
+pub fn commitment_proof_from_bytes(bytes: &[u8]) -> Result<CommitmentProof, ParseError> {
+    let mut reader = Reader::new(bytes);
+    let proof = Proof {
+        a: reader.g1("Ar")?,
+        b: reader.g2("Bs")?,
+        c: reader.g1("Krs")?,
+    };
+    if reader.is_empty() {
+        return Ok(CommitmentProof { proof, commitments: Vec::new(), commitment_pok: G1Point::zero() });
+    }
+
+    let count = reader.count()?;
+    let commitments = (0..count)
+        .map(|_| reader.g1("Commitments"))
+        .collect::<Result<Vec<_>, _>>()?;
+    let commitment_pok = reader.g1("CommitmentPok")?;
+    reader.finish()?;
+    Ok(CommitmentProof { proof, commitments, commitment_pok })
+}
+
+/// Decode a gnark Groth16 verifying key without Pedersen commitments
+///
+/// Fails with [`ParseError::UnexpectedCommitments`] for keys of circuits
+/// that call `api.Commit`; use [`commitment_vk_from_bytes`] for those.
+pub fn vk_from_bytes(bytes: &[u8]) -> Result<VerificationKey, ParseError> {
+    let vk = commitment_vk_from_bytes(bytes)?;
+    if vk.num_commitments() > 0 {
+        return Err(ParseError::UnexpectedCommitments { count: vk.num_commitments() });
+    }
+    Ok(vk.vk)
+}
+
+/// Decode a gnark Groth16 verifying key, keeping any commitment wires
+///
+/// With no commitments the returned Pedersen key is the identity and is
+/// never used.
+pub fn commitment_vk_from_bytes(bytes: &[u8]) -> Result<CommitmentVerificationKey, ParseError> {
+    let mut reader = Reader::new(bytes);
+    let alpha = reader.g1("alpha")?;
+    // [β]₁ and [δ]₁ are only used by gnark's prover-side checks
+    reader.g1("beta_1")?;
+    let beta = reader.g2("beta")?;
+    let gamma = reader.g2("gamma")?;
+    reader.g1("delta_1")?;
+    let delta = reader.g2("delta")?;
+    let count = reader.count()?;
+    let ic = (0..count).map(|_| reader.g1("K")).collect::<Result<Vec<_>, _>>()?;
+    let vk = VerificationKey { alpha, beta, gamma, delta, ic };
+
+    let mut committed = Vec::new();
+    let mut commitment_key = PedersenVerifyingKey { g: G2Point::zero(), g_sigma_neg: G2Point::zero() };
+    if !reader.is_empty() {
+        for _ in 0..reader.count()? {
+            let wires = (0..reader.count()?)
+                .map(|_| u32::try_from(reader.u64()?).map_err(|_| ParseError::IntegerOverflow))
+                .collect::<Result<Vec<_>, _>>()?;
+            committed.push(wires);
+        }
+        // v0.9 writes one key, later versions a `uint32` count and the keys.
+        // A key is two G2 points of 64 or 128 bytes each, so what is left is
+        // a multiple of 64 bytes exactly when there is no count.
+        let keys = if reader.remaining().is_multiple_of(64) {
+            vec![reader.pedersen_key()?]
+        } else {
+            let count = reader.count()?;
+            (0..count).map(|_| reader.pedersen_key()).collect::<Result<Vec<_>, _>>()?
+        };
+        // Every commitment shares one Pedersen key (see `crate::commitment`)
+        if !committed.is_empty() {
+            let (first, rest) = keys.split_first().ok_or(ParseError::InvalidCommitmentKeys)?;
+            if first.g.is_zero() || rest.iter().any(|k| !same_key(k, first)) {
+                return Err(ParseError::InvalidCommitmentKeys);
+            }
+            commitment_key = first.clone();
+        }
+    }
+    reader.finish()?;
+
+    Ok(CommitmentVerificationKey { vk, commitment_key, public_and_commitment_committed: committed })
+}
+
+fn same_key(a: &PedersenVerifyingKey, b: &PedersenVerifyingKey) -> bool {
+    a.g.to_bytes() == b.g.to_bytes() && a.g_sigma_neg.to_bytes() == b.g_sigma_neg.to_bytes()
+}
+
+/// Cursor over a gnark encoding
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<&'a [u8; N], ParseError> {
+        let (head, tail) = self.bytes.split_at_checked(N).ok_or(ParseError::UnexpectedEnd)?;
+        self.bytes = tail;
+        Ok(head.try_into().expect("split_at_checked returned N bytes"))
+    }
+
+    /// Flag bits of the next point, without consuming it
+    fn flags(&self) -> Result<u8, ParseError> {
+        self.bytes.first().map(|b| b & FLAG_MASK).ok_or(ParseError::UnexpectedEnd)
+    }
+
+    /// A `uint32` length prefix
+    fn count(&mut self) -> Result<usize, ParseError> {
+        Ok(u32::from_be_bytes(*self.take::<4>()?) as usize)
+    }
+
+    fn u64(&mut self) -> Result<u64, ParseError> {
+        Ok(u64::from_be_bytes(*self.take::<8>()?))
+    }
+
+    /// A big-endian coordinate with the flag bits cleared
+    fn masked(&mut self) -> Result<U256, ParseError> {
+        let mut x = *self.take::<32>()?;
+        x[0] &= !FLAG_MASK;
+        Ok(U256::from_be_bytes(&x))
+    }
+
+    fn g1(&mut self, element: &'static str) -> Result<G1Point, ParseError> {
+        let invalid = |reason| ParseError::InvalidPoint { element, reason };
+        match self.flags()? {
+            UNCOMPRESSED => {
+                let point = G1Point::from_bytes(self.take::<64>()?);
+                if !point.is_on_curve() {
+                    return Err(invalid(PointError::NotOnCurve));
+                }
+                Ok(point)
+            }
+            COMPRESSED_INFINITY => {
+                if self.masked()?.is_zero() {
+                    Ok(G1Point::zero())
+                } else {
+                    Err(ParseError::InvalidPointFlags)
+                }
+            }
+            flags => {
+                let largest = flags == COMPRESSED_LARGEST;
+                g1_decompress(&self.masked()?, largest).map_err(invalid)
+            }
+        }
+    }
+
+    fn g2(&mut self, element: &'static str) -> Result<G2Point, ParseError> {
+        let invalid = |reason| ParseError::InvalidPoint { element, reason };
+        match self.flags()? {
+            UNCOMPRESSED => {
+                let point = G2Point::from_bytes(self.take::<128>()?);
+                if !point.is_on_curve() {
+                    return Err(invalid(PointError::NotOnCurve));
+                }
+                Ok(point)
+            }
+            flags => {
+                // x.A1 carries the flags, x.A0 follows
+                let x1 = self.masked()?;
+                let x0 = U256::from_be_bytes(self.take::<32>()?);
+                if flags == COMPRESSED_INFINITY {
+                    return if x0.is_zero() && x1.is_zero() {
+                        Ok(G2Point::zero())
+                    } else {
+                        Err(ParseError::InvalidPointFlags)
+                    };
+                }
+                g2_decompress(&[x0, x1], flags == COMPRESSED_LARGEST).map_err(invalid)
+            }
+        }
+    }
+
+    fn pedersen_key(&mut self) -> Result<PedersenVerifyingKey, ParseError> {
+        Ok(PedersenVerifyingKey {
+            g: self.g2("commitment_key.g")?,
+            g_sigma_neg: self.g2("commitment_key.g_sigma_neg")?,
+        })
+    }
+
+    fn finish(self) -> Result<(), ParseError> {
+        match self.bytes.len() {
+            0 => Ok(()),
+            count => Err(ParseError::TrailingBytes { count }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::curve::{g1_y_is_largest, g2_y_is_largest};
+    use crate::groth16_tests::groth16_integration_tests::{fixture, FIXTURE_INPUTS};
+    use crate::types::FQ_MODULUS;
+    use crate::Verifier;
+
+    const COMPRESSED_SMALLEST: u8 = 0b10 << 6;
+
+    fn g1(p: &G1Point, compressed: bool) -> Vec<u8> {
+        if !compressed {
+            return p.to_bytes().to_vec();
+        }
+        let mut out = p.x.to_be_bytes().to_vec();
+        out[0] |= match (p.is_zero(), g1_y_is_largest(p)) {
+            (true, _) => COMPRESSED_INFINITY,
+            (false, true) => COMPRESSED_LARGEST,
+            (false, false) => COMPRESSED_SMALLEST,
+        };
+        out
+    }
+
+    fn g2(p: &G2Point, compressed: bool) -> Vec<u8> {
+        if !compressed {
+            return p.to_bytes().to_vec();
+        }
+        let mut out = p.to_bytes()[..64].to_vec();
+        out[0] |= match (p.is_zero(), g2_y_is_largest(p)) {
+            (true, _) => COMPRESSED_INFINITY,
+            (false, true) => COMPRESSED_LARGEST,
+            (false, false) => COMPRESSED_SMALLEST,
+        };
+        out
+    }
+
+    /// gnark `Proof.WriteTo` / `WriteRawTo`
+    fn encode_proof(proof: &CommitmentProof, compressed: bool) -> Vec<u8> {
+        let mut out = g1(&proof.proof.a, compressed);
+        out.extend(g2(&proof.proof.b, compressed));
+        out.extend(g1(&proof.proof.c, compressed));
+        out.extend((proof.commitments.len() as u32).to_be_bytes());
+        for d in &proof.commitments {
+            out.extend(g1(d, compressed));
+        }
+        out.extend(g1(&proof.commitment_pok, compressed));
+        out
+    }
+
+    /// gnark `VerifyingKey.WriteTo` / `WriteRawTo`, with the v0.10 key count
+    fn encode_vk(vk: &CommitmentVerificationKey, compressed: bool) -> Vec<u8> {
+        let generator = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+        let mut out = g1(&vk.vk.alpha, compressed);
+        out.extend(g1(&generator, compressed));
+        out.extend(g2(&vk.vk.beta, compressed));
+        out.extend(g2(&vk.vk.gamma, compressed));
+        out.extend(g1(&generator, compressed));
+        out.extend(g2(&vk.vk.delta, compressed));
+        out.extend((vk.vk.ic.len() as u32).to_be_bytes());
+        for k in &vk.vk.ic {
+            out.extend(g1(k, compressed));
+        }
+        out.extend((vk.public_and_commitment_committed.len() as u32).to_be_bytes());
+        for wires in &vk.public_and_commitment_committed {
+            out.extend((wires.len() as u32).to_be_bytes());
+            for w in wires {
+                out.extend((*w as u64).to_be_bytes());
+            }
+        }
+        let keys = vk.num_commitments().min(1) as u32;
+        out.extend(keys.to_be_bytes());
+        for _ in 0..keys {
+            out.extend(g2(&vk.commitment_key.g, compressed));
+            out.extend(g2(&vk.commitment_key.g_sigma_neg, compressed));
+        }
+        out
+    }
+
+    fn plain(vk: &VerificationKey) -> CommitmentVerificationKey {
+        CommitmentVerificationKey {
+            vk: vk.clone(),
+            commitment_key: PedersenVerifyingKey { g: G2Point::zero(), g_sigma_neg: G2Point::zero() },
+            public_and_commitment_committed: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_g1_generator_encoding() {
+        let mut smallest = [0u8; 32];
+        smallest[0] = 0x80;
+        smallest[31] = 1;
+        let mut reader = Reader::new(&smallest);
+        let p = reader.g1("G").unwrap();
+        assert_eq!((p.x, p.y), (U256::from(1u64), U256::from(2u64)));
+
+        let mut largest = smallest;
+        largest[0] = 0xc0;
+        let p = Reader::new(&largest).g1("G").unwrap();
+        assert_eq!(p.y, FQ_MODULUS - U256::from(2u64));
+
+        let mut infinity = [0u8; 32];
+        infinity[0] = 0x40;
+        assert!(Reader::new(&infinity).g1("G").unwrap().is_zero());
+        assert!(Reader::new(&[0u8; 64]).g1("G").unwrap().is_zero());
+        infinity[31] = 1;
+        assert_eq!(Reader::new(&infinity).g1("G").unwrap_err(), ParseError::InvalidPointFlags);
+    }
+
+    #[test]
+    fn test_proof_and_vk_roundtrip_verify() {
+        let (verifier, proof_json, _) = fixture();
+        let inputs: Vec<U256> = FIXTURE_INPUTS.iter().map(|s| U256::from_dec_str(s).unwrap()).collect();
+        let proof = CommitmentProof {
+            proof: proof_json.to_proof().unwrap(),
+            commitments: Vec::new(),
+            commitment_pok: G1Point::zero(),
+        };
+
+        for compressed in [true, false] {
+            let vk = vk_from_bytes(&encode_vk(&plain(&verifier.vk), compressed)).unwrap();
+            let decoded = Verifier::try_new(vk).unwrap();
+            let bytes = encode_proof(&proof, compressed);
+            assert_eq!(bytes.len(), if compressed { 4 * 32 + 4 + 32 } else { 4 * 64 + 4 + 64 });
+            let p = proof_from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.verify_checked(&inputs, &p), Ok(()));
+
+            // pre-v0.9 proofs stop after Krs
+            let legacy = proof_from_bytes(&bytes[..if compressed { 128 } else { 256 }]).unwrap();
+            assert_eq!(decoded.verify_checked(&inputs, &legacy), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_commitment_proof_and_vk_roundtrip_verify() {
+        let (verifier, inputs, proof) = crate::commitment::tests::fixture(2);
+        for compressed in [true, false] {
+            let vk_bytes = encode_vk(&verifier.vk, compressed);
+            let vk = commitment_vk_from_bytes(&vk_bytes).unwrap();
+            assert_eq!(vk.public_and_commitment_committed, vec![vec![1], vec![2, 3]]);
+            let decoded = crate::CommitmentVerifier::try_new(vk).unwrap();
+
+            let proof_bytes = encode_proof(&proof, compressed);
+            let p = commitment_proof_from_bytes(&proof_bytes).unwrap();
+            assert_eq!(decoded.verify_checked(&inputs, &p), Ok(()));
+
+            assert_eq!(
+                proof_from_bytes(&proof_bytes).unwrap_err(),
+                ParseError::UnexpectedCommitments { count: 2 }
+            );
+            assert_eq!(
+                vk_from_bytes(&vk_bytes).unwrap_err(),
+                ParseError::UnexpectedCommitments { count: 2 }
+            );
+        }
+
+        // gnark v0.9 writes the single key without a count
+        for (compressed, key_len) in [(true, 128), (false, 256)] {
+            let mut v09 = encode_vk(&verifier.vk, compressed);
+            v09.drain(v09.len() - key_len - 4..v09.len() - key_len);
+            let vk = commitment_vk_from_bytes(&v09).unwrap();
+            assert!(same_key(&vk.commitment_key, &verifier.vk.commitment_key));
+        }
+
+        // One key per commitment: accepted if they are all the same key
+        let mut two_keys = encode_vk(&verifier.vk, true);
+        let key = two_keys[two_keys.len() - 128..].to_vec();
+        let count = two_keys.len() - 128 - 4;
+        two_keys[count..count + 4].copy_from_slice(&2u32.to_be_bytes());
+        two_keys.extend(&key);
+        let vk = commitment_vk_from_bytes(&two_keys).unwrap();
+        assert!(same_key(&vk.commitment_key, &verifier.vk.commitment_key));
+
+        let mut other_key = two_keys.clone();
+        let g_sigma_neg = other_key.len() - 64;
+        other_key[g_sigma_neg..].copy_from_slice(&g2(&verifier.vk.commitment_key.g, true));
+        assert_eq!(commitment_vk_from_bytes(&other_key).unwrap_err(), ParseError::InvalidCommitmentKeys);
+    }
+
+    #[test]
+    fn test_malformed_encodings_rejected() {
+        let (verifier, proof_json, _) = fixture();
+        let proof = CommitmentProof {
+            proof: proof_json.to_proof().unwrap(),
+            commitments: Vec::new(),
+            commitment_pok: G1Point::zero(),
+        };
+        let bytes = encode_proof(&proof, true);
+
+        assert_eq!(proof_from_bytes(&bytes[..100]).unwrap_err(), ParseError::UnexpectedEnd);
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert_eq!(proof_from_bytes(&trailing).unwrap_err(), ParseError::TrailingBytes { count: 1 });
+
+        // x = 4 is not the abscissa of any G1 point
+        let mut off_curve = bytes.clone();
+        off_curve[..32].copy_from_slice(&U256::from(4u64).to_be_bytes());
+        off_curve[0] |= COMPRESSED_SMALLEST;
+        assert_eq!(
+            proof_from_bytes(&off_curve).unwrap_err(),
+            ParseError::InvalidPoint { element: "Ar", reason: PointError::NotOnCurve }
+        );
+
+        let mut raw = encode_vk(&plain(&verifier.vk), false);
+        raw[63] ^= 1;
+        assert_eq!(
+            vk_from_bytes(&raw).unwrap_err(),
+            ParseError::InvalidPoint { element: "alpha", reason: PointError::NotOnCurve }
+        );
+    }
+}
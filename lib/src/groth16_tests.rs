@@ -7,7 +7,10 @@
 //! mock implementations of the alt_bn128 precompiles.
 
 #[cfg(test)]
-mod groth16_integration_tests {
+pub(crate) mod groth16_integration_tests {
+    use crate::batch::BatchResult;
+    use crate::prepared::PreparedVerifier;
+    use crate::error::{ParseError, PointError, VerifyError, VkError};
     use crate::types::*;
     
     /// Example verification key for a simple circuit
@@ -239,6 +242,565 @@ mod groth16_integration_tests {
         assert_eq!(a, a2);
     }
     */
+
+    // ------------------------------------------------------------------
+    // End-to-end fixture
+    // ------------------------------------------------------------------
+    //
+    // A 2-input verification key and proof that satisfy the Groth16 pairing
+    // equation. Built from known discrete logs (alpha = 11·G1, beta = 22·G2,
+    // gamma = 33·G2, delta = 44·G2, IC = [55, 66, 77]·G1, A = 99·G1,
+    // B = 101·G2, C solved from the equation), so no circuit is needed.
+    // The mocked blockchain runs the real alt_bn128 host functions.
+
+    pub(crate) const FIXTURE_VK: &str = r#"{
+        "protocol": "groth16",
+        "curve": "bn128",
+        "nPublic": 2,
+        "vk_alpha_1": ["19033251874843656108471242320417533909414939332036131356573128480367742634479", "20792135454608030201903199625673964159744755218442260092768620403349374102584", "1"],
+        "vk_beta_2": [["13227684903893563143508500371360359177667163486926346070471263257904198645692", "13629114897952053417906539938577964911921951515022915637600461928677325528252"], ["1507005905758573255637908214470829508320172039536438264147720182507686188985", "4572487487480618770110299110524498444217059341677348111461748164333040044760"], ["1", "0"]],
+        "vk_gamma_2": [["17296964631866875666414577250007729606613623754163522903713032490388223445037", "1283677034539803510874027931207865251280207678130679415321551268807544273576"], ["13812093994560590184820145872054776567171925844569160955348410196240330134586", "8936292128759031376286991047402535836794406876407098545227428235741625269642"], ["1", "0"]],
+        "vk_delta_2": [["14723447415878424720010269203225520894960735394327153370593999433287005836180", "849931228475731710848854335459231361797353585262966996652302040119408313869"], ["11811455205613046277338997666037446642341379735809759775117747578677599454011", "21131339677253941101883175242832208139150781082220787719353536261069675551205"], ["1", "0"]],
+        "IC": [["3527795369844195554172197723159831261105130023402705803501075808490245373308", "5885873591116991251877003083163610233473425240097428011489248730437747759621", "1"], ["8537569993653485302411448825175154232056839549717967712611870726013097768538", "8069538380702435409016530752768398767159970132563044668721531263945738818644", "1"], ["21526464323725832663882905544083280657770325585151797133383551854196089356032", "8545759555567142326482563981456384114560528812235279370284511019768507753138", "1"]]
+    }"#;
+
+    pub(crate) const FIXTURE_PROOF: &str = r#"{
+        "pi_a": ["16026734324457394006709099908455813841327453089597478339392349214974132713767", "9979793308323154092212877965956664339793851857926579022797240442728602893343", "1"],
+        "pi_b": [["20762863843542035728992314298058812990646088598326444904834697657769690213260", "3556333881807180094052033762306264850372407821724194441073241315730917056457"], ["12177925779771322181292971427158106689229146301257825664386501896161063925763", "16604036701379689541315688143178677770682853808499377107169876802204583180998"], ["1", "0"]],
+        "pi_c": ["582143904226280540909630862403429681417985477382175936562286401979340721988", "15783773570725311797168001305415138656862820387039719606275684336858909105956", "1"],
+        "protocol": "groth16",
+        "curve": "bn128"
+    }"#;
+
+    pub(crate) const FIXTURE_INPUTS: [&str; 2] = ["123456789", "987654321"];
+
+    pub(crate) fn fixture() -> (crate::Verifier, ProofJson, Vec<String>) {
+        let vk: VerificationKeyJson = near_sdk::serde_json::from_str(FIXTURE_VK).unwrap();
+        let proof: ProofJson = near_sdk::serde_json::from_str(FIXTURE_PROOF).unwrap();
+        let inputs = FIXTURE_INPUTS.iter().map(|s| s.to_string()).collect();
+        (crate::Verifier::from_json(&vk).unwrap(), proof, inputs)
+    }
+
+    /// Two more valid proofs under FIXTURE_VK, with their inputs
+    const FIXTURE_BATCH: [(&str, [&str; 2]); 2] = [
+        (
+            r#"{"pi_a": ["5240721337203810155063577104887775964429040310352786870634285698927658009894", "8895618777946819312582035270689922760507554319433213576472857911545059134563", "1"], "pi_b": [["7028142549663052335671754164423500654114137871746782446584409088781790313687", "6563384918192991494701585124638393485919063181656052638870856831841225113315"], ["7819137033332639499909626485990344698372243746833512898919190689218293960756", "7553351249976068359041160938434928604162896955626806564212953342568291546978"], ["1", "0"]], "pi_c": ["16981985554416500587653495374707925933037478511466837086869101229159234600328", "3413376880952866484014222569266683365817274054833428811928850290445686056702", "1"]}"#,
+            ["5", "7"],
+        ),
+        (
+            r#"{"pi_a": ["11036043582047865453564016386824902795310079281107582909180237960108536015445", "15358238014194311822381538861123295394554528691519233917097077491788906195350", "1"], "pi_b": [["17353321617894664079146540800585249746830597841364790646417848257305287762016", "14938362609801859162936808636318890482870346401089285062996324740315527583109"], ["11592945187586245586813825775612526863212088469631749621426540535027245938119", "2910581454432690948576149730019767206308989090080393145537309523769929861343"], ["1", "0"]], "pi_c": ["5466980398376663611808572538867740785353495032030742818115418763708529616077", "15818337321002427430430923635203748548411200940112306820219091658663337541708", "1"]}"#,
+            ["0", "42"],
+        ),
+    ];
+
+    /// Three valid (inputs, proof) pairs under FIXTURE_VK
+    pub(crate) fn batch_fixture() -> (crate::Verifier, Vec<(Vec<U256>, Proof)>) {
+        let parse = |proof_json: &str, inputs: &[&str]| {
+            let proof: ProofJson = near_sdk::serde_json::from_str(proof_json).unwrap();
+            let inputs = inputs.iter().map(|s| U256::from_dec_str(s).unwrap()).collect();
+            (inputs, proof.to_proof().unwrap())
+        };
+        let (verifier, _, _) = fixture();
+        let mut batch = vec![parse(FIXTURE_PROOF, &FIXTURE_INPUTS)];
+        batch.extend(FIXTURE_BATCH.iter().map(|(p, i)| parse(p, i)));
+        (verifier, batch)
+    }
+
+    #[test]
+    fn test_prepared_verifier_matches_verifier() {
+        let (verifier, batch) = batch_fixture();
+        let prepared = verifier.prepare();
+        assert_eq!(prepared.num_inputs(), verifier.vk.num_inputs());
+
+        for (inputs, proof) in &batch {
+            assert_eq!(prepared.verify_checked(inputs, proof), Ok(()));
+
+            // Same bytes reach the host function as with the unprepared key
+            let vk_x = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+            assert_eq!(
+                prepared.pairing_input(proof, &vk_x.to_precompile_bytes()),
+                verifier.pairing_input(proof, &vk_x)
+            );
+        }
+
+        let (inputs, proof) = &batch[2];
+        let mut wrong = inputs.clone();
+        wrong[1] = U256::from(43u64);
+        assert_eq!(prepared.verify_checked(&wrong, proof), Err(VerifyError::PairingFailed));
+        assert_eq!(
+            prepared.verify_checked(&inputs[..1], proof),
+            Err(VerifyError::InputCountMismatch { expected: 2, found: 1 })
+        );
+    }
+
+    #[test]
+    fn test_prepared_verifier_sends_same_payloads() {
+        use crate::backend::{NearBackend, RecordingBackend};
+
+        let (verifier, batch) = batch_fixture();
+        let verifier = crate::Verifier::with_backend(verifier.vk, RecordingBackend::new(NearBackend));
+        let prepared = verifier.prepare();
+
+        let mut zeros = batch[0].clone();
+        zeros.0 = vec![U256::zero(); 2];
+        let mut one_zero = batch[2].clone();
+        one_zero.0[0] = U256::zero();
+
+        for (inputs, proof) in batch.iter().chain([&zeros, &one_zero]) {
+            assert_eq!(
+                prepared.verify_checked(inputs, proof),
+                verifier.verify_checked(inputs, proof)
+            );
+            let calls = verifier.backend().take_calls();
+            assert!(!calls.is_empty());
+            assert_eq!(prepared.backend().take_calls(), calls);
+        }
+    }
+
+    #[test]
+    fn test_prepared_verifier_json_and_borsh() {
+        let vk: VerificationKeyJson = near_sdk::serde_json::from_str(FIXTURE_VK).unwrap();
+        let prepared = PreparedVerifier::from_json_checked(&vk).unwrap();
+
+        // Round-trips through contract state unchanged
+        let bytes = near_sdk::borsh::to_vec(&prepared).unwrap();
+        let restored: PreparedVerifier = near_sdk::borsh::from_slice(&bytes).unwrap();
+
+        let (_, proof, inputs) = fixture();
+        assert_eq!(restored.verify_json_checked(&proof, &inputs), Ok(()));
+        assert!(!restored.verify_json(&proof, &["1".to_string(), "2".to_string()]));
+    }
+
+    #[test]
+    fn test_batch_all_valid() {
+        let (verifier, batch) = batch_fixture();
+        for (inputs, proof) in &batch {
+            assert_eq!(verifier.verify_checked(inputs, proof), Ok(()));
+        }
+        let result = verifier.verify_batch(&batch);
+        assert_eq!(result, BatchResult::AllValid);
+        assert_eq!(result.valid_indices(batch.len()), vec![0, 1, 2]);
+        assert!(verifier.verify_batch(&[]).is_valid());
+    }
+
+    #[test]
+    fn test_batch_reports_invalid_proofs() {
+        let (verifier, mut batch) = batch_fixture();
+        // Valid proof, wrong public input: only the combined check can catch it
+        batch[1].0[0] = U256::from(6u64);
+        let result = verifier.verify_batch(&batch);
+        assert_eq!(
+            result,
+            BatchResult::Failed(vec![Ok(()), Err(VerifyError::PairingFailed), Ok(())])
+        );
+        assert_eq!(result.valid_indices(batch.len()), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_batch_swapped_proofs_fail() {
+        // Each proof is valid for the other's inputs only if the check is per-proof
+        let (verifier, mut batch) = batch_fixture();
+        let inputs0 = batch[0].0.clone();
+        batch[0].0 = batch[2].0.clone();
+        batch[2].0 = inputs0;
+        let result = verifier.verify_batch(&batch);
+        assert_eq!(
+            result,
+            BatchResult::Failed(vec![
+                Err(VerifyError::PairingFailed),
+                Ok(()),
+                Err(VerifyError::PairingFailed),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_batch_malformed_request_excluded() {
+        let (verifier, mut batch) = batch_fixture();
+        batch[2].0.pop();
+        assert_eq!(
+            verifier.verify_batch(&batch),
+            BatchResult::Failed(vec![
+                Ok(()),
+                Ok(()),
+                Err(VerifyError::InputCountMismatch { expected: 2, found: 1 }),
+            ])
+        );
+    }
+
+    /// Run vk_x and the pairing check on both the mocked host and the native
+    /// backend, asserting they agree byte for byte; returns the pairing result
+    fn native_and_host_agree(verifier: &crate::Verifier, inputs: &[U256], proof: &Proof) -> bool {
+        use crate::native;
+        use near_sdk::env;
+
+        let mut multiexp_input = Vec::new();
+        for (ic, x) in verifier.vk.ic[1..].iter().zip(inputs) {
+            multiexp_input.extend_from_slice(&ic.to_precompile_bytes());
+            multiexp_input.extend_from_slice(&x.to_le_bytes());
+        }
+        let sum = env::alt_bn128_g1_multiexp(&multiexp_input);
+        assert_eq!(native::alt_bn128_g1_multiexp(&multiexp_input), sum);
+
+        let mut sum_input = vec![0u8];
+        sum_input.extend_from_slice(&verifier.vk.ic[0].to_precompile_bytes());
+        sum_input.push(0);
+        sum_input.extend_from_slice(&sum);
+        let vk_x = env::alt_bn128_g1_sum(&sum_input);
+        assert_eq!(native::alt_bn128_g1_sum(&sum_input), vk_x);
+
+        let vk_x = G1Point::from_precompile_bytes(&vk_x.try_into().unwrap());
+        let pairing_input = verifier.pairing_input(proof, &vk_x);
+        let result = env::alt_bn128_pairing_check(pairing_input);
+        assert_eq!(native::alt_bn128_pairing_check(pairing_input), result);
+        result
+    }
+
+    #[test]
+    fn test_native_backend_matches_host() {
+        let (verifier, batch) = batch_fixture();
+        for (inputs, proof) in &batch {
+            assert!(native_and_host_agree(&verifier, inputs, proof));
+
+            let mut wrong = inputs.clone();
+            wrong[0] += U256::one();
+            assert!(!native_and_host_agree(&verifier, &wrong, proof));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid G2 point")]
+    fn test_native_pairing_rejects_point_outside_subgroup() {
+        let (verifier, mut batch) = batch_fixture();
+        let (_, mut proof) = batch.remove(0);
+        // On the twist, but not in the order-r subgroup
+        proof.b = G2Point {
+            x: [U256::from(5u64), U256::from(1u64)],
+            y: [
+                U256::from_dec_str("1408319067812452414633879940013592055621582375252427422757364816067320890688").unwrap(),
+                U256::from_dec_str("16043447076329872375887584556683436513165016484529127304231559706947902103147").unwrap(),
+            ],
+        };
+        let vk_x = verifier.vk.ic[0].clone();
+        crate::native::alt_bn128_pairing_check(verifier.pairing_input(&proof, &vk_x));
+    }
+
+    #[test]
+    fn test_recording_backend_captures_payloads() {
+        use crate::backend::{BackendCall, NearBackend, RecordingBackend};
+        use near_sdk::env;
+
+        let (verifier, proof, inputs) = fixture();
+        let vk = verifier.vk;
+        let proof = proof.to_proof().unwrap();
+        let inputs: Vec<U256> = inputs.iter().map(|s| U256::from_dec_str(s).unwrap()).collect();
+
+        let verifier = crate::Verifier::with_backend(vk.clone(), RecordingBackend::new(NearBackend));
+        assert!(verifier.verify(inputs.clone(), proof.clone()));
+
+        // multiexp: IC[i+1] ‖ input[i] for every input
+        let mut multiexp = Vec::new();
+        for (ic, x) in vk.ic[1..].iter().zip(&inputs) {
+            multiexp.extend_from_slice(&ic.to_precompile_bytes());
+            multiexp.extend_from_slice(&x.to_le_bytes());
+        }
+        // g1_sum: IC[0] + multiexp result, both with the "add" sign byte
+        let mut sum = vec![0u8];
+        sum.extend_from_slice(&vk.ic[0].to_precompile_bytes());
+        sum.push(0);
+        sum.extend_from_slice(&env::alt_bn128_g1_multiexp(&multiexp));
+        let vk_x: [u8; 64] = env::alt_bn128_g1_sum(&sum).try_into().unwrap();
+        let pairing = verifier.pairing_input(&proof, &G1Point::from_precompile_bytes(&vk_x));
+
+        assert_eq!(
+            verifier.backend().take_calls(),
+            vec![
+                BackendCall::G1Multiexp(multiexp),
+                BackendCall::G1Sum(sum),
+                BackendCall::PairingCheck(pairing.to_vec()),
+            ]
+        );
+
+        // All-zero inputs: vk_x is IC[0] and only the pairing is called
+        let zeros = vec![U256::zero(); 2];
+        assert!(!verifier.verify(zeros, proof.clone()));
+        let pairing = verifier.pairing_input(&proof, &vk.ic[0]);
+        assert_eq!(verifier.backend().calls(), vec![BackendCall::PairingCheck(pairing.to_vec())]);
+    }
+
+    #[test]
+    fn test_valid_proof_verifies() {
+        let (verifier, proof, inputs) = fixture();
+        assert_eq!(verifier.verify_json_checked(&proof, &inputs), Ok(()));
+        assert!(verifier.verify_json(&proof, &inputs));
+    }
+
+    #[test]
+    fn test_wrong_input_fails_pairing() {
+        let (verifier, proof, mut inputs) = fixture();
+        inputs[1] = "987654322".to_string();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::PairingFailed)
+        );
+        assert!(!verifier.verify_json(&proof, &inputs));
+    }
+
+    #[test]
+    fn test_zero_inputs_skip_multiexp() {
+        let (verifier, proof, _) = fixture();
+        let inputs = vec!["0".to_string(), "0".to_string()];
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::PairingFailed)
+        );
+    }
+
+    #[test]
+    fn test_host_function_payload_layout() {
+        // Pins the exact bytes the verifier hands to NEAR's alt_bn128 host
+        // functions, checked against known answers for the generators
+        let g1 = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+        let two_g1 = G1Point {
+            x: U256::from_dec_str("1368015179489954701390400359078579693043519447331113978918064868415326638035").unwrap(),
+            y: U256::from_dec_str("9918110051302171585080402603319702774565515993150576347155970296011118125764").unwrap(),
+        };
+        let g2 = G2Point {
+            x: [
+                U256::from_dec_str("10857046999023057135944570762232829481370756359578518086990519993285655852781").unwrap(),
+                U256::from_dec_str("11559732032986387107991004021392285783925812861821192530917403151452391805634").unwrap(),
+            ],
+            y: [
+                U256::from_dec_str("8495653923123431417604973247489272438418190587263600148770280649306958101930").unwrap(),
+                U256::from_dec_str("4082367875863433681332203403145435568316851327593401208105741076214120093531").unwrap(),
+            ],
+        };
+
+        // G1: x || y, each 32 bytes little-endian
+        let g1_bytes = g1.to_precompile_bytes();
+        let mut expected = [0u8; 64];
+        expected[0] = 1;
+        expected[32] = 2;
+        assert_eq!(g1_bytes, expected);
+
+        // G2: x0 || x1 || y0 || y1, each 32 bytes little-endian
+        let g2_bytes = g2.to_precompile_bytes();
+        assert_eq!(&g2_bytes[..32], &g2.x[0].to_le_bytes()[..]);
+        assert_eq!(&g2_bytes[32..64], &g2.x[1].to_le_bytes()[..]);
+        assert_eq!(&g2_bytes[64..96], &g2.y[0].to_le_bytes()[..]);
+        assert_eq!(&g2_bytes[96..], &g2.y[1].to_le_bytes()[..]);
+
+        // multiexp item: point (64 bytes) || scalar (32 bytes LE)
+        let mut multiexp = g1_bytes.to_vec();
+        multiexp.extend_from_slice(&U256::from(2u64).to_le_bytes());
+        assert_eq!(multiexp.len(), 96);
+        assert_eq!(near_sdk::env::alt_bn128_g1_multiexp(&multiexp), two_g1.to_precompile_bytes());
+
+        // g1_sum item: sign byte (0 = add, 1 = subtract) || point (64 bytes)
+        let mut sum = vec![0];
+        sum.extend_from_slice(&g1_bytes);
+        sum.push(0);
+        sum.extend_from_slice(&g1_bytes);
+        assert_eq!(sum.len(), 130);
+        assert_eq!(near_sdk::env::alt_bn128_g1_sum(&sum), two_g1.to_precompile_bytes());
+        sum[65] = 1;
+        assert_eq!(near_sdk::env::alt_bn128_g1_sum(&sum), [0u8; 64]);
+
+        // pairing item: G1 (64 bytes) || G2 (128 bytes); e(G1, G2) · e(-G1, G2) = 1
+        let mut pairing = g1_bytes.to_vec();
+        pairing.extend_from_slice(&g2_bytes);
+        pairing.extend_from_slice(&negate_g1(&g1).to_precompile_bytes());
+        pairing.extend_from_slice(&g2_bytes);
+        assert!(near_sdk::env::alt_bn128_pairing_check(&pairing));
+    }
+
+    #[test]
+    fn test_input_count_mismatch() {
+        let (verifier, proof, mut inputs) = fixture();
+        inputs.pop();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::InputCountMismatch { expected: 2, found: 1 })
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_input_rejected() {
+        let (verifier, proof_json, mut inputs) = fixture();
+        let proof = proof_json.to_proof().unwrap();
+        let mut scalars: Vec<U256> = inputs.iter().map(|s| U256::from_dec_str(s).unwrap()).collect();
+        assert!(verifier.verify_checked(&scalars, &proof).is_ok());
+
+        // x + r is the same scalar, but must not be accepted as a second encoding
+        scalars[0] += FR_MODULUS;
+        assert_eq!(
+            verifier.verify_checked(&scalars, &proof),
+            Err(VerifyError::NonCanonicalInput { index: 0 })
+        );
+
+        inputs[1] = (U256::from_dec_str(&inputs[1]).unwrap() + FR_MODULUS).to_string();
+        assert_eq!(
+            verifier.verify_json_checked(&proof_json, &inputs),
+            Err(VerifyError::NonCanonicalInput { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_hex_inputs_verify() {
+        let (verifier, proof, inputs) = fixture();
+        let hex_inputs: Vec<String> = inputs
+            .iter()
+            .map(|s| format!("{:#x}", U256::from_dec_str(s).unwrap()))
+            .collect();
+        assert_eq!(verifier.verify_json_checked(&proof, &hex_inputs), Ok(()));
+    }
+
+    #[test]
+    fn test_precompile_bytes_verify() {
+        let (verifier, proof_json, inputs) = fixture();
+        let proof = proof_json.to_proof().unwrap();
+        let bytes = proof.to_precompile_bytes();
+        let mut scalars: Vec<[u8; 32]> = inputs
+            .iter()
+            .map(|s| U256::from_dec_str(s).unwrap().to_le_bytes())
+            .collect();
+        assert_eq!(verifier.verify_bytes_checked(&bytes, &scalars), Ok(()));
+        let decoded = Proof::from_precompile_bytes(&bytes);
+        assert_eq!((decoded.a.x, decoded.b.y, decoded.c.y), (proof.a.x, proof.b.y, proof.c.y));
+
+        // Contract arguments arrive as unchecked slices
+        assert!(Proof::from_precompile_slice(&bytes).is_ok());
+        assert_eq!(Proof::from_precompile_slice(&bytes[1..]).unwrap_err(), ParseError::UnexpectedEnd);
+        assert_eq!(
+            Proof::from_precompile_slice(&[bytes.as_slice(), &[0]].concat()).unwrap_err(),
+            ParseError::TrailingBytes { count: 1 }
+        );
+        let flat = scalars.concat();
+        assert_eq!(crate::parse_le_inputs(&flat), crate::parse_inputs(&inputs));
+        assert_eq!(
+            crate::parse_le_inputs(&flat[1..]),
+            Err(VerifyError::Parse(ParseError::UnexpectedEnd))
+        );
+
+        // Scalars are little-endian and must be below r, as in the JSON path
+        scalars[1] = (U256::from_dec_str(&inputs[1]).unwrap() + FR_MODULUS).to_le_bytes();
+        assert_eq!(
+            verifier.verify_bytes_checked(&bytes, &scalars),
+            Err(VerifyError::NonCanonicalInput { index: 1 })
+        );
+        scalars[1].reverse();
+        assert!(!verifier.verify_bytes(&bytes, &scalars));
+    }
+
+    #[test]
+    fn test_identity_proof_points_rejected() {
+        let (verifier, mut proof, inputs) = fixture();
+        proof.pi_a = vec!["0".to_string(), "1".to_string(), "0".to_string()];
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::InvalidProofPoint { element: "A", reason: PointError::Identity })
+        );
+
+        let (verifier, mut proof, inputs) = fixture();
+        proof.pi_c = vec!["0".to_string(), "1".to_string(), "0".to_string()];
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::InvalidProofPoint { element: "C", reason: PointError::Identity })
+        );
+    }
+
+    #[test]
+    fn test_off_curve_proof_points_rejected() {
+        // Without the check these would abort inside alt_bn128_pairing_check
+        let (verifier, mut proof, inputs) = fixture();
+        proof.pi_c[1] = "7".to_string();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::InvalidProofPoint { element: "C", reason: PointError::NotOnCurve })
+        );
+
+        // On the twist curve, but outside the order-r subgroup
+        let (verifier, mut proof, inputs) = fixture();
+        proof.pi_b = vec![
+            vec!["5".to_string(), "1".to_string()],
+            vec![
+                "1408319067812452414633879940013592055621582375252427422757364816067320890688".to_string(),
+                "16043447076329872375887584556683436513165016484529127304231559706947902103147".to_string(),
+            ],
+            vec!["1".to_string(), "0".to_string()],
+        ];
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::InvalidProofPoint { element: "B", reason: PointError::NotInSubgroup })
+        );
+    }
+
+    #[test]
+    fn test_verification_key_validation() {
+        let vk: VerificationKeyJson = near_sdk::serde_json::from_str(FIXTURE_VK).unwrap();
+        assert!(crate::Verifier::from_json_checked(&vk).is_ok());
+
+        let mut bad = near_sdk::serde_json::from_str::<VerificationKeyJson>(FIXTURE_VK).unwrap();
+        bad.vk_delta_2[0][0] = "1".to_string();
+        assert_eq!(
+            crate::Verifier::from_json_checked(&bad).unwrap_err(),
+            VerifyError::InvalidVerificationKey(VkError::InvalidPoint {
+                element: "delta",
+                reason: PointError::NotOnCurve,
+            })
+        );
+
+        let mut bad = near_sdk::serde_json::from_str::<VerificationKeyJson>(FIXTURE_VK).unwrap();
+        bad.vk_gamma_2[2] = vec!["0".to_string(), "0".to_string()];
+        assert_eq!(
+            crate::Verifier::from_json_checked(&bad).unwrap_err(),
+            VerifyError::InvalidVerificationKey(VkError::InvalidPoint {
+                element: "gamma",
+                reason: PointError::Identity,
+            })
+        );
+
+        let mut bad = near_sdk::serde_json::from_str::<VerificationKeyJson>(FIXTURE_VK).unwrap();
+        bad.ic[2][1] = "5".to_string();
+        assert_eq!(
+            bad.to_vk().unwrap().validate(),
+            Err(VkError::InvalidIcPoint { index: 2, reason: PointError::NotOnCurve })
+        );
+
+        // Unchecked construction still parses, for keys already known to be good
+        assert!(crate::Verifier::from_json(&bad).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_decimal_input() {
+        let (verifier, proof, mut inputs) = fixture();
+        inputs[1] = "12ab".to_string();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::InvalidInput { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_malformed_proof_json() {
+        let (verifier, mut proof, inputs) = fixture();
+        proof.pi_c.truncate(1);
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::Parse(ParseError::G1MissingCoordinates))
+        );
+    }
+
+    #[test]
+    fn test_missing_ic_point() {
+        let (mut verifier, proof, inputs) = fixture();
+        verifier.vk.ic.clear();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &[]),
+            Err(VerifyError::MissingIcPoint { index: 0 })
+        );
+        assert!(verifier.verify_json_checked(&proof, &inputs).is_err());
+    }
 }
 
 /// Test workflow documentation
@@ -0,0 +1,226 @@
+//! Hashed public inputs: one digest instead of many inputs
+//!
+//! Every public input costs one multiexp term, and the caller has to pass
+//! each of them. The input-compression pattern used by Ethereum rollups
+//! exposes a single public input instead:
+//!
+//! ```text
+//! input = H(x_0 ‖ x_1 ‖ … ‖ x_n) mod r
+//! ```
+//!
+//! with every `x_i` as a 32-byte big-endian word and `H` either SHA-256 or
+//! keccak256. Some circuits instead keep the low 253 bits of the digest
+//! (clearing the top three), which is always below r and cheaper to
+//! constrain; [`InputHasher::truncated`] selects that variant.
+//!
+//! The contract passes the full input list, [`Verifier::verify_hashed`]
+//! computes the digest with the NEAR hash host functions and verifies it
+//! against a 1-input verification key. The full inputs are hashed as plain
+//! 256-bit words: they need not be field elements.
+//!
+//! ```rust,ignore
+//! const HASHER: InputHasher = InputHasher::sha256().truncated();
+//!
+//! self.verifier.verify_hashed_json_checked(HASHER, &proof, &public_inputs)?;
+//! ```
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::backend::Bn254Backend;
+use crate::error::VerifyError;
+use crate::transcript::hash_to_field;
+use crate::types::{parse_u256, Proof, ProofJson, U256};
+use crate::verifier::Verifier;
+
+/// Hash function for [`InputHasher`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum InputHash {
+    Sha256,
+    Keccak256,
+}
+
+/// How the full public inputs are compressed into the circuit's one input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct InputHasher {
+    pub hash: InputHash,
+    /// Keep the low 253 bits of the digest instead of reducing it mod r
+    pub truncate: bool,
+}
+
+impl InputHasher {
+    /// `sha256(inputs) mod r`
+    pub const fn sha256() -> Self {
+        Self { hash: InputHash::Sha256, truncate: false }
+    }
+
+    /// `keccak256(inputs) mod r`
+    pub const fn keccak256() -> Self {
+        Self { hash: InputHash::Keccak256, truncate: false }
+    }
+
+    /// The same hash, truncated to its low 253 bits
+    pub const fn truncated(self) -> Self {
+        Self { truncate: true, ..self }
+    }
+
+    /// The public input for an already-packed preimage
+    pub fn digest<B: Bn254Backend>(&self, backend: &B, preimage: &[u8]) -> U256 {
+        let mut hash = match self.hash {
+            InputHash::Sha256 => backend.sha256(preimage),
+            InputHash::Keccak256 => backend.keccak256(preimage),
+        };
+        if self.truncate {
+            hash[0] &= 0x1f;
+            U256::from_be_bytes(&hash)
+        } else {
+            hash_to_field(&hash)
+        }
+    }
+
+    /// The public input for `inputs`, each packed as a 32-byte big-endian word
+    pub fn digest_inputs<B: Bn254Backend>(&self, backend: &B, inputs: &[U256]) -> U256 {
+        let preimage: Vec<u8> = inputs.iter().flat_map(|x| x.to_be_bytes()).collect();
+        self.digest(backend, &preimage)
+    }
+}
+
+impl<B: Bn254Backend> Verifier<B> {
+    /// Verify a proof whose one public input is the hash of `inputs`
+    pub fn verify_hashed(&self, hasher: InputHasher, inputs: &[U256], proof: &Proof) -> bool {
+        self.verify_hashed_checked(hasher, inputs, proof).is_ok()
+    }
+
+    /// Verify a proof whose one public input is the hash of `inputs`,
+    /// reporting the reason for any failure
+    ///
+    /// **Gas**: one hash of `32 · inputs.len()` bytes, then a 1-input
+    /// verification.
+    pub fn verify_hashed_checked(
+        &self,
+        hasher: InputHasher,
+        inputs: &[U256],
+        proof: &Proof,
+    ) -> Result<(), VerifyError> {
+        let digest = hasher.digest_inputs(self.backend(), inputs);
+        self.verify_checked(&[digest], proof)
+    }
+
+    /// Verify a proof whose one public input is the hash of a packed preimage
+    ///
+    /// For circuits that hash something other than 32-byte words, e.g.
+    /// `abi.encodePacked` data.
+    pub fn verify_hashed_bytes_checked(
+        &self,
+        hasher: InputHasher,
+        preimage: &[u8],
+        proof: &Proof,
+    ) -> Result<(), VerifyError> {
+        let digest = hasher.digest(self.backend(), preimage);
+        self.verify_checked(&[digest], proof)
+    }
+
+    /// [`Verifier::verify_hashed`] for a snarkjs proof with decimal or hex inputs
+    pub fn verify_hashed_json(&self, hasher: InputHasher, proof_json: &ProofJson, inputs: &[String]) -> bool {
+        self.verify_hashed_json_checked(hasher, proof_json, inputs).is_ok()
+    }
+
+    /// [`Verifier::verify_hashed_checked`] for a snarkjs proof with decimal or
+    /// hex inputs; any 256-bit value is accepted
+    pub fn verify_hashed_json_checked(
+        &self,
+        hasher: InputHasher,
+        proof_json: &ProofJson,
+        inputs: &[String],
+    ) -> Result<(), VerifyError> {
+        let proof = proof_json.to_proof()?;
+        let inputs = parse_words(inputs)?;
+        self.verify_hashed_checked(hasher, &inputs, &proof)
+    }
+}
+
+/// Parse decimal or `0x` hex inputs as 256-bit words, without the `< r`
+/// check of [`parse_inputs`](crate::verifier::parse_inputs)
+pub fn parse_words(inputs: &[String]) -> Result<Vec<U256>, VerifyError> {
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(index, s)| parse_u256(s).map_err(|_| VerifyError::InvalidInput { index }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::DefaultBackend;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+    use crate::types::FR_MODULUS;
+    use crate::verifier::{compute_vk_x, parse_inputs};
+
+    /// keccak256 and sha256 of 32 zero bytes
+    const KECCAK_ZERO: &str = "290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563";
+    const SHA256_ZERO: &str = "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925";
+
+    /// The fixture proof under a 1-input key that accepts `hasher(full)`
+    ///
+    /// IC'[0] = vk_x - d·IC[1], so the pairing sees the same vk_x as the
+    /// original 2-input verification.
+    fn hashed_verifier(hasher: InputHasher, full: &[U256]) -> Verifier {
+        let (verifier, _, inputs) = fixture();
+        let backend = DefaultBackend::default();
+        let vk = &verifier.vk;
+        let vk_x = compute_vk_x(&backend, 3, &parse_inputs(&inputs).unwrap(), |i| vk.ic[i].clone()).unwrap();
+        let d = hasher.digest_inputs(&backend, full);
+        let ic0 = compute_vk_x(&backend, 2, &[FR_MODULUS - d], |i| {
+            if i == 0 { vk_x.clone() } else { vk.ic[1].clone() }
+        })
+        .unwrap();
+
+        let mut vk = vk.clone();
+        vk.ic = vec![ic0, vk.ic[1].clone()];
+        Verifier::new(vk)
+    }
+
+    #[test]
+    fn test_digest_vectors() {
+        let backend = DefaultBackend::default();
+        let zero = [U256::zero()];
+        let keccak = U256::from_str_radix(KECCAK_ZERO, 16).unwrap();
+        let sha = U256::from_str_radix(SHA256_ZERO, 16).unwrap();
+
+        // keccak(0) < r, sha256(0) > r
+        assert_eq!(InputHasher::keccak256().digest_inputs(&backend, &zero), keccak);
+        assert_eq!(InputHasher::sha256().digest_inputs(&backend, &zero), sha % FR_MODULUS);
+
+        let low_253 = (U256::one() << 253) - 1;
+        assert_eq!(InputHasher::keccak256().truncated().digest_inputs(&backend, &zero), keccak & low_253);
+        assert_eq!(InputHasher::sha256().truncated().digest_inputs(&backend, &zero), sha & low_253);
+    }
+
+    #[test]
+    fn test_verify_hashed() {
+        // Full inputs are plain words, not necessarily field elements
+        let full = [U256::from(7u64), U256::MAX, FR_MODULUS];
+        let json: Vec<String> = full.iter().map(|x| format!("{:#x}", x)).collect();
+        let (_, proof, _) = fixture();
+
+        for hasher in [InputHasher::sha256(), InputHasher::keccak256().truncated()] {
+            let verifier = hashed_verifier(hasher, &full);
+            assert_eq!(verifier.verify_hashed_json_checked(hasher, &proof, &json), Ok(()));
+
+            let preimage: Vec<u8> = full.iter().flat_map(|x| x.to_be_bytes()).collect();
+            let proof = proof.to_proof().unwrap();
+            assert_eq!(verifier.verify_hashed_bytes_checked(hasher, &preimage, &proof), Ok(()));
+            assert!(!verifier.verify_hashed(hasher, &full[..2], &proof));
+        }
+
+        let verifier = hashed_verifier(InputHasher::sha256(), &full);
+        assert!(!verifier.verify_hashed_json(InputHasher::sha256().truncated(), &proof, &json));
+        assert_eq!(
+            verifier.verify_hashed_json_checked(InputHasher::sha256(), &proof, &["0x".to_string()]),
+            Err(VerifyError::InvalidInput { index: 0 })
+        );
+    }
+}
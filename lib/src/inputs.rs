@@ -0,0 +1,349 @@
+//! Typed public inputs
+//!
+//! Building the `Vec<U256>` for [`Verifier::verify`] by hand means
+//! remembering the circuit's signal order at every call site.
+//! `#[derive(PublicInputs)]` maps a struct to its ordered inputs instead:
+//! one input per field, in declaration order.
+//!
+//! ```rust,ignore
+//! use near_groth16_verifier::PublicInputs;
+//!
+//! #[derive(PublicInputs)]
+//! #[near(serializers = [json])]
+//! pub struct WithdrawInputs {
+//!     pub nullifier: U256,
+//!     pub root: U256,
+//!     #[field(encoding = "poseidon_account")]
+//!     pub recipient: AccountId,
+//!     pub amount: U128,
+//! }
+//!
+//! // in `new`: reject a key with a different number of inputs
+//! WithdrawInputs::check_vk(&verifier.vk)?;
+//!
+//! // in `withdraw(&mut self, proof: ProofJson, inputs: WithdrawInputs)`:
+//! self.verifier.verify_inputs(&inputs, &proof.to_proof()?)?;
+//! ```
+//!
+//! Fields without an attribute use the type's [`InputField`] encoding:
+//! `U256` as is, integers (`u64`, `u128`, `U128`, `U64`) and `bool` as their
+//! value, `[u8; 32]` as a big-endian integer. `#[field(encoding = "...")]`
+//! picks one of the [`encoding`] types instead:
+//!
+//! | name | type | decodable |
+//! |------|------|-----------|
+//! | `le` | `[u8; 32]` | yes |
+//! | `poseidon_account` | `AccountId` | no |
+//! | `sha256_account` | `AccountId` | no |
+//!
+//! The derive also implements [`FromPublicInputs`] (inputs back to the
+//! struct) unless a field uses a one-way encoding.
+
+use near_sdk::json_types::{U128, U64};
+use near_sdk::AccountId;
+
+use crate::backend::Bn254Backend;
+use crate::error::VerifyError;
+use crate::types::{Proof, VerificationKey, U256};
+use crate::verifier::{parse_inputs, Verifier};
+
+/// A struct whose fields are a circuit's public inputs, in order
+///
+/// Implement with `#[derive(PublicInputs)]`.
+pub trait PublicInputs {
+    /// Number of public inputs (one per field)
+    const NUM_INPUTS: usize;
+
+    /// The inputs in circuit order
+    fn to_inputs(&self) -> Vec<U256>;
+
+    /// The inputs as decimal strings, for [`Verifier::verify_json`]
+    fn to_input_strings(&self) -> Vec<String> {
+        self.to_inputs().iter().map(U256::to_string).collect()
+    }
+
+    /// Check that `vk` expects exactly one input per field
+    fn check_vk(vk: &VerificationKey) -> Result<(), VerifyError> {
+        if vk.num_inputs() != Self::NUM_INPUTS {
+            return Err(VerifyError::InputCountMismatch {
+                expected: vk.num_inputs(),
+                found: Self::NUM_INPUTS,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Rebuild a [`PublicInputs`] struct from its inputs
+///
+/// Derived together with [`PublicInputs`] when every field is decodable.
+pub trait FromPublicInputs: PublicInputs + Sized {
+    /// Fails on a wrong input count, or with [`VerifyError::InvalidInput`]
+    /// if an input is not the encoding of any value of its field
+    fn from_inputs(inputs: &[U256]) -> Result<Self, VerifyError>;
+
+    /// [`FromPublicInputs::from_inputs`] for snarkjs `public.json` strings
+    fn from_input_strings(inputs: &[String]) -> Result<Self, VerifyError> {
+        Self::from_inputs(&parse_inputs(inputs)?)
+    }
+}
+
+/// A field type with a default public-input encoding
+pub trait InputField: Sized {
+    fn to_input(&self) -> U256;
+
+    /// `None` if `input` does not encode any value of this type
+    fn from_input(input: U256) -> Option<Self>;
+}
+
+impl InputField for U256 {
+    fn to_input(&self) -> U256 {
+        *self
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        Some(input)
+    }
+}
+
+impl InputField for u64 {
+    fn to_input(&self) -> U256 {
+        U256::from(*self)
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        (input.bits() <= 64).then(|| input.low_u64())
+    }
+}
+
+impl InputField for u128 {
+    fn to_input(&self) -> U256 {
+        U256::from(*self)
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        (input.bits() <= 128).then(|| input.low_u128())
+    }
+}
+
+impl InputField for U64 {
+    fn to_input(&self) -> U256 {
+        self.0.to_input()
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        u64::from_input(input).map(U64)
+    }
+}
+
+impl InputField for U128 {
+    fn to_input(&self) -> U256 {
+        self.0.to_input()
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        u128::from_input(input).map(U128)
+    }
+}
+
+impl InputField for bool {
+    fn to_input(&self) -> U256 {
+        U256::from(*self as u8)
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        match input.as_u64() {
+            _ if input.bits() > 1 => None,
+            value => Some(value == 1),
+        }
+    }
+}
+
+/// Big-endian; values `>= r` are rejected by the verifier
+impl InputField for [u8; 32] {
+    fn to_input(&self) -> U256 {
+        U256::from_be_bytes(self)
+    }
+
+    fn from_input(input: U256) -> Option<Self> {
+        Some(input.to_be_bytes())
+    }
+}
+
+/// Named encodings for `#[field(encoding = "...")]`
+///
+/// The attribute value is the snake_case name of a type in this module.
+pub mod encoding {
+    use super::*;
+
+    /// Encodes a `T` field as one public input
+    pub trait Encoding<T> {
+        fn encode(value: &T) -> U256;
+    }
+
+    /// An [`Encoding`] that can be reversed
+    pub trait Decoding<T>: Encoding<T> {
+        fn decode(input: U256) -> Option<T>;
+    }
+
+    /// `[u8; 32]` as a little-endian integer
+    pub struct Le;
+
+    impl Encoding<[u8; 32]> for Le {
+        fn encode(value: &[u8; 32]) -> U256 {
+            U256::from_le_bytes(value)
+        }
+    }
+
+    impl Decoding<[u8; 32]> for Le {
+        fn decode(input: U256) -> Option<[u8; 32]> {
+            Some(input.to_le_bytes())
+        }
+    }
+
+    /// Poseidon hash of an account id
+    ///
+    /// The id's bytes are split into 31-byte chunks (the last one
+    /// zero-padded on the right), each read as a big-endian field element,
+    /// and folded with the 2-input Poseidon starting from the byte length:
+    ///
+    /// ```text
+    /// h = len
+    /// h = Poseidon(h, chunk_i)   for each chunk
+    /// ```
+    pub struct PoseidonAccount;
+
+    impl Encoding<AccountId> for PoseidonAccount {
+        fn encode(value: &AccountId) -> U256 {
+            use crate::poseidon::{poseidon_t3_opt, Fr};
+
+            let bytes = value.as_bytes();
+            let mut h = Fr::from_u64(bytes.len() as u64);
+            for chunk in bytes.chunks(31) {
+                let mut word = [0u8; 32];
+                word[1..1 + chunk.len()].copy_from_slice(chunk);
+                h = poseidon_t3_opt(&[h, Fr { limbs: U256::from_be_bytes(&word).0 }]);
+            }
+            U256(h.limbs)
+        }
+    }
+
+    /// SHA-256 of an account id, truncated to its low 253 bits
+    pub struct Sha256Account;
+
+    impl Encoding<AccountId> for Sha256Account {
+        fn encode(value: &AccountId) -> U256 {
+            let mut hash = near_sdk::env::sha256_array(value.as_bytes());
+            hash[0] &= 0x1f;
+            U256::from_be_bytes(&hash)
+        }
+    }
+}
+
+impl<B: Bn254Backend> Verifier<B> {
+    /// Verify a proof against typed public inputs
+    pub fn verify_inputs<T: PublicInputs>(&self, inputs: &T, proof: &Proof) -> Result<(), VerifyError> {
+        self.verify_checked(&inputs.to_inputs(), proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encoding::{Encoding, PoseidonAccount};
+    use super::*;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+    use crate::poseidon::poseidon_hash2;
+    use crate::PublicInputs;
+
+    #[derive(PublicInputs, Debug, PartialEq)]
+    struct Fixture {
+        a: u64,
+        b: U256,
+    }
+
+    #[derive(PublicInputs, Debug, PartialEq)]
+    struct Mixed {
+        nullifier: [u8; 32],
+        #[field(encoding = "le")]
+        leaf: [u8; 32],
+        amount: U128,
+        flag: bool,
+    }
+
+    #[derive(PublicInputs)]
+    struct Withdraw {
+        nullifier: U256,
+        #[field(encoding = "poseidon_account")]
+        recipient: AccountId,
+        #[field(encoding = "sha256_account")]
+        relayer: AccountId,
+    }
+
+    #[test]
+    fn test_derived_inputs_verify() {
+        let (verifier, proof, inputs) = fixture();
+        let typed = Fixture::from_input_strings(&inputs).unwrap();
+        assert_eq!(typed.to_input_strings(), inputs);
+        assert_eq!(Fixture::check_vk(&verifier.vk), Ok(()));
+        assert_eq!(verifier.verify_inputs(&typed, &proof.to_proof().unwrap()), Ok(()));
+
+        assert_eq!(
+            Withdraw::check_vk(&verifier.vk),
+            Err(VerifyError::InputCountMismatch { expected: 2, found: 3 })
+        );
+    }
+
+    #[test]
+    fn test_field_encodings_roundtrip() {
+        let mut leaf = [0u8; 32];
+        leaf[0] = 1;
+        let value = Mixed { nullifier: leaf, leaf, amount: U128(5), flag: true };
+
+        let inputs = value.to_inputs();
+        assert_eq!(inputs[0], U256::one() << 248);
+        assert_eq!(inputs[1], U256::one());
+        assert_eq!(Mixed::from_inputs(&inputs).unwrap(), value);
+
+        let mut bad = inputs.clone();
+        bad[3] = U256::from(2u64);
+        assert_eq!(Mixed::from_inputs(&bad), Err(VerifyError::InvalidInput { index: 3 }));
+        assert_eq!(
+            Mixed::from_inputs(&inputs[..3]),
+            Err(VerifyError::InputCountMismatch { expected: 4, found: 3 })
+        );
+    }
+
+    #[test]
+    fn test_account_encodings() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let value = Withdraw { nullifier: U256::one(), recipient: alice.clone(), relayer: alice.clone() };
+        let inputs = value.to_inputs();
+
+        // "alice.near" fits one chunk: Poseidon(10, chunk)
+        let chunk = U256::from_big_endian(b"alice.near") << (8 * 21);
+        assert_eq!(inputs[1].to_string(), poseidon_hash2("10", &chunk.to_string()));
+        assert_eq!(inputs[1], PoseidonAccount::encode(&alice));
+        assert!(inputs[2].bits() <= 253);
+    }
+
+    /// Pinned in sdk/test/inputs.test.ts as well: a partial chunk, exactly
+    /// one full chunk, three chunks
+    #[test]
+    fn test_poseidon_account_vectors() {
+        let vectors = [
+            ("alice.near", "8778125842548807958193322087000312670245895272044483519524001245225809719431"),
+            (
+                "abcdefghijklmnopqrstuvwxyz.near",
+                "18653267240135196620277531259493146812472663294260401187258426703737034071678",
+            ),
+            (
+                "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                "10539481583516436834325637726350711868622939599347597841586045252112824219914",
+            ),
+        ];
+        for (account, encoded) in vectors {
+            let account: AccountId = account.parse().unwrap();
+            assert_eq!(PoseidonAccount::encode(&account).to_string(), encoded, "{account}");
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Named roles for a circuit's public inputs
+//!
+//! Contracts used to read public inputs by position: `inputs[0]` is the
+//! nullifier, `inputs[1]` a commitment, and so on. If the circuit's signals
+//! are reordered, a contract written that way keeps verifying proofs but
+//! checks the wrong values. A [`PublicInputLayout`] names the role of every
+//! position once, at init:
+//!
+//! - [`PublicInputLayout::check_vk`] fails unless the layout has exactly one
+//!   role per verification key input. It compares counts only: a layout
+//!   that names the right number of inputs in the wrong order still passes
+//! - [`PublicInputLayout::require`] fails if a role the contract relies on
+//!   is missing
+//! - [`PublicInputLayout::bind`] gives typed accessors over the inputs of
+//!   one proof
+//!
+//! In JSON a layout is an array of roles, e.g.
+//! `["nullifier", "root", {"custom": "vote"}]`. A circuit that spends or
+//! creates several notes repeats the role, e.g. a 2-in/2-out transfer is
+//! `["root", "nullifier", "nullifier", "commitment", "commitment"]`; read
+//! those with [`LayoutInputs::nullifiers`] and [`LayoutInputs::commitments`].
+//!
+//! ```rust,ignore
+//! // in `new`:
+//! let layout = PublicInputLayout::new(vec![InputRole::Nullifier, InputRole::Root])?;
+//! layout.check_vk(&verifier.vk)?;
+//!
+//! // in a method:
+//! let inputs = self.layout.bind(&public_inputs)?;
+//! let nullifier = inputs.nullifier().expect("required at init");
+//! ```
+
+use std::fmt;
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::error::{LayoutError, VerifyError};
+use crate::types::{VerificationKey, U256};
+
+/// What a public input means to the contract
+#[derive(Clone, Debug, PartialEq, Eq, Hash, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "snake_case")]
+pub enum InputRole {
+    /// Spent-note or one-time-use marker
+    Nullifier,
+    /// Merkle root the proof was made against
+    Root,
+    /// New note or leaf created by the proof
+    Commitment,
+    /// Account receiving funds (as a field element)
+    Recipient,
+    /// Amount moved
+    Amount,
+    /// Asset id of the amount
+    Asset,
+    /// Relayer fee
+    Fee,
+    /// Account paid the fee (as a field element)
+    Relayer,
+    /// Scope of the nullifier, e.g. a poll id
+    ExternalNullifier,
+    /// Any other application-defined value
+    Custom(String),
+}
+
+impl InputRole {
+    /// `true` for roles a layout may name more than once: one nullifier per
+    /// spent note, one commitment per created note
+    pub fn is_repeatable(&self) -> bool {
+        matches!(self, InputRole::Nullifier | InputRole::Commitment)
+    }
+}
+
+impl fmt::Display for InputRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputRole::Nullifier => write!(f, "nullifier"),
+            InputRole::Root => write!(f, "root"),
+            InputRole::Commitment => write!(f, "commitment"),
+            InputRole::Recipient => write!(f, "recipient"),
+            InputRole::Amount => write!(f, "amount"),
+            InputRole::Asset => write!(f, "asset"),
+            InputRole::Fee => write!(f, "fee"),
+            InputRole::Relayer => write!(f, "relayer"),
+            InputRole::ExternalNullifier => write!(f, "external_nullifier"),
+            InputRole::Custom(name) => write!(f, "custom({})", name),
+        }
+    }
+}
+
+/// The role of every public input, in circuit order
+///
+/// Nullifiers and commitments may repeat; every other role appears at most
+/// once. Deserializing from JSON applies the same check as
+/// [`PublicInputLayout::new`].
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", try_from = "Vec<InputRole>", into = "Vec<InputRole>")]
+pub struct PublicInputLayout {
+    roles: Vec<InputRole>,
+}
+
+impl PublicInputLayout {
+    /// Layout with `roles[i]` as the role of input `i`
+    pub fn new(roles: Vec<InputRole>) -> Result<Self, LayoutError> {
+        for (i, role) in roles.iter().enumerate() {
+            if !role.is_repeatable() && roles[..i].contains(role) {
+                return Err(LayoutError::DuplicateRole { role: role.to_string() });
+            }
+        }
+        Ok(Self { roles })
+    }
+
+    /// Roles in circuit order
+    pub fn roles(&self) -> &[InputRole] {
+        &self.roles
+    }
+
+    /// Number of public inputs
+    pub fn len(&self) -> usize {
+        self.roles.len()
+    }
+
+    /// `true` for a circuit without public inputs
+    pub fn is_empty(&self) -> bool {
+        self.roles.is_empty()
+    }
+
+    /// Position of `role`, if the circuit exposes it; the first one for a
+    /// repeated role
+    pub fn position(&self, role: &InputRole) -> Option<usize> {
+        self.roles.iter().position(|r| r == role)
+    }
+
+    /// Every position of `role`, in circuit order
+    pub fn positions(&self, role: &InputRole) -> Vec<usize> {
+        self.roles.iter().enumerate().filter(|(_, r)| *r == role).map(|(i, _)| i).collect()
+    }
+
+    /// Position of a role the contract cannot work without
+    pub fn require(&self, role: &InputRole) -> Result<usize, LayoutError> {
+        self.position(role)
+            .ok_or_else(|| LayoutError::MissingRole { role: role.to_string() })
+    }
+
+    /// Position of a role the contract cannot work without and reads once,
+    /// e.g. the nullifier of a vote
+    pub fn require_unique(&self, role: &InputRole) -> Result<usize, LayoutError> {
+        let position = self.require(role)?;
+        if self.positions(role).len() > 1 {
+            return Err(LayoutError::DuplicateRole { role: role.to_string() });
+        }
+        Ok(position)
+    }
+
+    /// Check that the layout describes every input of `vk`, no more, no less
+    ///
+    /// Only the number of inputs is compared: a verification key carries no
+    /// signal names, so a layout in the wrong order is not detected here.
+    pub fn check_vk(&self, vk: &VerificationKey) -> Result<(), LayoutError> {
+        if self.len() != vk.num_inputs() {
+            return Err(LayoutError::InputCountMismatch {
+                layout: self.len(),
+                vk: vk.num_inputs(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Typed view of one proof's inputs
+    pub fn bind<'a>(&'a self, inputs: &'a [U256]) -> Result<LayoutInputs<'a>, VerifyError> {
+        if inputs.len() != self.len() {
+            return Err(VerifyError::InputCountMismatch {
+                expected: self.len(),
+                found: inputs.len(),
+            });
+        }
+        Ok(LayoutInputs { layout: self, inputs })
+    }
+}
+
+impl TryFrom<Vec<InputRole>> for PublicInputLayout {
+    type Error = LayoutError;
+
+    fn try_from(roles: Vec<InputRole>) -> Result<Self, LayoutError> {
+        Self::new(roles)
+    }
+}
+
+impl From<PublicInputLayout> for Vec<InputRole> {
+    fn from(layout: PublicInputLayout) -> Self {
+        layout.roles
+    }
+}
+
+/// Public inputs of one proof, read by role; see [`PublicInputLayout::bind`]
+#[derive(Clone, Copy, Debug)]
+pub struct LayoutInputs<'a> {
+    layout: &'a PublicInputLayout,
+    inputs: &'a [U256],
+}
+
+impl<'a> LayoutInputs<'a> {
+    /// All inputs in circuit order, as passed to the verifier
+    pub fn as_slice(&self) -> &'a [U256] {
+        self.inputs
+    }
+
+    /// The input with `role`, if the circuit exposes it; the first one for
+    /// a repeated role
+    pub fn get(&self, role: &InputRole) -> Option<U256> {
+        self.layout.position(role).map(|i| self.inputs[i])
+    }
+
+    /// Every input with `role`, in circuit order
+    pub fn get_all(&self, role: &InputRole) -> Vec<U256> {
+        self.layout.positions(role).into_iter().map(|i| self.inputs[i]).collect()
+    }
+
+    /// First nullifier; see [`nullifiers`](Self::nullifiers)
+    pub fn nullifier(&self) -> Option<U256> {
+        self.get(&InputRole::Nullifier)
+    }
+
+    pub fn root(&self) -> Option<U256> {
+        self.get(&InputRole::Root)
+    }
+
+    /// First commitment; see [`commitments`](Self::commitments)
+    pub fn commitment(&self) -> Option<U256> {
+        self.get(&InputRole::Commitment)
+    }
+
+    /// Every nullifier, for circuits that spend several notes
+    pub fn nullifiers(&self) -> Vec<U256> {
+        self.get_all(&InputRole::Nullifier)
+    }
+
+    /// Every commitment, for circuits that create several notes
+    pub fn commitments(&self) -> Vec<U256> {
+        self.get_all(&InputRole::Commitment)
+    }
+
+    pub fn recipient(&self) -> Option<U256> {
+        self.get(&InputRole::Recipient)
+    }
+
+    pub fn amount(&self) -> Option<U256> {
+        self.get(&InputRole::Amount)
+    }
+
+    pub fn asset(&self) -> Option<U256> {
+        self.get(&InputRole::Asset)
+    }
+
+    pub fn fee(&self) -> Option<U256> {
+        self.get(&InputRole::Fee)
+    }
+
+    pub fn relayer(&self) -> Option<U256> {
+        self.get(&InputRole::Relayer)
+    }
+
+    pub fn external_nullifier(&self) -> Option<U256> {
+        self.get(&InputRole::ExternalNullifier)
+    }
+
+    /// The input with role `Custom(name)`
+    pub fn custom(&self, name: &str) -> Option<U256> {
+        self.get(&InputRole::Custom(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+
+    #[test]
+    fn test_layout_checked_against_vk() {
+        let (verifier, _, _) = fixture();
+
+        let layout = PublicInputLayout::new(vec![InputRole::Nullifier, InputRole::Root]).unwrap();
+        assert_eq!(layout.check_vk(&verifier.vk), Ok(()));
+        assert_eq!(layout.require(&InputRole::Root), Ok(1));
+        assert_eq!(
+            layout.require(&InputRole::Commitment),
+            Err(LayoutError::MissingRole { role: "commitment".to_string() })
+        );
+
+        let short = PublicInputLayout::new(vec![InputRole::Nullifier]).unwrap();
+        assert_eq!(
+            short.check_vk(&verifier.vk),
+            Err(LayoutError::InputCountMismatch { layout: 1, vk: 2 })
+        );
+        assert_eq!(
+            PublicInputLayout::new(vec![InputRole::Root, InputRole::Root]),
+            Err(LayoutError::DuplicateRole { role: "root".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_repeated_nullifiers_and_commitments() {
+        // transfer.circom: root, 2 input nullifiers, 2 output commitments
+        let layout = PublicInputLayout::new(vec![
+            InputRole::Root,
+            InputRole::Nullifier,
+            InputRole::Nullifier,
+            InputRole::Commitment,
+            InputRole::Commitment,
+        ])
+        .unwrap();
+        let inputs: Vec<U256> = (10u64..15).map(U256::from).collect();
+
+        let bound = layout.bind(&inputs).unwrap();
+        assert_eq!(bound.nullifiers(), vec![U256::from(11u64), U256::from(12u64)]);
+        assert_eq!(bound.commitments(), vec![U256::from(13u64), U256::from(14u64)]);
+        assert_eq!(bound.nullifier(), Some(U256::from(11u64)));
+        assert_eq!(bound.get_all(&InputRole::Asset), vec![]);
+
+        assert_eq!(layout.require(&InputRole::Nullifier), Ok(1));
+        assert_eq!(
+            layout.require_unique(&InputRole::Nullifier),
+            Err(LayoutError::DuplicateRole { role: "nullifier".to_string() })
+        );
+        assert_eq!(layout.require_unique(&InputRole::Root), Ok(0));
+    }
+
+    #[test]
+    fn test_bind_reads_by_role() {
+        let layout = PublicInputLayout::new(vec![
+            InputRole::ExternalNullifier,
+            InputRole::Nullifier,
+            InputRole::Custom("vote".to_string()),
+        ])
+        .unwrap();
+        let inputs = [U256::from(7u64), U256::from(42u64), U256::one()];
+
+        let bound = layout.bind(&inputs).unwrap();
+        assert_eq!(bound.nullifier(), Some(U256::from(42u64)));
+        assert_eq!(bound.external_nullifier(), Some(U256::from(7u64)));
+        assert_eq!(bound.custom("vote"), Some(U256::one()));
+        assert_eq!(bound.root(), None);
+        assert_eq!(bound.as_slice(), &inputs);
+
+        assert_eq!(
+            layout.bind(&inputs[..2]).unwrap_err(),
+            VerifyError::InputCountMismatch { expected: 3, found: 2 }
+        );
+    }
+
+    #[test]
+    fn test_layout_json() {
+        let json = r#"["nullifier","root",{"custom":"vote"}]"#;
+        let layout: PublicInputLayout = near_sdk::serde_json::from_str(json).unwrap();
+        assert_eq!(layout.position(&InputRole::Custom("vote".to_string())), Some(2));
+        assert_eq!(near_sdk::serde_json::to_string(&layout).unwrap(), json);
+
+        assert!(near_sdk::serde_json::from_str::<PublicInputLayout>(r#"["fee","fee"]"#).is_err());
+        let two_in: PublicInputLayout = near_sdk::serde_json::from_str(r#"["nullifier","nullifier"]"#).unwrap();
+        assert_eq!(two_in.positions(&InputRole::Nullifier), vec![0, 1]);
+    }
+}
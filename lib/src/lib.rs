@@ -6,7 +6,36 @@
 //! ## Features
 //! - Uses NEAR's native `alt_bn128` precompiles for efficient on-chain verification
 //! - Compatible with snarkjs JSON proof format
+//! - [`Verifier::verify_bytes`] for proofs and inputs already in the precompile
+//!   layout, skipping decimal parsing
 //! - NEAR SDK 5.x compatible
+//! - `standalone` feature: pure-Rust BN254 backend, so the same [`Verifier`]
+//!   verifies proofs outside the NEAR runtime (CLI, relayers, `cargo test`)
+//! - [`PlonkVerifier`] for snarkjs `plonk` proofs (universal setup, no
+//!   per-circuit ceremony)
+//! - [`FflonkVerifier`] for snarkjs `fflonk` proofs (one pairing, constant-size proof)
+//! - [`CommitmentVerifier`] for gnark Groth16 proofs with Pedersen commitments
+//! - [`CompactProof`]: 128-byte proofs with compressed points, for small
+//!   transaction arguments
+//! - [`VerifierRegistry`]: keys for several circuits in contract storage,
+//!   with versioning and deprecation
+//! - [`StoredVerifier`]: a key read lazily from storage, loading only the IC
+//!   points of non-zero inputs
+//! - [`Verifier::verify_hashed`] for circuits whose one public input is a
+//!   SHA-256 or keccak256 digest of the full input list
+//! - [`PublicInputLayout`]: named roles for public inputs, checked against
+//!   the verification key at init
+//! - `#[derive(PublicInputs)]`: typed structs for a circuit's public inputs,
+//!   see [`inputs`]
+//! - `#[zk_method]`: proof parsing, verification and nullifier bookkeeping
+//!   in front of a contract method, see [`method`]
+//! - [`IncrementalMerkleTree`]: on-chain Poseidon commitment tree with a
+//!   history of recent roots; [`MerkleTree`] builds membership witnesses
+//!   off-chain
+//! - [`SparseMerkleTree`]: circomlib-compatible sparse Merkle tree with
+//!   membership and non-membership proofs, in memory or contract storage
+//! - [`gnark`] decoders for gnark's binary proof and verifying key encodings
+//! - [`arkworks`] `CanonicalSerialize` encodings, for proofs from `ark-groth16`
 //!
 //! ## Usage
 //!
@@ -33,24 +62,106 @@
 //! }
 //! ```
 
+// Lets `#[derive(PublicInputs)]` output (`::near_groth16_verifier::...`)
+// compile inside this crate too
+extern crate self as near_groth16_verifier;
+
+pub mod error;
+pub mod fields;
+pub mod curve;
+#[cfg(any(feature = "standalone", test))]
+pub mod pairing;
+#[cfg(any(feature = "standalone", test))]
+pub mod native;
+pub mod backend;
 pub mod types;
 pub mod verifier;
+pub mod batch;
+pub mod commitment;
+pub mod compact;
+pub mod registry;
+pub mod stored;
+pub mod hashed;
+pub mod layout;
+pub mod inputs;
+pub mod method;
+pub mod merkle;
+pub mod smt;
+pub mod gnark;
+pub mod arkworks;
+pub mod prepared;
 pub mod transcript;
+pub mod plonk;
+pub mod fflonk;
 pub mod poseidon;
 pub mod poseidon_precomputed;
 
 // Re-export main types
-pub use types::{G1Point, G2Point, Proof, ProofJson, U256, VerificationKeyJson};
-pub use verifier::Verifier;
+pub use backend::{BackendCall, Bn254Backend, DefaultBackend, NearBackend, RecordingBackend};
+#[cfg(feature = "standalone")]
+pub use backend::NativeBackend;
+pub use error::{LayoutError, MerkleError, ParseError, PointError, RegistryError, VerifyError, VkError};
+pub use types::{
+    parse_field_element, G1Point, G2Point, Proof, ProofJson, U256, VerificationKeyJson, FQ_MODULUS,
+    FR_MODULUS,
+};
+pub use verifier::{parse_inputs, parse_le_inputs, Verifier};
+pub use batch::BatchResult;
+pub use commitment::{
+    CommitmentProof, CommitmentVerificationKey, CommitmentVerifier, PedersenVerifyingKey,
+};
+pub use compact::CompactProof;
+pub use registry::{CircuitInfo, RegistryEvent, VerifierRegistry};
+pub use stored::StoredVerifier;
+pub use hashed::{parse_words, InputHash, InputHasher};
+pub use layout::{InputRole, LayoutInputs, PublicInputLayout};
+pub use inputs::{FromPublicInputs, InputField, PublicInputs};
+pub use near_groth16_verifier_derive::{zk_method, PublicInputs};
+pub use method::ZkMethodEvent;
+pub use merkle::{
+    verify_merkle_proof, IncrementalMerkleTree, MerkleProof, MerkleProofJson, MerkleTree,
+    MerkleTreeState, DEFAULT_ROOT_HISTORY,
+};
+pub use smt::{verify_smt_proof, SmtProof, SmtProofJson, SparseMerkleTree, StoredSparseMerkleTree};
+pub use prepared::PreparedVerifier;
 pub use transcript::FiatShamirTranscript;
-pub use poseidon::{Fr, poseidon_hash2, poseidon_hash4, compute_commitment, compute_nullifier_hash};
+pub use plonk::{PlonkProof, PlonkVerificationKey, PlonkVerifier, ProofPlonkJson, VerificationKeyPlonkJson};
+pub use fflonk::{
+    FflonkProof, FflonkVerificationKey, FflonkVerifier, ProofFflonkJson, VerificationKeyFflonkJson,
+};
+pub use poseidon::{Fr, poseidon, poseidon_hash, poseidon_hash2, poseidon_hash4, compute_commitment, compute_commitment_poseidon4, compute_nullifier_hash};
 
 /// Prelude module for convenient imports
 pub mod prelude {
-    pub use crate::types::{G1Point, G2Point, Proof, ProofJson, U256, VerificationKeyJson};
-    pub use crate::verifier::Verifier;
+    pub use crate::backend::{Bn254Backend, DefaultBackend, NearBackend};
+    pub use crate::error::{
+        LayoutError, MerkleError, ParseError, PointError, RegistryError, VerifyError, VkError,
+    };
+    pub use crate::types::{
+        parse_field_element, G1Point, G2Point, Proof, ProofJson, U256, VerificationKeyJson,
+        FR_MODULUS,
+    };
+    pub use crate::verifier::{parse_inputs, parse_le_inputs, Verifier};
+    pub use crate::batch::BatchResult;
+    pub use crate::commitment::{
+        CommitmentProof, CommitmentVerificationKey, CommitmentVerifier, PedersenVerifyingKey,
+    };
+    pub use crate::compact::CompactProof;
+    pub use crate::registry::{CircuitInfo, VerifierRegistry};
+    pub use crate::stored::StoredVerifier;
+    pub use crate::hashed::{parse_words, InputHash, InputHasher};
+    pub use crate::layout::{InputRole, PublicInputLayout};
+    pub use crate::inputs::{FromPublicInputs, PublicInputs};
+    pub use near_groth16_verifier_derive::{zk_method, PublicInputs};
+    pub use crate::merkle::{IncrementalMerkleTree, MerkleProof, MerkleTree, DEFAULT_ROOT_HISTORY};
+    pub use crate::smt::{SmtProof, SparseMerkleTree, StoredSparseMerkleTree};
+    pub use crate::prepared::PreparedVerifier;
     pub use crate::transcript::FiatShamirTranscript;
-    pub use crate::poseidon::{Fr, poseidon_hash2, poseidon_hash4, compute_commitment, compute_nullifier_hash};
+    pub use crate::plonk::{PlonkProof, PlonkVerificationKey, PlonkVerifier, ProofPlonkJson, VerificationKeyPlonkJson};
+    pub use crate::fflonk::{
+        FflonkProof, FflonkVerificationKey, FflonkVerifier, ProofFflonkJson, VerificationKeyFflonkJson,
+    };
+    pub use crate::poseidon::{Fr, poseidon, poseidon_hash, poseidon_hash2, poseidon_hash4, compute_commitment, compute_commitment_poseidon4, compute_nullifier_hash};
 }
 
 #[cfg(test)]
@@ -0,0 +1,582 @@
+//! Poseidon Merkle trees
+//!
+//! [`IncrementalMerkleTree`] is the append-only commitment tree of a privacy
+//! pool, kept on-chain. It stores only what the next insertion needs: the
+//! zero hash of every level, the frontier (the last left node of every
+//! level) and a ring buffer of recent roots. Leaves themselves are not
+//! stored; clients rebuild the tree from deposit events or the contract's
+//! own leaf list.
+//!
+//! The hashing matches `MerkleProof` in `circuits/production/utils.circom`
+//! and `sdk/src/merkle.ts`: `parent = Poseidon(left, right)`, empty leaves
+//! are 0, and the empty subtree of height `i + 1` is
+//! `Poseidon(zero_i, zero_i)`.
+//!
+//! A withdrawal proof is made against the root the prover saw, and deposits
+//! can land before it is included. [`IncrementalMerkleTree::is_known_root`]
+//! therefore accepts any of the last `root_history` roots:
+//!
+//! ```rust,ignore
+//! // in `new`:
+//! let tree = IncrementalMerkleTree::new(20, DEFAULT_ROOT_HISTORY)?;
+//!
+//! // on deposit:
+//! let leaf_index = self.tree.insert(commitment)?;
+//!
+//! // on withdrawal:
+//! require!(self.tree.is_known_root(&root), "Unknown Merkle root");
+//! ```
+//!
+//! **Gas**: an insertion computes `depth` Poseidon hashes.
+//!
+//! [`MerkleTree`] is the off-chain counterpart for relayers and indexers: it
+//! keeps every node, so it can produce the `pathElements` / `pathIndices`
+//! witness of any leaf. Its state serializes to the same JSON as
+//! `IncrementalMerkleTree.exportState()` in the TypeScript SDK:
+//!
+//! ```rust,ignore
+//! let mut tree = MerkleTree::new(20)?;
+//! for commitment in commitments {
+//!     tree.insert(commitment)?;
+//! }
+//! let proof = tree.proof(leaf_index)?;
+//! assert!(verify_merkle_proof(&proof));
+//! let witness = near_sdk::serde_json::to_string(&proof.to_json())?;
+//! ```
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::error::{MerkleError, ParseError};
+use crate::poseidon::{poseidon_t3_opt, Fr};
+use crate::types::{parse_field_element, FR_MODULUS, U256};
+
+/// Deepest supported tree (2^32 leaves)
+pub const MAX_DEPTH: u8 = 32;
+
+/// Roots kept by default, as in Tornado Cash's `MerkleTreeWithHistory`
+pub const DEFAULT_ROOT_HISTORY: u32 = 30;
+
+/// `Poseidon(left, right)`, the parent of two nodes
+pub fn hash_pair(left: &U256, right: &U256) -> U256 {
+    U256(poseidon_t3_opt(&[Fr { limbs: left.0 }, Fr { limbs: right.0 }]).limbs)
+}
+
+/// Roots of empty subtrees of height `0..=depth`
+pub fn zero_hashes(depth: u8) -> Vec<U256> {
+    let mut zeros = Vec::with_capacity(depth as usize + 1);
+    zeros.push(U256::zero());
+    for i in 0..depth as usize {
+        zeros.push(hash_pair(&zeros[i], &zeros[i]));
+    }
+    zeros
+}
+
+/// Append-only Poseidon Merkle tree with a history of recent roots
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct IncrementalMerkleTree {
+    depth: u8,
+    next_index: u64,
+    /// `zeros[i]`: root of an empty subtree of height `i`
+    zeros: Vec<U256>,
+    /// `frontier[i]`: last left node inserted at height `i`
+    frontier: Vec<U256>,
+    /// Ring buffer of recent roots; zero marks an unused slot
+    roots: Vec<U256>,
+    current_root: u32,
+}
+
+impl IncrementalMerkleTree {
+    /// Empty tree of `depth` levels keeping the last `root_history` roots
+    pub fn new(depth: u8, root_history: u32) -> Result<Self, MerkleError> {
+        if depth == 0 || depth > MAX_DEPTH {
+            return Err(MerkleError::InvalidDepth { depth });
+        }
+        if root_history == 0 {
+            return Err(MerkleError::EmptyRootHistory);
+        }
+
+        let zeros = zero_hashes(depth);
+        let mut roots = vec![U256::zero(); root_history as usize];
+        roots[0] = zeros[depth as usize];
+        Ok(Self {
+            depth,
+            next_index: 0,
+            frontier: zeros[..depth as usize].to_vec(),
+            zeros,
+            roots,
+            current_root: 0,
+        })
+    }
+
+    /// Number of levels
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Number of leaves inserted so far, i.e. the index of the next one
+    pub fn len(&self) -> u64 {
+        self.next_index
+    }
+
+    /// `true` before the first insertion
+    pub fn is_empty(&self) -> bool {
+        self.next_index == 0
+    }
+
+    /// Maximum number of leaves, `2^depth`
+    pub fn capacity(&self) -> u64 {
+        1u64 << self.depth
+    }
+
+    /// Root of an empty subtree of height `level`
+    pub fn zero(&self, level: u8) -> U256 {
+        self.zeros[level as usize]
+    }
+
+    /// Current root
+    pub fn root(&self) -> U256 {
+        self.roots[self.current_root as usize]
+    }
+
+    /// `true` if `root` is the current root or one of the previous
+    /// `root_history - 1` roots
+    pub fn is_known_root(&self, root: &U256) -> bool {
+        !root.is_zero() && self.roots.contains(root)
+    }
+
+    /// Append `leaf` and return its index
+    pub fn insert(&mut self, leaf: U256) -> Result<u64, MerkleError> {
+        if leaf >= FR_MODULUS {
+            return Err(MerkleError::NonCanonicalLeaf);
+        }
+        if self.next_index >= self.capacity() {
+            return Err(MerkleError::TreeFull { capacity: self.capacity() });
+        }
+
+        let leaf_index = self.next_index;
+        let mut index = leaf_index;
+        let mut node = leaf;
+        for level in 0..self.depth as usize {
+            node = if index & 1 == 0 {
+                self.frontier[level] = node;
+                hash_pair(&node, &self.zeros[level])
+            } else {
+                hash_pair(&self.frontier[level], &node)
+            };
+            index /= 2;
+        }
+
+        self.current_root = (self.current_root + 1) % self.roots.len() as u32;
+        self.roots[self.current_root as usize] = node;
+        self.next_index += 1;
+        Ok(leaf_index)
+    }
+}
+
+/// Poseidon Merkle tree holding every node, for proof generation
+///
+/// Serializes as [`MerkleTreeState`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", try_from = "MerkleTreeState", into = "MerkleTreeState")]
+pub struct MerkleTree {
+    depth: u8,
+    zeros: Vec<U256>,
+    /// `levels[0]`: leaves, `levels[depth]`: the root once a leaf exists
+    levels: Vec<Vec<U256>>,
+}
+
+impl MerkleTree {
+    /// Empty tree of `depth` levels
+    pub fn new(depth: u8) -> Result<Self, MerkleError> {
+        if depth == 0 || depth > MAX_DEPTH {
+            return Err(MerkleError::InvalidDepth { depth });
+        }
+        Ok(Self {
+            depth,
+            zeros: zero_hashes(depth),
+            levels: vec![Vec::new(); depth as usize + 1],
+        })
+    }
+
+    /// Tree of `depth` levels over `leaves`, e.g. a contract's leaf list
+    pub fn from_leaves(depth: u8, leaves: &[U256]) -> Result<Self, MerkleError> {
+        let mut tree = Self::new(depth)?;
+        for leaf in leaves {
+            tree.insert(*leaf)?;
+        }
+        Ok(tree)
+    }
+
+    /// Number of levels
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Number of leaves
+    pub fn len(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    /// `true` before the first insertion
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Maximum number of leaves, `2^depth`
+    pub fn capacity(&self) -> u64 {
+        1u64 << self.depth
+    }
+
+    /// Leaves in insertion order
+    pub fn leaves(&self) -> &[U256] {
+        &self.levels[0]
+    }
+
+    /// Current root
+    pub fn root(&self) -> U256 {
+        self.levels[self.depth as usize]
+            .first()
+            .copied()
+            .unwrap_or(self.zeros[self.depth as usize])
+    }
+
+    /// Append `leaf` and return its index
+    pub fn insert(&mut self, leaf: U256) -> Result<u64, MerkleError> {
+        if leaf >= FR_MODULUS {
+            return Err(MerkleError::NonCanonicalLeaf);
+        }
+        if self.len() >= self.capacity() {
+            return Err(MerkleError::TreeFull { capacity: self.capacity() });
+        }
+
+        let leaf_index = self.len();
+        self.levels[0].push(leaf);
+        let mut index = leaf_index as usize;
+        let mut node = leaf;
+        for level in 0..self.depth as usize {
+            node = if index & 1 == 0 {
+                hash_pair(&node, &self.sibling(level, index + 1))
+            } else {
+                hash_pair(&self.levels[level][index - 1], &node)
+            };
+            index /= 2;
+
+            // Append-only: the parent is either new or the last node of its level
+            let parents = &mut self.levels[level + 1];
+            if index < parents.len() {
+                parents[index] = node;
+            } else {
+                parents.push(node);
+            }
+        }
+        Ok(leaf_index)
+    }
+
+    /// Membership witness of leaf `leaf_index` against the current root
+    pub fn proof(&self, leaf_index: u64) -> Result<MerkleProof, MerkleError> {
+        if leaf_index >= self.len() {
+            return Err(MerkleError::UnknownLeaf { index: leaf_index });
+        }
+
+        let mut index = leaf_index as usize;
+        let mut path_elements = Vec::with_capacity(self.depth as usize);
+        let mut path_indices = Vec::with_capacity(self.depth as usize);
+        for level in 0..self.depth as usize {
+            path_elements.push(self.sibling(level, index ^ 1));
+            path_indices.push((index & 1) as u8);
+            index /= 2;
+        }
+
+        Ok(MerkleProof {
+            leaf: self.levels[0][leaf_index as usize],
+            leaf_index,
+            path_elements,
+            path_indices,
+            root: self.root(),
+        })
+    }
+
+    /// State in the TypeScript SDK's `exportState()` format
+    pub fn export_state(&self) -> MerkleTreeState {
+        MerkleTreeState {
+            depth: self.depth,
+            leaf_count: self.len(),
+            leaves: self.leaves().iter().map(|leaf| format!("{:#x}", leaf)).collect(),
+        }
+    }
+
+    /// Rebuild a tree from [`MerkleTree::export_state`] or TypeScript
+    /// `exportState()` output
+    pub fn import_state(state: &MerkleTreeState) -> Result<Self, MerkleError> {
+        if state.leaf_count != state.leaves.len() as u64 {
+            return Err(MerkleError::LeafCountMismatch {
+                expected: state.leaf_count,
+                found: state.leaves.len(),
+            });
+        }
+        let leaves = state
+            .leaves
+            .iter()
+            .enumerate()
+            .map(|(index, leaf)| parse_field_element(leaf).map_err(|_| MerkleError::InvalidLeaf { index }))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_leaves(state.depth, &leaves)
+    }
+
+    /// Node `index` of `level`, or the zero hash if it does not exist yet
+    fn sibling(&self, level: usize, index: usize) -> U256 {
+        self.levels[level].get(index).copied().unwrap_or(self.zeros[level])
+    }
+}
+
+impl TryFrom<MerkleTreeState> for MerkleTree {
+    type Error = MerkleError;
+
+    fn try_from(state: MerkleTreeState) -> Result<Self, MerkleError> {
+        Self::import_state(&state)
+    }
+}
+
+impl From<MerkleTree> for MerkleTreeState {
+    fn from(tree: MerkleTree) -> Self {
+        tree.export_state()
+    }
+}
+
+/// Serialized [`MerkleTree`]: `{"depth", "leafCount", "leaves": ["0x…"]}`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+pub struct MerkleTreeState {
+    pub depth: u8,
+    pub leaf_count: u64,
+    /// `0x`-prefixed hex, as the TypeScript SDK writes them; decimal is
+    /// accepted on import
+    pub leaves: Vec<String>,
+}
+
+/// Membership witness of one leaf, as `MerkleProof(levels)` takes it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub leaf: U256,
+    pub leaf_index: u64,
+    /// Sibling of the path node at each level, leaf first
+    pub path_elements: Vec<U256>,
+    /// 0 if the path node is a left child, 1 if a right child
+    pub path_indices: Vec<u8>,
+    pub root: U256,
+}
+
+impl MerkleProof {
+    /// Root implied by the leaf and path
+    pub fn compute_root(&self) -> U256 {
+        self.path_elements
+            .iter()
+            .zip(&self.path_indices)
+            .fold(self.leaf, |node, (sibling, &bit)| {
+                if bit == 1 {
+                    hash_pair(sibling, &node)
+                } else {
+                    hash_pair(&node, sibling)
+                }
+            })
+    }
+
+    /// Circuit witness JSON with decimal strings
+    pub fn to_json(&self) -> MerkleProofJson {
+        MerkleProofJson {
+            leaf: self.leaf.to_string(),
+            leaf_index: self.leaf_index,
+            path_elements: self.path_elements.iter().map(U256::to_string).collect(),
+            path_indices: self.path_indices.clone(),
+            root: self.root.to_string(),
+        }
+    }
+}
+
+/// `true` if `proof` leads from its leaf to its root
+///
+/// Path indices other than 0 and 1 are rejected, as the circuit's
+/// `pathBit * (1 - pathBit) === 0` constraint does.
+pub fn verify_merkle_proof(proof: &MerkleProof) -> bool {
+    proof.path_elements.len() == proof.path_indices.len()
+        && proof.path_indices.iter().all(|&bit| bit <= 1)
+        && proof.compute_root() == proof.root
+}
+
+/// snarkjs-style JSON of a [`MerkleProof`], field names as in the
+/// TypeScript SDK
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+pub struct MerkleProofJson {
+    pub leaf: String,
+    pub leaf_index: u64,
+    pub path_elements: Vec<String>,
+    pub path_indices: Vec<u8>,
+    pub root: String,
+}
+
+impl MerkleProofJson {
+    /// Parse the decimal or hex field elements
+    pub fn to_proof(&self) -> Result<MerkleProof, ParseError> {
+        Ok(MerkleProof {
+            leaf: parse_field_element(&self.leaf)?,
+            leaf_index: self.leaf_index,
+            path_elements: self
+                .path_elements
+                .iter()
+                .map(|element| parse_field_element(element))
+                .collect::<Result<_, _>>()?,
+            path_indices: self.path_indices.clone(),
+            root: parse_field_element(&self.root)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::poseidon_tests::test_vectors::EMPTY_TREE_ZEROS;
+
+    /// Root of a full tree of `depth` levels over `leaves`, padded with zeros
+    fn naive_root(depth: u8, leaves: &[U256]) -> U256 {
+        let mut level = leaves.to_vec();
+        level.resize(1 << depth, U256::zero());
+        while level.len() > 1 {
+            level = level.chunks(2).map(|pair| hash_pair(&pair[0], &pair[1])).collect();
+        }
+        level[0]
+    }
+
+    #[test]
+    fn test_zero_hashes_match_vectors() {
+        let zeros = zero_hashes(4);
+        for (zero, expected) in zeros.iter().zip(EMPTY_TREE_ZEROS) {
+            assert_eq!(zero.to_string(), expected);
+        }
+
+        let tree = IncrementalMerkleTree::new(4, 2).unwrap();
+        assert_eq!(tree.root(), zeros[4]);
+        assert!(tree.is_known_root(&zeros[4]));
+        assert!(!tree.is_known_root(&U256::zero()));
+    }
+
+    #[test]
+    fn test_insert_matches_full_tree() {
+        let mut tree = IncrementalMerkleTree::new(3, DEFAULT_ROOT_HISTORY).unwrap();
+        let leaves: Vec<U256> = (1..=8u64).map(U256::from).collect();
+        for (i, leaf) in leaves.iter().enumerate() {
+            assert_eq!(tree.insert(*leaf), Ok(i as u64));
+            assert_eq!(tree.root(), naive_root(3, &leaves[..=i]));
+        }
+
+        assert_eq!(tree.insert(U256::one()), Err(MerkleError::TreeFull { capacity: 8 }));
+        assert_eq!(tree.len(), 8);
+    }
+
+    #[test]
+    fn test_root_history() {
+        let mut tree = IncrementalMerkleTree::new(4, 3).unwrap();
+        let empty = tree.root();
+        let mut roots = vec![empty];
+        for leaf in 1..=3u64 {
+            tree.insert(U256::from(leaf)).unwrap();
+            roots.push(tree.root());
+        }
+
+        // Only the last 3 roots are kept
+        assert!(!tree.is_known_root(&empty));
+        assert!(roots[1..].iter().all(|root| tree.is_known_root(root)));
+
+        assert_eq!(tree.insert(FR_MODULUS), Err(MerkleError::NonCanonicalLeaf));
+        assert_eq!(IncrementalMerkleTree::new(0, 1), Err(MerkleError::InvalidDepth { depth: 0 }));
+        assert_eq!(IncrementalMerkleTree::new(4, 0), Err(MerkleError::EmptyRootHistory));
+    }
+
+    #[test]
+    fn test_merkle_tree_matches_on_chain_tree() {
+        let mut tree = MerkleTree::new(4).unwrap();
+        let mut on_chain = IncrementalMerkleTree::new(4, DEFAULT_ROOT_HISTORY).unwrap();
+        assert_eq!(tree.root(), on_chain.root());
+
+        let leaves: Vec<U256> = (1..=11u64).map(|i| U256::from(i * 1000 + 7)).collect();
+        for leaf in &leaves {
+            assert_eq!(tree.insert(*leaf), on_chain.insert(*leaf));
+            assert_eq!(tree.root(), on_chain.root());
+        }
+        assert_eq!(tree.root(), naive_root(4, &leaves));
+
+        for leaf_index in 0..tree.len() {
+            let proof = tree.proof(leaf_index).unwrap();
+            assert_eq!(proof.leaf, leaves[leaf_index as usize]);
+            assert!(verify_merkle_proof(&proof));
+        }
+        assert_eq!(tree.proof(11), Err(MerkleError::UnknownLeaf { index: 11 }));
+    }
+
+    #[test]
+    fn test_proof_json_and_verification() {
+        let tree = MerkleTree::from_leaves(3, &[U256::from(1u64), U256::from(2u64), U256::from(3u64)]).unwrap();
+        let proof = tree.proof(2).unwrap();
+
+        // Leaf 2 = 0b010: left, right, left; its right sibling is still empty
+        assert_eq!(proof.path_indices, vec![0, 1, 0]);
+        assert_eq!(proof.path_elements[0], U256::zero());
+        assert_eq!(proof.path_elements[1], hash_pair(&U256::from(1u64), &U256::from(2u64)));
+        assert_eq!(proof.path_elements[2], zero_hashes(2)[2]);
+
+        let json = near_sdk::serde_json::to_value(proof.to_json()).unwrap();
+        assert_eq!(json["leafIndex"], 2);
+        assert_eq!(json["pathIndices"], near_sdk::serde_json::json!([0, 1, 0]));
+        assert_eq!(json["leaf"], "3");
+        let parsed: MerkleProofJson = near_sdk::serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.to_proof().unwrap(), proof);
+
+        let mut bad = proof.clone();
+        bad.path_indices[1] = 2;
+        assert!(!verify_merkle_proof(&bad));
+        let mut bad = proof;
+        bad.leaf = U256::from(4u64);
+        assert!(!verify_merkle_proof(&bad));
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let tree = MerkleTree::from_leaves(5, &[U256::from(255u64), U256::from(16u64)]).unwrap();
+        let json = near_sdk::serde_json::to_string(&tree).unwrap();
+        assert_eq!(json, r#"{"depth":5,"leafCount":2,"leaves":["0xff","0x10"]}"#);
+
+        let restored: MerkleTree = near_sdk::serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, tree);
+        assert_eq!(restored.root(), tree.root());
+
+        let state = MerkleTreeState { depth: 5, leaf_count: 3, leaves: vec!["0xff".to_string()] };
+        assert_eq!(
+            MerkleTree::import_state(&state),
+            Err(MerkleError::LeafCountMismatch { expected: 3, found: 1 })
+        );
+        let state = MerkleTreeState { depth: 5, leaf_count: 1, leaves: vec!["0xzz".to_string()] };
+        assert_eq!(MerkleTree::import_state(&state), Err(MerkleError::InvalidLeaf { index: 0 }));
+    }
+
+    /// Roots over leaves `i * 1000 + 7` for `i = 1..=n`, depth 20; the SDK's
+    /// `sdk/test/merkle.test.ts` checks its tree against the same values
+    const SDK_ROOTS: [(u64, &str); 2] = [
+        (3, "8195400888830981910601246489126998430755625839197294666455421976640144083276"),
+        (5, "1020791116174676611517720155168092884247281460124522095558744697616005116087"),
+    ];
+
+    #[test]
+    fn test_sdk_roots() {
+        for (count, expected) in SDK_ROOTS {
+            let leaves: Vec<U256> = (1..=count).map(|i| U256::from(i * 1000 + 7)).collect();
+            let tree = MerkleTree::from_leaves(20, &leaves).unwrap();
+            let mut on_chain = IncrementalMerkleTree::new(20, DEFAULT_ROOT_HISTORY).unwrap();
+            for leaf in &leaves {
+                on_chain.insert(*leaf).unwrap();
+            }
+            assert_eq!(tree.root().to_string(), expected);
+            assert_eq!(on_chain.root().to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Runtime support for `#[zk_method]`
+//!
+//! Every proof-consuming contract method starts the same way: parse the
+//! proof, parse and range-check the inputs, reject a used nullifier, verify,
+//! record the nullifier, emit an event. `#[zk_method]` writes those steps in
+//! front of the method body:
+//!
+//! ```rust,ignore
+//! use near_groth16_verifier::{zk_method, ProofJson, PublicInputs};
+//!
+//! #[derive(PublicInputs)]
+//! pub struct WithdrawInputs {
+//!     pub nullifier: U256,
+//!     pub root: U256,
+//!     pub amount: U128,
+//! }
+//!
+//! #[near]
+//! impl Pool {
+//!     #[zk_method(circuit = "withdraw", inputs = WithdrawInputs, nullifier = 0)]
+//!     pub fn withdraw(&mut self, proof: ProofJson, public_inputs: Vec<String>) -> Promise {
+//!         // `proof` is the parsed `Proof`, `public_inputs` a verified
+//!         // `WithdrawInputs` whose nullifier is now marked as used
+//!         Promise::new(env::predecessor_account_id()).transfer(public_inputs.amount.into())
+//!     }
+//! }
+//! ```
+//!
+//! The method keeps its signature, so `#[near]` exports it unchanged: callers
+//! pass a snarkjs proof and `public.json`. Attribute arguments:
+//!
+//! - `circuit = "..."`: name used in panic messages and events (required)
+//! - `inputs = Type`: a [`FromPublicInputs`] struct `public_inputs` is
+//!   decoded into (required)
+//! - `nullifier = N`: index of the nullifier input; without it no
+//!   nullifier is tracked
+//! - `nullifiers = "field"`: the `LookupSet<[u8; 32]>` of used nullifiers
+//!   (default `"nullifiers"`)
+//! - `verifier = "field"`: the [`Verifier`] (default `"verifier"`)
+//!
+//! Any failure panics with `"<circuit>: proof verification failed: <reason>"`,
+//! or `"<circuit>: nullifier already used"`.
+
+use near_sdk::store::LookupSet;
+use near_sdk::{env, near, AccountId};
+
+use crate::backend::Bn254Backend;
+use crate::error::VerifyError;
+use crate::inputs::FromPublicInputs;
+use crate::types::{Proof, U256};
+use crate::verifier::{parse_inputs, Verifier};
+
+/// Emitted by every successful `#[zk_method]` call
+#[near(event_json(standard = "nep297"))]
+pub enum ZkMethodEvent {
+    #[event_version("1.0.0")]
+    ProofVerified {
+        circuit: String,
+        /// The nullifier input, if the method tracks one
+        nullifier: Option<String>,
+        caller: AccountId,
+    },
+}
+
+/// Panic with the reason a `circuit` proof was rejected
+pub fn reject(circuit: &str, e: VerifyError) -> ! {
+    env::panic_str(&format!("{}: proof verification failed: {}", circuit, e))
+}
+
+/// The parsed proof, or a panic
+pub fn parse_proof<E: Into<VerifyError>>(circuit: &str, proof: Result<Proof, E>) -> Proof {
+    proof.unwrap_or_else(|e| reject(circuit, e.into()))
+}
+
+/// The canonical inputs and their typed form, or a panic
+pub fn parse_typed_inputs<T: FromPublicInputs>(circuit: &str, inputs: &[String]) -> (Vec<U256>, T) {
+    let inputs = parse_inputs(inputs).unwrap_or_else(|e| reject(circuit, e));
+    let typed = T::from_inputs(&inputs).unwrap_or_else(|e| reject(circuit, e));
+    (inputs, typed)
+}
+
+/// Check and record the nullifier at `nullifier.0`, verify, and emit
+/// [`ZkMethodEvent::ProofVerified`]; panics on any failure
+pub fn verify_and_nullify<B: Bn254Backend>(
+    circuit: &str,
+    verifier: &Verifier<B>,
+    proof: &Proof,
+    inputs: &[U256],
+    nullifier: Option<(usize, &mut LookupSet<[u8; 32]>)>,
+) {
+    let nullifier = nullifier.map(|(index, set)| {
+        let value = inputs[index];
+        if set.contains(&value.to_be_bytes()) {
+            env::panic_str(&format!("{}: nullifier already used", circuit));
+        }
+        (value, set)
+    });
+
+    if let Err(e) = verifier.verify_checked(inputs, proof) {
+        reject(circuit, e);
+    }
+
+    let nullifier = nullifier.map(|(value, set)| {
+        set.insert(value.to_be_bytes());
+        value.to_string()
+    });
+    ZkMethodEvent::ProofVerified {
+        circuit: circuit.to_string(),
+        nullifier,
+        caller: env::predecessor_account_id(),
+    }
+    .emit();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+    use crate::types::ProofJson;
+    use crate::{zk_method, PublicInputs};
+
+    #[derive(PublicInputs)]
+    struct SpendInputs {
+        nullifier: U256,
+        amount: u64,
+    }
+
+    struct Pool {
+        verifier: Verifier,
+        spent: LookupSet<[u8; 32]>,
+        total: u64,
+    }
+
+    impl Pool {
+        fn new() -> Self {
+            let (verifier, _, _) = fixture();
+            Self { verifier, spent: LookupSet::new(b"n"), total: 0 }
+        }
+
+        #[zk_method(circuit = "spend", inputs = SpendInputs, nullifier = 0, nullifiers = "spent")]
+        fn spend(&mut self, proof: ProofJson, public_inputs: Vec<String>) -> u64 {
+            assert!(proof.validate().is_ok());
+            self.total += public_inputs.amount;
+            self.total
+        }
+
+        #[zk_method(circuit = "check", inputs = SpendInputs)]
+        fn check(&self, proof: ProofJson, public_inputs: Vec<String>) -> U256 {
+            let _ = proof;
+            public_inputs.nullifier
+        }
+    }
+
+    #[test]
+    fn test_zk_method_verifies_and_records_nullifier() {
+        let (_, proof, inputs) = fixture();
+        let mut pool = Pool::new();
+
+        assert_eq!(pool.check(fixture().1, inputs.clone()), parse_inputs(&inputs).unwrap()[0]);
+        assert!(near_sdk::test_utils::get_logs()[0].contains(r#""circuit":"check","nullifier":null"#));
+
+        let amount = parse_inputs(&inputs).unwrap()[1].as_u64();
+        assert_eq!(pool.spend(proof, inputs.clone()), amount);
+        assert!(pool.spent.contains(&parse_inputs(&inputs).unwrap()[0].to_be_bytes()));
+        assert!(near_sdk::test_utils::get_logs()[1].contains(r#""event":"proof_verified""#));
+    }
+
+    #[test]
+    #[should_panic(expected = "spend: nullifier already used")]
+    fn test_zk_method_rejects_reused_nullifier() {
+        let (_, proof, inputs) = fixture();
+        let mut pool = Pool::new();
+        pool.spend(fixture().1, inputs.clone());
+        pool.spend(proof, inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "spend: proof verification failed: public input 1 is not below")]
+    fn test_zk_method_rejects_non_canonical_input() {
+        let (_, proof, mut inputs) = fixture();
+        let amount = parse_inputs(&inputs).unwrap()[1];
+        inputs[1] = (amount + crate::types::FR_MODULUS).to_string();
+        Pool::new().spend(proof, inputs);
+    }
+}
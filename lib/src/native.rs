@@ -0,0 +1,168 @@
+//! Pure-Rust replacements for NEAR's alt_bn128 host functions
+//!
+//! Same byte layout and the same results as `env::alt_bn128_g1_multiexp`,
+//! `env::alt_bn128_g1_sum` and `env::alt_bn128_pairing_check`, so verifier
+//! code runs unchanged outside the NEAR runtime (CLI, relayers, plain
+//! `cargo test`). Enabled by the `standalone` feature and exposed as
+//! [`NativeBackend`](crate::backend::NativeBackend).
+//!
+//! Input the host would reject (bad length, coordinates `>= q`, points off the
+//! curve or outside the G2 subgroup, multiexp scalars `>= r`, sign bytes other
+//! than 0 / 1) panics here, just as the host aborts the transaction.
+//!
+//! This is not constant time. It verifies public data only and must not be
+//! used with secret scalars.
+
+use crate::curve::{g1_is_on_curve, G1Jacobian};
+use crate::fields::Fq;
+use crate::pairing;
+use crate::types::{G1Point, G2Point, U256, FR_MODULUS};
+
+/// Σ scalar_i · P_i; input is `(point (64) ‖ scalar (32))*`, output 64 bytes
+pub fn alt_bn128_g1_multiexp(value: impl AsRef<[u8]>) -> Vec<u8> {
+    let value = value.as_ref();
+    if value.len() % 96 != 0 {
+        invalid_input("g1_multiexp input length must be a multiple of 96");
+    }
+
+    let mut acc = G1Jacobian::identity();
+    for item in value.chunks_exact(96) {
+        let point = read_g1(&item[..64]);
+        let scalar = U256::from_le_bytes(&item[64..]);
+        if scalar >= FR_MODULUS {
+            invalid_input("invalid fr");
+        }
+        acc = acc.add(&point.mul(&scalar.0));
+    }
+    write_g1(acc).to_vec()
+}
+
+/// Σ ±P_i; input is `(sign (1) ‖ point (64))*` with sign 0 = add, 1 = subtract
+pub fn alt_bn128_g1_sum(value: impl AsRef<[u8]>) -> Vec<u8> {
+    let value = value.as_ref();
+    if value.len() % 65 != 0 {
+        invalid_input("g1_sum input length must be a multiple of 65");
+    }
+
+    let mut acc = G1Jacobian::identity();
+    for item in value.chunks_exact(65) {
+        let point = read_g1(&item[1..]);
+        acc = match item[0] {
+            0 => acc.add(&point),
+            1 => acc.add(&point.neg()),
+            _ => invalid_input("invalid sign byte"),
+        };
+    }
+    write_g1(acc).to_vec()
+}
+
+/// Π e(P_i, Q_i) = 1; input is `(G1 (64) ‖ G2 (128))*`
+pub fn alt_bn128_pairing_check(value: impl AsRef<[u8]>) -> bool {
+    let value = value.as_ref();
+    if value.len() % 192 != 0 {
+        invalid_input("pairing input length must be a multiple of 192");
+    }
+
+    let pairs: Vec<(G1Point, G2Point)> = value
+        .chunks_exact(192)
+        .map(|item| {
+            let p = G1Point::from_precompile_bytes(item[..64].try_into().unwrap());
+            if !g1_is_on_curve(&p) {
+                invalid_input("invalid G1 point");
+            }
+            let q = G2Point::from_precompile_bytes(item[64..].try_into().unwrap());
+            if !q.is_in_subgroup() {
+                invalid_input("invalid G2 point");
+            }
+            (p, q)
+        })
+        .collect();
+
+    pairing::pairing_check(&pairs)
+}
+
+/// Off-chain stand-in for `env::random_seed`: there is no block randomness,
+/// so this is empty and callers fall back to hashing their own inputs
+pub fn random_seed() -> Vec<u8> {
+    Vec::new()
+}
+
+/// Parse and check a little-endian G1 point the way the host does
+fn read_g1(bytes: &[u8]) -> G1Jacobian {
+    let p = G1Point::from_precompile_bytes(bytes.try_into().unwrap());
+    match (Fq::from_u256(&p.x), Fq::from_u256(&p.y)) {
+        (Some(x), Some(y)) if g1_is_on_curve(&p) => G1Jacobian::from_affine(&x, &y),
+        _ => invalid_input("invalid G1 point"),
+    }
+}
+
+fn write_g1(p: G1Jacobian) -> [u8; 64] {
+    let (x, y) = p.to_affine();
+    G1Point { x: x.to_u256(), y: y.to_u256() }.to_precompile_bytes()
+}
+
+fn invalid_input(msg: &str) -> ! {
+    panic!("AltBn128InvalidInput: {}", msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::env;
+
+    fn g1_bytes(k: u64) -> [u8; 64] {
+        let g = G1Jacobian::from_affine(&Fq::one(), &Fq::from_u64(2));
+        write_g1(g.mul(&[k, 0, 0, 0]))
+    }
+
+    fn scalar(k: U256) -> [u8; 32] {
+        k.to_le_bytes()
+    }
+
+    #[test]
+    fn test_g1_multiexp_matches_host() {
+        let mut input = Vec::new();
+        for (k, s) in [(3u64, U256::from(5u64)), (7, FR_MODULUS - 1), (11, U256::zero())] {
+            input.extend_from_slice(&g1_bytes(k));
+            input.extend_from_slice(&scalar(s));
+        }
+        // The identity as a point is accepted too
+        input.extend_from_slice(&[0u8; 64]);
+        input.extend_from_slice(&scalar(U256::from(9u64)));
+
+        assert_eq!(alt_bn128_g1_multiexp(&input), env::alt_bn128_g1_multiexp(&input));
+        assert_eq!(alt_bn128_g1_multiexp([]), env::alt_bn128_g1_multiexp([]));
+    }
+
+    #[test]
+    fn test_g1_sum_matches_host() {
+        let mut input = Vec::new();
+        for (sign, k) in [(0u8, 2u64), (0, 5), (1, 3), (1, 4)] {
+            input.push(sign);
+            input.extend_from_slice(&g1_bytes(k));
+        }
+        // 2 + 5 - 3 - 4 = 0
+        assert_eq!(alt_bn128_g1_sum(&input), vec![0u8; 64]);
+        assert_eq!(alt_bn128_g1_sum(&input), env::alt_bn128_g1_sum(&input));
+
+        let doubled = [[0u8].as_slice(), &g1_bytes(6), &[0u8], &g1_bytes(6)].concat();
+        assert_eq!(alt_bn128_g1_sum(&doubled), g1_bytes(12).to_vec());
+        assert_eq!(alt_bn128_g1_sum(&doubled), env::alt_bn128_g1_sum(&doubled));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid fr")]
+    fn test_multiexp_rejects_non_canonical_scalar() {
+        let input = [g1_bytes(1).as_slice(), &scalar(FR_MODULUS)].concat();
+        alt_bn128_g1_multiexp(input);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid G1 point")]
+    fn test_g1_sum_rejects_off_curve_point() {
+        let mut input = [0u8; 65];
+        input[1] = 1;
+        input[33] = 3;
+        alt_bn128_g1_sum(input);
+    }
+}
@@ -0,0 +1,267 @@
+//! Optimal ate pairing over BN254
+//!
+//! Pure-Rust pairing product check used by the native backend
+//! ([`crate::native`]). It follows the arkworks construction for the D-type
+//! twist: a Miller loop over the signed digits of `6x + 2`, two extra line
+//! evaluations at `π(Q)` and `-π²(Q)`, and the final exponentiation by
+//! `(q¹² - 1) / r`.
+//!
+//! Only the result of the product check is exposed; callers validate points
+//! (on-curve, subgroup) before getting here.
+
+use crate::curve::{TWIST_B, TWIST_MUL_BY_Q_X, TWIST_MUL_BY_Q_Y};
+use crate::fields::{Fq, Fq12, Fq2, Fq6};
+use crate::types::{G1Point, G2Point};
+
+/// Signed binary digits of `6x + 2`, least significant first
+/// (x = 4965661367192848881)
+const ATE_LOOP_COUNT: [i8; 65] = [
+    0, 0, 0, 1, 0, 1, 0, -1, 0, 0, 1, -1, 0, 0, 1, 0, 0, 1, 1, 0, -1, 0, 0, 1, 0, -1, 0, 0, 0, 0,
+    1, 1, 1, 0, 0, -1, 0, 0, 1, 0, 0, 0, 0, 0, -1, 0, 0, 1, 1, 0, 0, -1, 0, 0, 0, 1, 1, 0, -1, 0,
+    0, 1, 0, 1, 1,
+];
+
+/// q², little-endian limbs
+const Q_SQUARED: [u64; 8] = [
+    0x3b5458a2275d69b1,
+    0xa602072d09eac101,
+    0x4a50189c6d96cadc,
+    0x04689e957a1242c8,
+    0x26edfa5c34c6b38d,
+    0xb00b855116375606,
+    0x599a6f7c0348d21c,
+    0x0925c4b8763cbf9c,
+];
+
+/// Hard part of the final exponentiation: (q⁴ - q² + 1) / r
+const HARD_EXPONENT: [u64; 12] = [
+    0xe81bb482ccdf42b1,
+    0x5abf5cc4f49c36d4,
+    0xf1154e7e1da014fd,
+    0xdcc7b44c87cdbacf,
+    0xaaa441e3954bcf8a,
+    0x6b887d56d5095f23,
+    0x79581e16f3fd90c6,
+    0x3b1b1355d189227d,
+    0x4e529a5861876f6b,
+    0x6c0eb522d5b12278,
+    0x331ec15183177faf,
+    0x01baaa710b0759ad,
+];
+
+/// Check `Π e(P_i, Q_i) = 1`
+///
+/// Pairs where either point is the identity contribute 1 and are skipped.
+/// Points must already be valid; coordinates `>= q` make the check fail.
+pub fn pairing_check(pairs: &[(G1Point, G2Point)]) -> bool {
+    let mut prepared = Vec::with_capacity(pairs.len());
+    for (p, q) in pairs {
+        if p.is_zero() || q.is_zero() {
+            continue;
+        }
+        let (px, py) = match (Fq::from_u256(&p.x), Fq::from_u256(&p.y)) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return false,
+        };
+        let (qx, qy) = match (Fq2::from_u256(&q.x), Fq2::from_u256(&q.y)) {
+            (Some(x), Some(y)) => (x, y),
+            _ => return false,
+        };
+        prepared.push(MillerPair::new(px, py, qx, qy));
+    }
+
+    final_exponentiation(&miller_loop(&mut prepared)) == Some(Fq12::one())
+}
+
+/// Per-pair state of the Miller loop
+struct MillerPair {
+    p: (Fq, Fq),
+    q: (Fq2, Fq2),
+    neg_q: (Fq2, Fq2),
+    r: G2Projective,
+}
+
+impl MillerPair {
+    fn new(px: Fq, py: Fq, qx: Fq2, qy: Fq2) -> Self {
+        Self {
+            p: (px, py),
+            q: (qx, qy),
+            neg_q: (qx, qy.neg()),
+            r: G2Projective { x: qx, y: qy, z: Fq2::one() },
+        }
+    }
+
+    /// Multiply `f` by the line through the current step, evaluated at P
+    fn ell(&self, f: &Fq12, coeffs: &LineCoeffs) -> Fq12 {
+        let (c0, c1, c2) = coeffs;
+        let c0 = c0.mul_by_fq(&self.p.1);
+        let c1 = c1.mul_by_fq(&self.p.0);
+        let line = Fq12::new(
+            Fq6::new(c0, Fq2::zero(), Fq2::zero()),
+            Fq6::new(c1, *c2, Fq2::zero()),
+        );
+        f.mul(&line)
+    }
+}
+
+type LineCoeffs = (Fq2, Fq2, Fq2);
+
+/// G2 point in homogeneous projective coordinates: (X, Y, Z) ↦ (X/Z, Y/Z)
+struct G2Projective {
+    x: Fq2,
+    y: Fq2,
+    z: Fq2,
+}
+
+impl G2Projective {
+    /// Doubling step; returns the tangent line coefficients
+    fn double_in_place(&mut self, two_inv: &Fq) -> LineCoeffs {
+        let a = self.x.mul(&self.y).mul_by_fq(two_inv);
+        let b = self.y.square();
+        let c = self.z.square();
+        let e = TWIST_B.mul(&c.double().add(&c));
+        let f = e.double().add(&e);
+        let g = b.add(&f).mul_by_fq(two_inv);
+        let h = self.y.add(&self.z).square().sub(&b.add(&c));
+        let i = e.sub(&b);
+        let j = self.x.square();
+        let e_square = e.square();
+
+        self.x = a.mul(&b.sub(&f));
+        self.y = g.square().sub(&e_square.double().add(&e_square));
+        self.z = b.mul(&h);
+
+        (h.neg(), j.double().add(&j), i)
+    }
+
+    /// Addition step with an affine point; returns the chord line coefficients
+    fn add_in_place(&mut self, q: &(Fq2, Fq2)) -> LineCoeffs {
+        let (qx, qy) = q;
+        let theta = self.y.sub(&qy.mul(&self.z));
+        let lambda = self.x.sub(&qx.mul(&self.z));
+        let c = theta.square();
+        let d = lambda.square();
+        let e = lambda.mul(&d);
+        let f = self.z.mul(&c);
+        let g = self.x.mul(&d);
+        let h = e.add(&f).sub(&g.double());
+
+        self.x = lambda.mul(&h);
+        self.y = theta.mul(&g.sub(&h)).sub(&e.mul(&self.y));
+        self.z = self.z.mul(&e);
+        let j = theta.mul(qx).sub(&lambda.mul(qy));
+
+        (lambda, theta.neg(), j)
+    }
+}
+
+/// Untwist-Frobenius-twist endomorphism π on an affine G2 point
+fn mul_by_char(q: &(Fq2, Fq2)) -> (Fq2, Fq2) {
+    (
+        q.0.conjugate().mul(&TWIST_MUL_BY_Q_X),
+        q.1.conjugate().mul(&TWIST_MUL_BY_Q_Y),
+    )
+}
+
+/// Multi-Miller loop over all pairs, sharing the squarings of `f`
+fn miller_loop(pairs: &mut [MillerPair]) -> Fq12 {
+    let two_inv = Fq::from_u64(2).inverse().expect("2 is invertible");
+    let mut f = Fq12::one();
+
+    for i in (1..ATE_LOOP_COUNT.len()).rev() {
+        if i != ATE_LOOP_COUNT.len() - 1 {
+            f = f.square();
+        }
+
+        for pair in pairs.iter_mut() {
+            let coeffs = pair.r.double_in_place(&two_inv);
+            f = pair.ell(&f, &coeffs);
+        }
+
+        let negate = match ATE_LOOP_COUNT[i - 1] {
+            1 => false,
+            -1 => true,
+            _ => continue,
+        };
+        for pair in pairs.iter_mut() {
+            let addend = if negate { pair.neg_q } else { pair.q };
+            let coeffs = pair.r.add_in_place(&addend);
+            f = pair.ell(&f, &coeffs);
+        }
+    }
+
+    // Final additions of π(Q) and -π²(Q)
+    for pair in pairs.iter_mut() {
+        let q1 = mul_by_char(&pair.q);
+        let coeffs = pair.r.add_in_place(&q1);
+        f = pair.ell(&f, &coeffs);
+
+        let q2 = mul_by_char(&q1);
+        let coeffs = pair.r.add_in_place(&(q2.0, q2.1.neg()));
+        f = pair.ell(&f, &coeffs);
+    }
+
+    f
+}
+
+/// `f^((q¹² - 1) / r)`; `None` if `f` is zero
+///
+/// Easy part `(q⁶ - 1)(q² + 1)`, then the hard part `(q⁴ - q² + 1) / r` by
+/// plain square-and-multiply.
+fn final_exponentiation(f: &Fq12) -> Option<Fq12> {
+    let f1 = f.conjugate().mul(&f.inverse()?);
+    let f2 = f1.pow(&Q_SQUARED).mul(&f1);
+    Some(f2.pow(&HARD_EXPONENT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::U256;
+
+    fn u(s: &str) -> U256 {
+        U256::from_dec_str(s).unwrap()
+    }
+
+    fn g1() -> G1Point {
+        G1Point { x: U256::from(1u64), y: U256::from(2u64) }
+    }
+
+    fn g2() -> G2Point {
+        G2Point {
+            x: [
+                u("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+                u("11559732032986387107991004021392285783925812861821192530917403151452391805634"),
+            ],
+            y: [
+                u("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+                u("4082367875863433681332203403145435568316851327593401208105741076214120093531"),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_pairing_bilinearity() {
+        // e(G1, G2) · e(-G1, G2) = 1
+        let neg_g1 = crate::types::negate_g1(&g1());
+        assert!(pairing_check(&[(g1(), g2()), (neg_g1.clone(), g2())]));
+
+        // e(G1, G2) alone is not 1 (non-degeneracy)
+        assert!(!pairing_check(&[(g1(), g2())]));
+
+        // e(2·G1, G2) · e(-G1, G2)² = 1
+        let (x, y) = crate::curve::G1Jacobian::from_affine(&Fq::one(), &Fq::from_u64(2))
+            .double()
+            .to_affine();
+        let two_g1 = G1Point { x: x.to_u256(), y: y.to_u256() };
+        assert!(pairing_check(&[(two_g1.clone(), g2()), (neg_g1.clone(), g2()), (neg_g1, g2())]));
+        assert!(!pairing_check(&[(two_g1, g2()), (crate::types::negate_g1(&g1()), g2())]));
+    }
+
+    #[test]
+    fn test_pairing_identity_and_empty() {
+        assert!(pairing_check(&[]));
+        assert!(pairing_check(&[(G1Point::zero(), g2())]));
+        assert!(pairing_check(&[(g1(), G2Point::zero())]));
+    }
+}
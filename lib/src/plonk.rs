@@ -0,0 +1,903 @@
+//! PLONK verifier for snarkjs `plonk` proofs
+//!
+//! PLONK uses a universal (powers of tau) setup, so new circuits need no
+//! per-circuit ceremony. This module verifies proofs produced by
+//! `snarkjs plonk prove` against keys from `snarkjs zkey export
+//! verificationkey`, following snarkjs' `plonk_verify`:
+//!
+//! 1. Fiat-Shamir challenges β, γ, α, ξ, v, u from a keccak256 transcript
+//!    ([`PlonkChallenges::snarkjs`])
+//! 2. Lagrange evaluations L_1..L_n at ξ, the public input polynomial PI(ξ)
+//!    and the constant part r₀ of the linearization
+//! 3. The linearization commitment D and the batched commitment F
+//! 4. The KZG batch opening at ξ and ξω:
+//!
+//! ```text
+//! e(-(W_ξ + u·W_ξω), [x]₂) · e(ξ·W_ξ + uξω·W_ξω + F - E, [1]₂) = 1
+//! ```
+//!
+//! Everything on the right of the second pairing is folded into a single
+//! `alt_bn128_g1_multiexp` call, so a verification costs two multiexp calls
+//! and one 2-pair `alt_bn128_pairing_check`, independent of the circuit.
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+use crate::backend::{Bn254Backend, DefaultBackend};
+use crate::batch::multiexp;
+use crate::error::{ParseError, PointError, VerifyError, VkError};
+use crate::poseidon::Fr;
+use crate::transcript::{FiatShamirTranscript, PlonkChallenges};
+use crate::types::{parse_field_element, G1Point, G2Point, U256, FR_MODULUS};
+use crate::verifier::parse_inputs;
+
+/// Largest supported domain: Fr has 2^28-th roots of unity
+pub(crate) const MAX_POWER: u32 = 28;
+
+/// Primitive 2^28-th root of unity in Fr: 5^((r - 1) / 2^28)
+const ROOT_OF_UNITY_2_28: Fr = Fr {
+    limbs: [
+        0x9bd61b6e725b19f0,
+        0x402d111e41112ed4,
+        0x00e0a7eb8ef62abc,
+        0x2a3c09f0a58a7e85,
+    ],
+};
+
+/// Generator of G2 (the `[1]₂` of the KZG check)
+pub(crate) const G2_GENERATOR: G2Point = G2Point {
+    x: [
+        U256([0x46debd5cd992f6ed, 0x674322d4f75edadd, 0x426a00665e5c4479, 0x1800deef121f1e76]),
+        U256([0x97e485b7aef312c2, 0xf1aa493335a9e712, 0x7260bfb731fb5d25, 0x198e9393920d483a]),
+    ],
+    y: [
+        U256([0x4ce6cc0166fa7daa, 0xe3d1e7690c43d37b, 0x4aab71808dcb408f, 0x12c85ea5db8c6deb]),
+        U256([0x55acdadcd122975b, 0xbc4b313370b38ef3, 0xec9e99ad690c3395, 0x090689d0585ff075]),
+    ],
+};
+
+/// PLONK verification key
+///
+/// Compatible with the snarkjs `plonk` verification_key.json.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlonkVerificationKey {
+    /// Number of public inputs
+    pub n_public: u32,
+    /// log2 of the domain size n
+    pub power: u32,
+    /// Coset shift of the second wire column
+    pub k1: U256,
+    /// Coset shift of the third wire column
+    pub k2: U256,
+    /// Selector commitments
+    pub qm: G1Point,
+    pub ql: G1Point,
+    pub qr: G1Point,
+    pub qo: G1Point,
+    pub qc: G1Point,
+    /// Permutation commitments
+    pub s1: G1Point,
+    pub s2: G1Point,
+    pub s3: G1Point,
+    /// `[x]₂` from the powers of tau
+    pub x_2: G2Point,
+    /// Generator of the order-n evaluation domain
+    pub w: U256,
+}
+
+impl PlonkVerificationKey {
+    /// Number of public inputs this verification key expects
+    pub fn num_inputs(&self) -> usize {
+        self.n_public as usize
+    }
+
+    /// Check every point and scalar of the key
+    ///
+    /// The commitments must be on G1 (the identity is allowed: an unused
+    /// selector commits to it), `X_2` must be a non-identity point in the G2
+    /// subgroup, and `w` must generate the domain of size `2^power`.
+    pub fn validate(&self) -> Result<(), VkError> {
+        let commitments = [
+            ("Qm", &self.qm),
+            ("Ql", &self.ql),
+            ("Qr", &self.qr),
+            ("Qo", &self.qo),
+            ("Qc", &self.qc),
+            ("S1", &self.s1),
+            ("S2", &self.s2),
+            ("S3", &self.s3),
+        ];
+        for (element, point) in commitments {
+            if !point.is_on_curve() {
+                return Err(VkError::InvalidPoint { element, reason: PointError::NotOnCurve });
+            }
+        }
+        self.x_2
+            .validate()
+            .map_err(|reason| VkError::InvalidPoint { element: "X_2", reason })?;
+
+        for (element, scalar) in [("k1", &self.k1), ("k2", &self.k2), ("w", &self.w)] {
+            if *scalar >= FR_MODULUS {
+                return Err(VkError::NonCanonicalScalar { element });
+            }
+        }
+        if !(1..=MAX_POWER).contains(&self.power) || domain_generator(self.power) != fr(&self.w) {
+            return Err(VkError::InvalidDomain { power: self.power });
+        }
+        Ok(())
+    }
+}
+
+/// PLONK proof
+///
+/// Compatible with the snarkjs `plonk` proof.json.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlonkProof {
+    /// Wire commitments
+    pub a: G1Point,
+    pub b: G1Point,
+    pub c: G1Point,
+    /// Permutation accumulator commitment
+    pub z: G1Point,
+    /// Quotient polynomial commitments (low, middle, high)
+    pub t1: G1Point,
+    pub t2: G1Point,
+    pub t3: G1Point,
+    /// Opening proof at ξ
+    pub wxi: G1Point,
+    /// Opening proof at ξω
+    pub wxiw: G1Point,
+    /// Evaluations at ξ (and ξω for `eval_zw`)
+    pub eval_a: U256,
+    pub eval_b: U256,
+    pub eval_c: U256,
+    pub eval_s1: U256,
+    pub eval_s2: U256,
+    pub eval_zw: U256,
+}
+
+impl PlonkProof {
+    /// Check every proof element before it reaches the host functions
+    ///
+    /// Commitments must be on G1 and evaluations must be canonical scalars.
+    pub fn validate(&self) -> Result<(), VerifyError> {
+        for (element, point) in self.commitments() {
+            if !point.is_on_curve() {
+                return Err(VerifyError::InvalidProofPoint {
+                    element,
+                    reason: PointError::NotOnCurve,
+                });
+            }
+        }
+        for (element, scalar) in self.evaluations() {
+            if *scalar >= FR_MODULUS {
+                return Err(VerifyError::NonCanonicalProofScalar { element });
+            }
+        }
+        Ok(())
+    }
+
+    fn commitments(&self) -> [(&'static str, &G1Point); 9] {
+        [
+            ("A", &self.a),
+            ("B", &self.b),
+            ("C", &self.c),
+            ("Z", &self.z),
+            ("T1", &self.t1),
+            ("T2", &self.t2),
+            ("T3", &self.t3),
+            ("Wxi", &self.wxi),
+            ("Wxiw", &self.wxiw),
+        ]
+    }
+
+    fn evaluations(&self) -> [(&'static str, &U256); 6] {
+        [
+            ("eval_a", &self.eval_a),
+            ("eval_b", &self.eval_b),
+            ("eval_c", &self.eval_c),
+            ("eval_s1", &self.eval_s1),
+            ("eval_s2", &self.eval_s2),
+            ("eval_zw", &self.eval_zw),
+        ]
+    }
+}
+
+/// JSON format for snarkjs `plonk` verification_key.json
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerificationKeyPlonkJson {
+    pub protocol: Option<String>,
+    pub curve: Option<String>,
+    #[serde(rename = "nPublic")]
+    pub n_public: u32,
+    pub power: u32,
+    pub k1: String,
+    pub k2: String,
+    #[serde(rename = "Qm")]
+    pub qm: Vec<String>,
+    #[serde(rename = "Ql")]
+    pub ql: Vec<String>,
+    #[serde(rename = "Qr")]
+    pub qr: Vec<String>,
+    #[serde(rename = "Qo")]
+    pub qo: Vec<String>,
+    #[serde(rename = "Qc")]
+    pub qc: Vec<String>,
+    #[serde(rename = "S1")]
+    pub s1: Vec<String>,
+    #[serde(rename = "S2")]
+    pub s2: Vec<String>,
+    #[serde(rename = "S3")]
+    pub s3: Vec<String>,
+    #[serde(rename = "X_2")]
+    pub x_2: Vec<Vec<String>>,
+    pub w: String,
+}
+
+impl VerificationKeyPlonkJson {
+    /// Convert to PlonkVerificationKey struct
+    pub fn to_vk(&self) -> Result<PlonkVerificationKey, ParseError> {
+        Ok(PlonkVerificationKey {
+            n_public: self.n_public,
+            power: self.power,
+            k1: parse_field_element(&self.k1)?,
+            k2: parse_field_element(&self.k2)?,
+            qm: G1Point::from_json_array(&self.qm)?,
+            ql: G1Point::from_json_array(&self.ql)?,
+            qr: G1Point::from_json_array(&self.qr)?,
+            qo: G1Point::from_json_array(&self.qo)?,
+            qc: G1Point::from_json_array(&self.qc)?,
+            s1: G1Point::from_json_array(&self.s1)?,
+            s2: G1Point::from_json_array(&self.s2)?,
+            s3: G1Point::from_json_array(&self.s3)?,
+            x_2: G2Point::from_json_array(&self.x_2)?,
+            w: parse_field_element(&self.w)?,
+        })
+    }
+}
+
+/// JSON format for snarkjs `plonk` proof files
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProofPlonkJson {
+    #[serde(rename = "A")]
+    pub a: Vec<String>,
+    #[serde(rename = "B")]
+    pub b: Vec<String>,
+    #[serde(rename = "C")]
+    pub c: Vec<String>,
+    #[serde(rename = "Z")]
+    pub z: Vec<String>,
+    #[serde(rename = "T1")]
+    pub t1: Vec<String>,
+    #[serde(rename = "T2")]
+    pub t2: Vec<String>,
+    #[serde(rename = "T3")]
+    pub t3: Vec<String>,
+    #[serde(rename = "Wxi")]
+    pub wxi: Vec<String>,
+    #[serde(rename = "Wxiw")]
+    pub wxiw: Vec<String>,
+    pub eval_a: String,
+    pub eval_b: String,
+    pub eval_c: String,
+    pub eval_s1: String,
+    pub eval_s2: String,
+    pub eval_zw: String,
+    pub protocol: Option<String>,
+    pub curve: Option<String>,
+}
+
+impl ProofPlonkJson {
+    /// Convert to PlonkProof struct
+    pub fn to_proof(&self) -> Result<PlonkProof, ParseError> {
+        Ok(PlonkProof {
+            a: G1Point::from_json_array(&self.a)?,
+            b: G1Point::from_json_array(&self.b)?,
+            c: G1Point::from_json_array(&self.c)?,
+            z: G1Point::from_json_array(&self.z)?,
+            t1: G1Point::from_json_array(&self.t1)?,
+            t2: G1Point::from_json_array(&self.t2)?,
+            t3: G1Point::from_json_array(&self.t3)?,
+            wxi: G1Point::from_json_array(&self.wxi)?,
+            wxiw: G1Point::from_json_array(&self.wxiw)?,
+            eval_a: parse_field_element(&self.eval_a)?,
+            eval_b: parse_field_element(&self.eval_b)?,
+            eval_c: parse_field_element(&self.eval_c)?,
+            eval_s1: parse_field_element(&self.eval_s1)?,
+            eval_s2: parse_field_element(&self.eval_s2)?,
+            eval_zw: parse_field_element(&self.eval_zw)?,
+        })
+    }
+}
+
+impl PlonkChallenges {
+    /// Derive the challenges exactly as snarkjs `plonk_verify` does
+    ///
+    /// Each round hashes the previous challenge(s) followed by the new
+    /// commitments; the first round also binds the verification key and the
+    /// public inputs:
+    ///
+    /// ```text
+    /// β = H(Qm, Ql, Qr, Qo, Qc, S1, S2, S3, inputs, A, B, C)
+    /// γ = H(β)
+    /// α = H(β, γ, Z)
+    /// ξ = H(α, T1, T2, T3)
+    /// v = H(ξ, a, b, c, s1, s2, zw)
+    /// u = H(W_ξ, W_ξω)
+    /// ```
+    pub fn snarkjs<B: Bn254Backend>(
+        backend: B,
+        vk: &PlonkVerificationKey,
+        inputs: &[U256],
+        proof: &PlonkProof,
+    ) -> Self {
+        let mut transcript = FiatShamirTranscript::with_backend(backend);
+
+        for point in [&vk.qm, &vk.ql, &vk.qr, &vk.qo, &vk.qc, &vk.s1, &vk.s2, &vk.s3] {
+            transcript.append_g1(point);
+        }
+        transcript.append_scalars(inputs);
+        for point in [&proof.a, &proof.b, &proof.c] {
+            transcript.append_g1(point);
+        }
+        let beta = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&beta);
+        let gamma = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&beta);
+        transcript.append_scalar(&gamma);
+        transcript.append_g1(&proof.z);
+        let alpha = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&alpha);
+        for point in [&proof.t1, &proof.t2, &proof.t3] {
+            transcript.append_g1(point);
+        }
+        let zeta = transcript.squeeze_challenge();
+
+        transcript.append_scalar(&zeta);
+        for (_, eval) in proof.evaluations() {
+            transcript.append_scalar(eval);
+        }
+        let v = transcript.squeeze_challenge();
+
+        transcript.append_g1(&proof.wxi);
+        transcript.append_g1(&proof.wxiw);
+        let u = transcript.squeeze_challenge();
+
+        Self { beta, gamma, alpha, zeta, v, u }
+    }
+}
+
+/// snarkjs PLONK verifier
+///
+/// Stores the verification key; the backend is not part of the serialized
+/// state.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PlonkVerifier<B = DefaultBackend> {
+    /// The verification key
+    pub vk: PlonkVerificationKey,
+    #[borsh(skip)]
+    #[serde(skip)]
+    backend: B,
+}
+
+impl PlonkVerifier {
+    /// Create a new verifier with the given verification key
+    ///
+    /// The key is not validated; prefer [`PlonkVerifier::try_new`] for keys
+    /// that come from users.
+    pub fn new(vk: PlonkVerificationKey) -> Self {
+        Self::with_backend(vk, DefaultBackend::default())
+    }
+
+    /// Create a verifier after checking every point and scalar of the key
+    pub fn try_new(vk: PlonkVerificationKey) -> Result<Self, VkError> {
+        vk.validate()?;
+        Ok(Self::new(vk))
+    }
+
+    /// Create verifier from a snarkjs `plonk` verification key
+    ///
+    /// Only parses the key; see [`PlonkVerifier::from_json_checked`].
+    pub fn from_json(vk_json: &VerificationKeyPlonkJson) -> Result<Self, ParseError> {
+        Ok(Self::new(vk_json.to_vk()?))
+    }
+
+    /// Parse and validate a snarkjs `plonk` verification key
+    pub fn from_json_checked(vk_json: &VerificationKeyPlonkJson) -> Result<Self, VerifyError> {
+        Ok(Self::try_new(vk_json.to_vk()?)?)
+    }
+}
+
+impl<B: Bn254Backend> PlonkVerifier<B> {
+    /// Create a verifier that runs its curve operations on `backend`
+    ///
+    /// The key is not validated; call [`PlonkVerificationKey::validate`]
+    /// first for keys that come from users.
+    pub fn with_backend(vk: PlonkVerificationKey, backend: B) -> Self {
+        Self { vk, backend }
+    }
+
+    /// The backend this verifier calls
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Verify a PLONK proof
+    ///
+    /// Use [`PlonkVerifier::verify_checked`] to learn why a proof was rejected.
+    pub fn verify(&self, inputs: &[U256], proof: &PlonkProof) -> bool {
+        self.verify_checked(inputs, proof).is_ok()
+    }
+
+    /// Verify a proof with inputs as decimal strings (snarkjs format)
+    pub fn verify_json(&self, proof_json: &ProofPlonkJson, inputs: &[String]) -> bool {
+        self.verify_json_checked(proof_json, inputs).is_ok()
+    }
+
+    /// Verify a PLONK proof, reporting the reason for any failure
+    pub fn verify_checked(&self, inputs: &[U256], proof: &PlonkProof) -> Result<(), VerifyError> {
+        if inputs.len() != self.vk.num_inputs() {
+            return Err(VerifyError::InputCountMismatch {
+                expected: self.vk.num_inputs(),
+                found: inputs.len(),
+            });
+        }
+        if let Some(index) = inputs.iter().position(|x| *x >= FR_MODULUS) {
+            return Err(VerifyError::NonCanonicalInput { index });
+        }
+        proof.validate()?;
+
+        let challenges = PlonkChallenges::snarkjs(&self.backend, &self.vk, inputs, proof);
+        let (neg_a1, b1) = self.opening_points(inputs, proof, &challenges)?;
+
+        // e(-A1, [x]₂) · e(B1, [1]₂) = 1
+        let mut input = [0u8; 2 * 192];
+        input[0..64].copy_from_slice(&neg_a1.to_precompile_bytes());
+        input[64..192].copy_from_slice(&self.vk.x_2.to_precompile_bytes());
+        input[192..256].copy_from_slice(&b1.to_precompile_bytes());
+        input[256..384].copy_from_slice(&G2_GENERATOR.to_precompile_bytes());
+
+        if self.backend.pairing_check(&input) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingFailed)
+        }
+    }
+
+    /// Verify a proof with decimal-string inputs, reporting the reason for any failure
+    pub fn verify_json_checked(
+        &self,
+        proof_json: &ProofPlonkJson,
+        inputs: &[String],
+    ) -> Result<(), VerifyError> {
+        let proof = proof_json.to_proof()?;
+        let inputs = parse_inputs(inputs)?;
+        self.verify_checked(&inputs, &proof)
+    }
+
+    /// The two G1 points of the KZG batch-opening check
+    ///
+    /// ```text
+    /// -A1 = -W_ξ - u·W_ξω
+    ///  B1 = ξ·W_ξ + uξω·W_ξω + D + v·A + v²·B + v³·C + v⁴·S1 + v⁵·S2 - E
+    /// ```
+    ///
+    /// where D is the linearization commitment and E = e·[1]₁ commits to
+    /// the batched evaluations. Each is a single multiexp.
+    fn opening_points(
+        &self,
+        inputs: &[U256],
+        proof: &PlonkProof,
+        challenges: &PlonkChallenges,
+    ) -> Result<(G1Point, G1Point), VerifyError> {
+        let vk = &self.vk;
+        let beta = fr(&challenges.beta);
+        let gamma = fr(&challenges.gamma);
+        let alpha = fr(&challenges.alpha);
+        let xi = fr(&challenges.zeta);
+        let v1 = fr(&challenges.v);
+        let u = fr(&challenges.u);
+        let w = fr(&vk.w);
+        let (a, b, c) = (fr(&proof.eval_a), fr(&proof.eval_b), fr(&proof.eval_c));
+        let (s1, s2, zw) = (fr(&proof.eval_s1), fr(&proof.eval_s2), fr(&proof.eval_zw));
+
+        let v2 = v1.mul(&v1);
+        let v3 = v2.mul(&v1);
+        let v4 = v3.mul(&v1);
+        let v5 = v4.mul(&v1);
+
+        // ξⁿ and Z_H(ξ) = ξⁿ - 1
+        let mut xin = xi;
+        for _ in 0..vk.power {
+            xin = xin.square();
+        }
+        let zh = xin.sub(&Fr::ONE);
+
+        // A challenge inside the domain makes the Lagrange evaluations
+        // undefined; such a proof cannot be valid
+        let lagrange = lagrange_evaluations(&xi, &w, &zh, vk.power, vk.num_inputs().max(1))
+            .ok_or(VerifyError::PairingFailed)?;
+        let l1 = lagrange[0];
+
+        // PI(ξ) = -Σ input_i · L_{i+1}(ξ)
+        let pi = inputs
+            .iter()
+            .zip(&lagrange)
+            .fold(Fr::ZERO, |acc, (x, l)| acc.sub(&fr(x).mul(l)));
+
+        let alpha2 = alpha.square();
+        let e1 = a.add(&beta.mul(&s1)).add(&gamma);
+        let e2 = b.add(&beta.mul(&s2)).add(&gamma);
+        let e3 = c.add(&gamma);
+
+        // r₀ = PI(ξ) - L1(ξ)·α² - α·(a + βs1 + γ)(b + βs2 + γ)(c + γ)·zw
+        let r0 = pi
+            .sub(&l1.mul(&alpha2))
+            .sub(&alpha.mul(&e1).mul(&e2).mul(&e3).mul(&zw));
+
+        // Scalar of Z in D: α(a + βξ + γ)(b + βk1ξ + γ)(c + βk2ξ + γ) + L1α² + u
+        let beta_xi = beta.mul(&xi);
+        let z_scalar = alpha
+            .mul(&a.add(&beta_xi).add(&gamma))
+            .mul(&b.add(&beta_xi.mul(&fr(&vk.k1))).add(&gamma))
+            .mul(&c.add(&beta_xi.mul(&fr(&vk.k2))).add(&gamma))
+            .add(&l1.mul(&alpha2))
+            .add(&u);
+
+        // Scalar of S3 in D: -αβ·zw·(a + βs1 + γ)(b + βs2 + γ)
+        let s3_scalar = alpha.mul(&beta).mul(&zw).mul(&e1).mul(&e2).neg();
+
+        // E = (-r₀ + v·a + v²·b + v³·c + v⁴·s1 + v⁵·s2 + u·zw)·[1]₁
+        let e = r0
+            .neg()
+            .add(&v1.mul(&a))
+            .add(&v2.mul(&b))
+            .add(&v3.mul(&c))
+            .add(&v4.mul(&s1))
+            .add(&v5.mul(&s2))
+            .add(&u.mul(&zw));
+
+        let g1 = G1Point { x: U256::one(), y: U256::from(2u64) };
+        let b1 = multiexp(
+            &self.backend,
+            &[
+                (&proof.wxi, xi),
+                (&proof.wxiw, u.mul(&xi).mul(&w)),
+                (&vk.qm, a.mul(&b)),
+                (&vk.ql, a),
+                (&vk.qr, b),
+                (&vk.qo, c),
+                (&vk.qc, Fr::ONE),
+                (&proof.z, z_scalar),
+                (&vk.s3, s3_scalar),
+                (&proof.t1, zh.neg()),
+                (&proof.t2, zh.mul(&xin).neg()),
+                (&proof.t3, zh.mul(&xin).mul(&xin).neg()),
+                (&proof.a, v1),
+                (&proof.b, v2),
+                (&proof.c, v3),
+                (&vk.s1, v4),
+                (&vk.s2, v5),
+                (&g1, e.neg()),
+            ],
+        )?;
+
+        let neg_a1 = multiexp(
+            &self.backend,
+            &[(&proof.wxi, Fr::ONE.neg()), (&proof.wxiw, u.neg())],
+        )?;
+
+        Ok((neg_a1, b1))
+    }
+}
+
+/// L_i(ξ) = ωⁱ⁻¹·Z_H(ξ) / (n·(ξ - ωⁱ⁻¹)) for i = 1..=count
+///
+/// The denominators are inverted together (one field inversion);
+/// `None` if ξ is in the domain.
+pub(crate) fn lagrange_evaluations(
+    xi: &Fr,
+    w: &Fr,
+    zh: &Fr,
+    power: u32,
+    count: usize,
+) -> Option<Vec<Fr>> {
+    let n = Fr::from_u64(1u64 << power);
+
+    let mut numerators = Vec::with_capacity(count);
+    let mut denominators = Vec::with_capacity(count);
+    let mut w_i = Fr::ONE;
+    for _ in 0..count {
+        numerators.push(w_i.mul(zh));
+        denominators.push(n.mul(&xi.sub(&w_i)));
+        w_i = w_i.mul(w);
+    }
+    if !Fr::batch_inverse(&mut denominators) {
+        return None;
+    }
+    Some(numerators.iter().zip(&denominators).map(|(num, den)| num.mul(den)).collect())
+}
+
+/// Generator of the order-2^power subgroup of Fr*
+pub(crate) fn domain_generator(power: u32) -> Fr {
+    let mut w = ROOT_OF_UNITY_2_28;
+    for _ in power..MAX_POWER {
+        w = w.square();
+    }
+    w
+}
+
+/// Canonical U256 scalar as an Fr element
+pub(crate) fn fr(x: &U256) -> Fr {
+    Fr { limbs: x.0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_generator() {
+        // ω has order exactly 2^power
+        for power in [1, 3, 11, MAX_POWER] {
+            let w = domain_generator(power);
+            let mut x = w;
+            for _ in 1..power {
+                x = x.square();
+            }
+            assert_ne!(x, Fr::ONE, "power {}", power);
+            assert_eq!(x.square(), Fr::ONE, "power {}", power);
+        }
+        assert_eq!(domain_generator(1), Fr::ONE.neg());
+    }
+
+    fn g1() -> G1Point {
+        G1Point { x: U256::one(), y: U256::from(2u64) }
+    }
+
+    fn test_vk(n_public: u32) -> PlonkVerificationKey {
+        PlonkVerificationKey {
+            n_public,
+            power: 3,
+            k1: U256::from(2u64),
+            k2: U256::from(3u64),
+            qm: g1(),
+            ql: g1(),
+            qr: g1(),
+            qo: g1(),
+            qc: G1Point::zero(),
+            s1: g1(),
+            s2: g1(),
+            s3: g1(),
+            x_2: G2_GENERATOR,
+            w: U256(domain_generator(3).limbs),
+        }
+    }
+
+    fn test_proof() -> PlonkProof {
+        PlonkProof {
+            a: g1(),
+            b: g1(),
+            c: g1(),
+            z: g1(),
+            t1: g1(),
+            t2: g1(),
+            t3: g1(),
+            wxi: g1(),
+            wxiw: g1(),
+            eval_a: U256::from(1u64),
+            eval_b: U256::from(2u64),
+            eval_c: U256::from(3u64),
+            eval_s1: U256::from(4u64),
+            eval_s2: U256::from(5u64),
+            eval_zw: U256::from(6u64),
+        }
+    }
+
+    // ------------------------------------------------------------------
+    // End-to-end fixture
+    // ------------------------------------------------------------------
+    //
+    // A 2-public-input circuit (x = w·w, y = w + x with w = 3) on an n = 8
+    // domain, scripts/fixtures/square.circom. snarkjs is not available in
+    // this build environment, so the key and proof were produced by a
+    // standalone re-implementation of snarkjs 0.7 `plonk_prove` (same
+    // blinding layout, quotient split and keccak256 transcript) with a
+    // throwaway powers-of-tau trapdoor, in snarkjs' verification_key.json /
+    // proof.json / public.json format. scripts/fixtures/snarkjs.sh runs the
+    // real snarkjs on the same circuit; its output replaces these three
+    // constants as is.
+
+    const FIXTURE_VK: &str = r#"{
+        "protocol": "plonk",
+        "curve": "bn128",
+        "nPublic": 2,
+        "power": 3,
+        "k1": "2",
+        "k2": "3",
+        "Qm": ["12650134903636724957618620815854226558514255017954242809169520109726203014456", "14928306300488227500514943791527010367050887935100857961396189769523048950893", "1"],
+        "Ql": ["4173263901367398656204183780690369015955685912218716856332814385121002116928", "20162528199585998761972159881541980131189074585650037001477802629975125096121", "1"],
+        "Qr": ["5175793446377897648015321846316529785116391977771136022577183067120883564505", "11035922010444415542461045747611650269940827457153156089376380351193192070455", "1"],
+        "Qo": ["17528529275404936428183463354599805446863254131795388441361466742658173683698", "11838376135167717334006910658732806471777209812683966214163431056758658173645", "1"],
+        "Qc": ["0", "1", "0"],
+        "S1": ["13474689810101727590400757290773755005965443307083399289451367483533906958606", "9121772369150091903374041602821562536773211080321314687449462457439834051388", "1"],
+        "S2": ["8693174088301280460564871008128164950810167901899951123720878407784222834724", "20198366189660516398278911057613433732496946141697034816855078882908380658037", "1"],
+        "S3": ["6589343103244176077495396351360334835007952539530184688173495411067525823801", "8235243486970634855068372524861758718961760941353374230909955027631976387059", "1"],
+        "X_2": [["6469001724733979498919319397742645479242735207663015079982368602950344725159", "7921376370851223342395328519530041440875779511467460079126771421741327053580"], ["4131001511424341351220456064158473830191408260707560813879152181261066324148", "19776109961398337787919632542212753475992629354846025132661107487312612223659"], ["1", "0"]],
+        "w": "19540430494807482326159819597004422086093766032135589407132600596362845576832"
+    }"#;
+
+    const FIXTURE_PROOF: &str = r#"{
+        "A": ["11614914899723810034494735814207888798717377235022210545299992154895846087305", "2927520114757309025374793392784206286513279385351110195365117675319106122125", "1"],
+        "B": ["16126118960187894715628012984278654027456255867198145379702916467476338613744", "14389767764956808335183483453998488899953045675493683167106448664866856366985", "1"],
+        "C": ["13645356712689775721306652919172521769630681650677851570236593844160533532141", "17011493396077757716633465435476345657711884957852679796438037072866174663026", "1"],
+        "Z": ["4049740279482298123386851440819817492057231788967053213157683048621872019404", "19616638553447265871437611847172385794150486542475613844848195167044858133349", "1"],
+        "T1": ["8459598068118317891081480491755285967866881301201805236327187848271018715842", "17125445866720454446022046900208900850968987892256157745967534432076402141590", "1"],
+        "T2": ["12253260204980586194128863779620041806469685385723287020572556807837754639705", "20341579047329657704080196571281101423665364566219906417690365881652752823734", "1"],
+        "T3": ["15665358242769112951005703751068252040535991713714680994451842678913849534359", "13792744765248338915601178667934525561394149791315109482945762806779411174669", "1"],
+        "Wxi": ["15968368219217157561759544185788508075004460598630495794329467487217225703495", "19716639442570971998429850341944409216748540323235693741627601858011032128830", "1"],
+        "Wxiw": ["7256120241953605674785563525438352950727603598645034507896643715144413481187", "1672878299233469729043336153729630206134843358108617648897324384158197568179", "1"],
+        "eval_a": "18749033479990585306214431395006481102734623368867427716420743243640134419237",
+        "eval_b": "7364601312250694567560500149027847113869936557942237741831999848206980277687",
+        "eval_c": "2492865394972008332740638672482592232566980720474848765844646365301872994924",
+        "eval_s1": "7424831818291243004729317095372236539129453729094415534844484422599586169886",
+        "eval_s2": "7530068635144260507774555264047503779881521409913059599022235545941421162297",
+        "eval_zw": "11203058753593331531589486290668140822132048692237682429624710942038499207854",
+        "protocol": "plonk",
+        "curve": "bn128"
+    }"#;
+
+    const FIXTURE_PUBLIC: [&str; 2] = ["9", "12"];
+
+    fn fixture() -> (PlonkVerifier, ProofPlonkJson, Vec<String>) {
+        let vk: VerificationKeyPlonkJson = near_sdk::serde_json::from_str(FIXTURE_VK).unwrap();
+        let proof: ProofPlonkJson = near_sdk::serde_json::from_str(FIXTURE_PROOF).unwrap();
+        let inputs = FIXTURE_PUBLIC.iter().map(|s| s.to_string()).collect();
+        (PlonkVerifier::from_json_checked(&vk).unwrap(), proof, inputs)
+    }
+
+    #[test]
+    fn test_valid_proof_verifies() {
+        let (verifier, proof, inputs) = fixture();
+        assert_eq!(verifier.verify_json_checked(&proof, &inputs), Ok(()));
+        assert!(verifier.verify_json(&proof, &inputs));
+    }
+
+    #[test]
+    fn test_changed_public_input_rejected() {
+        let (verifier, proof, mut inputs) = fixture();
+        inputs[1] = "13".to_string();
+        assert_eq!(
+            verifier.verify_json_checked(&proof, &inputs),
+            Err(VerifyError::PairingFailed)
+        );
+    }
+
+    #[test]
+    fn test_vk_validation() {
+        assert!(G2_GENERATOR.validate().is_ok());
+        assert!(test_vk(1).validate().is_ok());
+
+        let mut vk = test_vk(1);
+        vk.w = U256::from(5u64);
+        assert_eq!(vk.validate().unwrap_err(), VkError::InvalidDomain { power: 3 });
+
+        let mut vk = test_vk(1);
+        vk.power = MAX_POWER + 1;
+        assert_eq!(vk.validate().unwrap_err(), VkError::InvalidDomain { power: MAX_POWER + 1 });
+
+        let mut vk = test_vk(1);
+        vk.k1 = FR_MODULUS;
+        assert_eq!(vk.validate().unwrap_err(), VkError::NonCanonicalScalar { element: "k1" });
+
+        let mut vk = test_vk(1);
+        vk.s2.y = U256::from(3u64);
+        assert_eq!(
+            vk.validate().unwrap_err(),
+            VkError::InvalidPoint { element: "S2", reason: PointError::NotOnCurve }
+        );
+    }
+
+    #[test]
+    fn test_malformed_requests_rejected() {
+        let verifier = PlonkVerifier::new(test_vk(1));
+        let inputs = [U256::from(7u64)];
+
+        assert_eq!(
+            verifier.verify_checked(&[], &test_proof()).unwrap_err(),
+            VerifyError::InputCountMismatch { expected: 1, found: 0 }
+        );
+        assert_eq!(
+            verifier.verify_checked(&[FR_MODULUS], &test_proof()).unwrap_err(),
+            VerifyError::NonCanonicalInput { index: 0 }
+        );
+
+        let mut proof = test_proof();
+        proof.eval_s2 = FR_MODULUS;
+        assert_eq!(
+            verifier.verify_checked(&inputs, &proof).unwrap_err(),
+            VerifyError::NonCanonicalProofScalar { element: "eval_s2" }
+        );
+
+        let mut proof = test_proof();
+        proof.t3.x = U256::from(2u64);
+        assert_eq!(
+            verifier.verify_checked(&inputs, &proof).unwrap_err(),
+            VerifyError::InvalidProofPoint { element: "T3", reason: PointError::NotOnCurve }
+        );
+    }
+
+    #[test]
+    fn test_invalid_proof_fails_pairing() {
+        use crate::backend::{BackendCall, NearBackend, RecordingBackend};
+
+        let verifier = PlonkVerifier::with_backend(test_vk(1), RecordingBackend::new(NearBackend));
+        assert_eq!(
+            verifier.verify_checked(&[U256::from(7u64)], &test_proof()).unwrap_err(),
+            VerifyError::PairingFailed
+        );
+
+        // Six transcript hashes, two multiexps, one 2-pair pairing check
+        let calls = verifier.backend().take_calls();
+        assert_eq!(calls.len(), 9);
+        assert!(calls[..6].iter().all(|call| matches!(call, BackendCall::Keccak256(_))));
+        assert!(matches!(calls[6], BackendCall::G1Multiexp(_)));
+        assert!(matches!(calls[7], BackendCall::G1Multiexp(_)));
+        assert!(matches!(&calls[8], BackendCall::PairingCheck(input) if input.len() == 2 * 192));
+    }
+
+    #[test]
+    fn test_json_parsing() {
+        let g1 = r#"["1", "2", "1"]"#;
+        let vk_json = format!(
+            r#"{{"protocol": "plonk", "curve": "bn128", "nPublic": 1, "power": 3,
+                "k1": "2", "k2": "3", "Qm": {g1}, "Ql": {g1}, "Qr": {g1}, "Qo": {g1},
+                "Qc": ["0", "1", "0"], "S1": {g1}, "S2": {g1}, "S3": {g1},
+                "X_2": [["{x0}", "{x1}"], ["{y0}", "{y1}"], ["1", "0"]], "w": "{w}"}}"#,
+            x0 = G2_GENERATOR.x[0],
+            x1 = G2_GENERATOR.x[1],
+            y0 = G2_GENERATOR.y[0],
+            y1 = G2_GENERATOR.y[1],
+            w = domain_generator(3).to_string(),
+        );
+        let vk_json: VerificationKeyPlonkJson = near_sdk::serde_json::from_str(&vk_json).unwrap();
+        let verifier = PlonkVerifier::from_json_checked(&vk_json).unwrap();
+        assert_eq!(verifier.vk.w, test_vk(1).w);
+        assert!(verifier.vk.qc.is_zero());
+
+        let proof_json = format!(
+            r#"{{"A": {g1}, "B": {g1}, "C": {g1}, "Z": {g1}, "T1": {g1}, "T2": {g1},
+                "T3": {g1}, "Wxi": {g1}, "Wxiw": {g1}, "eval_a": "1", "eval_b": "2",
+                "eval_c": "3", "eval_s1": "4", "eval_s2": "5", "eval_zw": "0x6",
+                "protocol": "plonk", "curve": "bn128"}}"#
+        );
+        let proof_json: ProofPlonkJson = near_sdk::serde_json::from_str(&proof_json).unwrap();
+        assert_eq!(proof_json.to_proof().unwrap().eval_zw, U256::from(6u64));
+        assert_eq!(
+            verifier.verify_json_checked(&proof_json, &["x".to_string()]).unwrap_err(),
+            VerifyError::InvalidInput { index: 0 }
+        );
+    }
+
+    #[test]
+    fn test_lagrange_evaluations() {
+        // Σ L_i(ξ) over the whole domain is 1
+        let power = 3;
+        let w = domain_generator(power);
+        let xi = Fr::from_u64(123456789);
+        let zh = xi.pow(&[8, 0, 0, 0]).sub(&Fr::ONE);
+        let l = lagrange_evaluations(&xi, &w, &zh, power, 8).unwrap();
+        assert_eq!(l.iter().fold(Fr::ZERO, |acc, x| acc.add(x)), Fr::ONE);
+
+        // ξ in the domain: L_i(ξ) is undefined
+        assert!(lagrange_evaluations(&w, &w, &Fr::ZERO, power, 2).is_none());
+    }
+}
@@ -9,12 +9,30 @@
  * - Security level: 128 bits
  * - S-box: x^5
  * - Full rounds (RF): 8 (4 at beginning, 4 at end)
- * - Partial rounds (RP): 57 for t=3, 60 for t=5
+ * - Partial rounds (RP): 57 for t=3, 60 for t=5, see `N_ROUNDS_P` for all widths
  * 
+ * `poseidon` covers every circomlib width, t=2..17 (`Poseidon(1)` to
+ * `Poseidon(16)`). The 2-input hash runs `poseidon_t3_opt`, circomlibjs'
+ * `poseidon_opt` form; `poseidon_t3` is the reference it is tested against.
+ *
+ * Field multiplications per 2-input hash:
+ *
+ * |                     | full rounds | partial rounds | total | depth-20 path |
+ * |---------------------|-------------|----------------|-------|---------------|
+ * | `poseidon_t3`       | 8 x 18      | 57 x 12        | 828   | 16,560        |
+ * | `poseidon_t3_opt`   | 8 x 18      | 57 x 8         | 600   | 12,000        |
+ *
+ * Each round spends 3 multiplications per S-box. A partial round's dense
+ * 3x3 matrix (9 multiplications) becomes a sparse one (3 for the first row,
+ * 2 for the first column), so a hash and a Merkle path need 28% fewer
+ * multiplications. Measured in wasm, one hash costs 5.06 TGas instead of
+ * 7.18 TGas, and a depth-20 `IncrementalMerkleTree::insert` 101 TGas
+ * instead of 144 TGas (see docs/GAS_OPTIMIZATION.md).
+ *
  * Reference: https://github.com/iden3/circomlibjs/blob/main/src/poseidon.js
  */
 
-use crate::poseidon_precomputed::{C_T3_FR, M_T3_FR};
+use crate::poseidon_precomputed::*;
 
 /// BN254 scalar field modulus
 const MODULUS: [u64; 4] = [
@@ -406,6 +424,63 @@ impl Fr {
         x4.mul(self)
     }
 
+    /// -x mod p
+    pub fn neg(&self) -> Self {
+        Fr::ZERO.sub(self)
+    }
+
+    /// x² mod p
+    #[inline]
+    pub fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    /// x^exp mod p, exponent as little-endian limbs (not constant-time)
+    pub fn pow(&self, exp: &[u64; 4]) -> Self {
+        let mut result = Fr::ONE;
+        for limb in exp.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                if (limb >> bit) & 1 == 1 {
+                    result = result.mul(self);
+                }
+            }
+        }
+        result
+    }
+
+    /// Multiplicative inverse x^(p-2) mod p; `None` for zero
+    pub fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let mut exp = MODULUS;
+        exp[0] -= 2;
+        Some(self.pow(&exp))
+    }
+
+    /// Invert every element in place with one field inversion (Montgomery's trick)
+    ///
+    /// Returns `false` and leaves `values` untouched if any element is zero.
+    pub fn batch_inverse(values: &mut [Fr]) -> bool {
+        let mut prefix = Vec::with_capacity(values.len());
+        let mut acc = Fr::ONE;
+        for x in values.iter() {
+            prefix.push(acc);
+            acc = acc.mul(x);
+        }
+        let mut inv = match acc.inverse() {
+            Some(inv) => inv,
+            None => return false,
+        };
+        for (x, p) in values.iter_mut().zip(prefix).rev() {
+            let x_inv = inv.mul(&p);
+            inv = inv.mul(x);
+            *x = x_inv;
+        }
+        true
+    }
+
     // ========================================================================
     // Helper functions
     // ========================================================================
@@ -612,6 +687,9 @@ impl<const T: usize> PoseidonState<T> {
 
 /// Poseidon hash for t=3 (2-input hash, used for Merkle tree)
 /// Uses pre-computed Fr constants for efficiency
+///
+/// Reference implementation, one dense MDS multiplication per round; hashing
+/// code should use [`poseidon_t3_opt`], which gives the same output.
 pub fn poseidon_t3(inputs: &[Fr]) -> Fr {
     assert!(inputs.len() <= 2, "Too many inputs for t=3 Poseidon");
 
@@ -683,6 +761,66 @@ pub fn poseidon_t3(inputs: &[Fr]) -> Fr {
     state[0]
 }
 
+/// Poseidon hash for t=3 in circomlibjs' `poseidon_opt` form
+///
+/// Same output as [`poseidon_t3`]. The constants of each round are added
+/// after the previous S-box instead of before the next one, which leaves a
+/// single constant per partial round, and the 57 partial-round matrices are
+/// factored into one dense pre-sparse matrix (applied once, after the first
+/// half of full rounds) and 57 sparse ones: identity except for the first
+/// row and column.
+pub fn poseidon_t3_opt(inputs: &[Fr]) -> Fr {
+    assert!(inputs.len() <= 2, "Too many inputs for t=3 Poseidon");
+
+    const T: usize = 3;
+    const N_ROUNDS_F: usize = 8;
+
+    let c = &C_T3_OPT_FR;
+    let mut state = [Fr::ZERO; T];
+    state[1..=inputs.len()].copy_from_slice(inputs);
+    for (x, c) in state.iter_mut().zip(&c[..T]) {
+        *x = x.add(c);
+    }
+    let mut k = T;
+
+    // First half of full rounds; the last one mixes with the pre-sparse matrix
+    for round in 0..N_ROUNDS_F / 2 {
+        for (x, c) in state.iter_mut().zip(&c[k..k + T]) {
+            *x = x.pow5().add(c);
+        }
+        k += T;
+        let m = if round == N_ROUNDS_F / 2 - 1 { &P_T3_OPT_FR } else { &M_T3_FR };
+        state = mds_multiply_t3(&state, m);
+    }
+
+    // Partial rounds: S-box and one constant on state[0], then a sparse matrix
+    for s in S_T3_OPT_FR.iter() {
+        state[0] = state[0].pow5().add(&c[k]);
+        k += 1;
+        let first = state[0].mul(&s[0]).add(&state[1].mul(&s[1])).add(&state[2].mul(&s[2]));
+        state[1] = state[1].add(&state[0].mul(&s[3]));
+        state[2] = state[2].add(&state[0].mul(&s[4]));
+        state[0] = first;
+    }
+
+    // Second half of full rounds; the last one adds no constant
+    for round in 0..N_ROUNDS_F / 2 {
+        for x in state.iter_mut() {
+            *x = x.pow5();
+        }
+        if round < N_ROUNDS_F / 2 - 1 {
+            for (x, c) in state.iter_mut().zip(&c[k..k + T]) {
+                *x = x.add(c);
+            }
+            k += T;
+        }
+        state = mds_multiply_t3(&state, &M_T3_FR);
+    }
+    debug_assert_eq!(k, c.len());
+
+    state[0]
+}
+
 /// MDS matrix multiplication for t=3
 fn mds_multiply_t3(state: &[Fr; 3], m: &[[Fr; 3]; 3]) -> [Fr; 3] {
     let mut result = [Fr::ZERO; 3];
@@ -697,6 +835,83 @@ fn mds_multiply_t3(state: &[Fr; 3], m: &[[Fr; 3]; 3]) -> [Fr; 3] {
     result
 }
 
+/// Partial rounds for t=2..17, as in circomlib
+pub const N_ROUNDS_P: [usize; 16] = [56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68];
+
+/// Poseidon hash for t=4 (3-input hash, circomlib `Poseidon(3)`)
+///
+/// Used for the leaves of circomlib's sparse Merkle tree. Missing inputs are
+/// zero.
+pub fn poseidon_t4(inputs: &[Fr]) -> Fr {
+    assert!(inputs.len() <= 3, "Too many inputs for t=4 Poseidon");
+
+    let mut padded = [Fr::ZERO; 3];
+    padded[..inputs.len()].copy_from_slice(inputs);
+    poseidon(&padded)
+}
+
+/// circomlib `Poseidon(n)` of `n = inputs.len()` inputs, 1 to 16
+///
+/// The width is `t = n + 1`, so `poseidon(&[a, b])` is `poseidon_t3_opt`
+/// and `poseidon(&[a, b, c, d])` is the 4-input hash circuits get from
+/// `Poseidon(4)`.
+pub fn poseidon(inputs: &[Fr]) -> Fr {
+    match inputs.len() {
+        1 => sponge(inputs, &C_T2_FR, &M_T2_FR),
+        2 => poseidon_t3_opt(inputs),
+        3 => sponge(inputs, &C_T4_FR, &M_T4_FR),
+        4 => sponge(inputs, &C_T5_FR, &M_T5_FR),
+        5 => sponge(inputs, &C_T6_FR, &M_T6_FR),
+        6 => sponge(inputs, &C_T7_FR, &M_T7_FR),
+        7 => sponge(inputs, &C_T8_FR, &M_T8_FR),
+        8 => sponge(inputs, &C_T9_FR, &M_T9_FR),
+        9 => sponge(inputs, &C_T10_FR, &M_T10_FR),
+        10 => sponge(inputs, &C_T11_FR, &M_T11_FR),
+        11 => sponge(inputs, &C_T12_FR, &M_T12_FR),
+        12 => sponge(inputs, &C_T13_FR, &M_T13_FR),
+        13 => sponge(inputs, &C_T14_FR, &M_T14_FR),
+        14 => sponge(inputs, &C_T15_FR, &M_T15_FR),
+        15 => sponge(inputs, &C_T16_FR, &M_T16_FR),
+        16 => sponge(inputs, &C_T17_FR, &M_T17_FR),
+        n => panic!("Poseidon takes 1 to 16 inputs, got {}", n),
+    }
+}
+
+/// State `[0, inputs...]` through the width-`T` permutation; returns `state[0]`
+fn sponge<const T: usize>(inputs: &[Fr], c: &[Fr], m: &[[Fr; T]; T]) -> Fr {
+    let mut state = [Fr::ZERO; T];
+    state[1..].copy_from_slice(inputs);
+    permute(state, c, m, N_ROUNDS_P[T - 2])[0]
+}
+
+/// Poseidon permutation for width `T`: 4 full rounds, `n_rounds_p` partial
+/// rounds, 4 full rounds
+fn permute<const T: usize>(mut state: [Fr; T], c: &[Fr], m: &[[Fr; T]; T], n_rounds_p: usize) -> [Fr; T] {
+    const N_ROUNDS_F: usize = 8;
+
+    for round in 0..N_ROUNDS_F + n_rounds_p {
+        for (i, x) in state.iter_mut().enumerate() {
+            *x = x.add(&c[round * T + i]);
+        }
+        if round < N_ROUNDS_F / 2 || round >= N_ROUNDS_F / 2 + n_rounds_p {
+            for x in state.iter_mut() {
+                *x = x.pow5();
+            }
+        } else {
+            state[0] = state[0].pow5();
+        }
+
+        let mut mixed = [Fr::ZERO; T];
+        for (i, row) in m.iter().enumerate() {
+            for (x, m_ij) in state.iter().zip(row) {
+                mixed[i] = mixed[i].add(&x.mul(m_ij));
+            }
+        }
+        state = mixed;
+    }
+    state
+}
+
 // ============================================================================
 // PUBLIC API
 // ============================================================================
@@ -705,7 +920,7 @@ fn mds_multiply_t3(state: &[Fr; 3], m: &[[Fr; 3]; 3]) -> [Fr; 3] {
 pub fn poseidon_hash2(left: &str, right: &str) -> String {
     let a = Fr::from_str(left);
     let b = Fr::from_str(right);
-    let result = poseidon_t3(&[a, b]);
+    let result = poseidon_t3_opt(&[a, b]);
     result.to_string()
 }
 
@@ -802,8 +1017,18 @@ pub fn debug_mul(a: &str, b: &str) -> Vec<String> {
     debug_output
 }
 
+/// Hash any number (1 to 16) of field element strings with circomlib
+/// `Poseidon(n)`
+pub fn poseidon_hash(inputs: &[&str]) -> String {
+    let inputs: Vec<Fr> = inputs.iter().map(|input| Fr::from_str(input)).collect();
+    poseidon(&inputs).to_string()
+}
+
 /// Hash four field element strings (for note commitment)
 /// Uses tree hashing: hash(hash(a,b), hash(c,d))
+///
+/// This is *not* circomlib `Poseidon(4)`; circuits hashing with
+/// `Poseidon(4)` need [`poseidon_hash`] or [`compute_commitment_poseidon4`].
 pub fn poseidon_hash4(a: &str, b: &str, c: &str, d: &str) -> String {
     let h1 = poseidon_hash2(a, b);
     let h2 = poseidon_hash2(c, d);
@@ -821,6 +1046,21 @@ pub fn compute_commitment(
     poseidon_hash4(nullifier, secret, amount, asset_id)
 }
 
+/// Compute note commitment with a single 4-input hash
+/// commitment = Poseidon(4)(nullifier, secret, amount, assetId)
+///
+/// For circuits whose commitment is `Poseidon(4)`, as in
+/// `docs/INTEGRATION.md`, rather than the nested 2-input hashes of
+/// [`compute_commitment`].
+pub fn compute_commitment_poseidon4(
+    nullifier: &str,
+    secret: &str,
+    amount: &str,
+    asset_id: &str,
+) -> String {
+    poseidon_hash(&[nullifier, secret, amount, asset_id])
+}
+
 /// Compute nullifier hash
 /// nullifierHash = Poseidon(nullifier, leafIndex)
 pub fn compute_nullifier_hash(nullifier: &str, leaf_index: u64) -> String {
@@ -864,6 +1104,23 @@ mod tests {
         assert_eq!(b.to_string(), "243"); // 3^5 = 243
     }
 
+    #[test]
+    fn test_fr_inverse() {
+        let a = Fr::from_u64(7);
+        assert_eq!(a.inverse().unwrap().mul(&a), Fr::ONE);
+        assert_eq!(a.neg().add(&a), Fr::ZERO);
+        assert_eq!(a.pow(&[3, 0, 0, 0]).to_string(), "343");
+        assert_eq!(Fr::ZERO.inverse(), None);
+
+        let mut values = [Fr::from_u64(2), a, Fr::from_u64(9)];
+        assert!(Fr::batch_inverse(&mut values));
+        assert_eq!(values[1], a.inverse().unwrap());
+        assert_eq!(values[2].mul(&Fr::from_u64(9)), Fr::ONE);
+        let mut values = [a, Fr::ZERO];
+        assert!(!Fr::batch_inverse(&mut values));
+        assert_eq!(values, [a, Fr::ZERO]);
+    }
+
     #[test]
     fn test_poseidon_basic() {
         // Test that Poseidon produces a deterministic output
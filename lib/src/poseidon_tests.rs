@@ -10,7 +10,9 @@
 
 #[cfg(test)]
 mod poseidon_tests {
-    use crate::poseidon::{poseidon_hash2, poseidon_hash4, compute_commitment, compute_nullifier_hash, Fr, poseidon_t3};
+    use crate::poseidon::{poseidon_hash2, poseidon_hash4, compute_commitment, compute_nullifier_hash, Fr, poseidon_t3, poseidon_t4};
+    use crate::poseidon::{compute_commitment_poseidon4, poseidon, poseidon_hash, poseidon_t3_opt};
+    use super::test_vectors::{HASH_1_2_3, POSEIDON_N};
 
     /// Test vectors from circomlibjs
     /// These MUST match or proofs will fail!
@@ -57,6 +59,56 @@ mod poseidon_tests {
         assert_ne!(h1, h3, "hash(1,2) should differ from hash(1,3)");
     }
 
+    #[test]
+    fn test_poseidon_t4_vector() {
+        let hash = poseidon_t4(&[Fr::from_u64(1), Fr::from_u64(2), Fr::from_u64(3)]);
+        assert_eq!(hash.to_string(), HASH_1_2_3);
+        // Missing inputs are zero, as in the t=3 hash
+        assert_eq!(poseidon_t4(&[Fr::from_u64(1)]), poseidon_t4(&[Fr::from_u64(1), Fr::ZERO, Fr::ZERO]));
+    }
+
+    #[test]
+    fn test_poseidon_all_widths() {
+        let mut widths: Vec<usize> = POSEIDON_N.iter().map(|(inputs, _)| inputs.len()).collect();
+        widths.dedup();
+        assert_eq!(widths, (1..=16).collect::<Vec<_>>());
+
+        for (inputs, expected) in POSEIDON_N {
+            let inputs: Vec<Fr> = inputs.iter().map(|&i| Fr::from_u64(i)).collect();
+            assert_eq!(poseidon(&inputs).to_string(), *expected, "Poseidon({})", inputs.len());
+        }
+        let two = [Fr::from_u64(12345), Fr::from_u64(67890)];
+        assert_eq!(poseidon(&two), poseidon_t3(&two));
+
+        // Poseidon(4) differs from the nested 2-input commitment
+        let native = compute_commitment_poseidon4("1", "2", "3", "4");
+        assert_eq!(native, poseidon_hash(&["1", "2", "3", "4"]));
+        assert_eq!(native, POSEIDON_N[4].1);
+        assert_ne!(native, compute_commitment("1", "2", "3", "4"));
+    }
+
+    #[test]
+    fn test_poseidon_t3_opt_matches_reference() {
+        let p_minus_1 = Fr::from_str("21888242871839275222246405745257275088548364400416034343698204186575808495616");
+        let mut cases = vec![vec![], vec![Fr::ONE], vec![Fr::ZERO, Fr::ZERO], vec![p_minus_1, Fr::ONE]];
+        let mut x = Fr::from_u64(7);
+        for _ in 0..16 {
+            let y = x.mul(&x).add(&Fr::from_u64(3));
+            cases.push(vec![x, y]);
+            x = y.pow5();
+        }
+        for inputs in &cases {
+            assert_eq!(poseidon_t3_opt(inputs), poseidon_t3(inputs));
+        }
+        assert_eq!(poseidon_t3_opt(&[Fr::from_u64(1), Fr::from_u64(2)]).to_string(), POSEIDON_N[1].1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Poseidon takes 1 to 16 inputs, got 17")]
+    fn test_poseidon_too_many_inputs() {
+        poseidon(&[Fr::ONE; 17]);
+    }
+
     #[test]
     fn test_merkle_tree_zeros() {
         // Compute the zero values for an empty Merkle tree
@@ -153,6 +205,60 @@ pub mod test_vectors {
     /// ```
     pub const HASH_1_2: &str = "7853200120776062878684798364095072458815029376092732009249414926327459813530";
     
+    /// Poseidon(1, 2, 3) with t=4 parameters: `poseidon([1n, 2n, 3n])`
+    pub const HASH_1_2_3: &str = "6542985608222806190361240322586112750744169038454362455181422643027100751666";
+
+    /// `poseidon(inputs)`, at least one entry per width t=2..17
+    ///
+    /// The entries for 1-6 and 16 inputs are circomlibjs' own test vectors.
+    /// Those for 7-15 inputs come from `scripts/poseidon_reference.py`, which
+    /// derives the constants with the reference Grain LFSR generator rather
+    /// than reading our tables, and reproduces every circomlibjs entry.
+    pub const POSEIDON_N: &[(&[u64], &str)] = &[
+        (&[1], "18586133768512220936620570745912940619677854269274689475585506675881198879027"),
+        (&[1, 2], "7853200120776062878684798364095072458815029376092732009249414926327459813530"),
+        (&[3, 4], "14763215145315200506921711489642608356394854266165572616578112107564877678998"),
+        (&[1, 2, 3], "6542985608222806190361240322586112750744169038454362455181422643027100751666"),
+        (&[1, 2, 3, 4], "18821383157269793795438455681495246036402687001665670618754263018637548127333"),
+        (&[1, 2, 0, 0, 0], "1018317224307729531995786483840663576608797660851238720571059489595066344487"),
+        (&[3, 4, 5, 10, 23], "13034429309846638789535561449942021891039729847501137143363028890275222221409"),
+        (&[1, 2, 3, 4, 5, 6], "20400040500897583745843009878988256314335038853985262692600694741116813247201"),
+        (&[1, 2, 3, 4, 5, 6, 7], "12748163991115452309045839028154629052133952896122405799815156419278439301912"),
+        (&[1, 2, 3, 4, 5, 6, 7, 8], "18604317144381847857886385684060986177838410221561136253933256952257712543953"),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9],
+            "13589767895268936107593642967621470491511464502761040466226072462545218539640",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
+            "3657500514307717306974218405144578736633140001277925127187636780142269815841",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            "3572015662710076994097916907865950486270383304442561406230608893458731714472",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12],
+            "2501997477381648492950318384533644783248002172679259592360114615426357826485",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13],
+            "7041832639553862712666971417715061873827921493498355005117622707743491651590",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            "8354478399926161176778659061636406690034081872658507739535256090879947077494",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            "4203130618016961831408770638653325366880478848856764494148034853759773445968",
+        ),
+        (
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+            "9989051620750914585850546081941653841776809718687451684622678807385399211877",
+        ),
+    ];
+
     /// Poseidon(0, 0)
     pub const HASH_0_0: &str = "14744269619966411208579211824598458697587494354926760081771325075741142829156";
     
@@ -0,0 +1,196 @@
+//! Groth16 verifier that keeps the verification key in precompile layout
+//!
+//! [`Verifier`] stores the key as `U256` coordinates and converts every point
+//! to the little-endian alt_bn128 layout on each call. `PreparedVerifier`
+//! does that conversion once, at construction, and stores the result in
+//! contract state. Verification then only copies stored bytes into the
+//! multiexp and pairing buffers:
+//!
+//! - α and β are stored back to back, exactly as pair 2 of the pairing input
+//! - γ, δ and every IC point are stored as ready-to-copy byte arrays
+//! - the pairing input is a fixed 768-byte stack buffer, with no allocation
+//!
+//! The host calls are the same as for [`Verifier`], and so is the gas:
+//! re-encoding the key is a tiny part of a verification. Measured in Wasm
+//! for a 2-input key, a verification costs 42.97 TGas against 42.99 TGas
+//! with [`Verifier`], dominated by the host calls and the proof's G2
+//! subgroup check. It is not a gas optimization; use it only if the byte
+//! layout is convenient. Like [`Verifier`], it runs on [`DefaultBackend`]
+//! unless built with [`PreparedVerifier::with_backend`].
+//!
+//! ```rust,ignore
+//! #[near(contract_state)]
+//! pub struct Contract {
+//!     verifier: PreparedVerifier,
+//! }
+//!
+//! #[init]
+//! pub fn new(vk: VerificationKeyJson) -> Self {
+//!     let verifier = PreparedVerifier::from_json_checked(&vk)
+//!         .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
+//!     Self { verifier }
+//! }
+//! ```
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::error::{VerifyError, VkError};
+use crate::backend::{Bn254Backend, DefaultBackend};
+use crate::types::{negate_g1, G1Point, Proof, ProofJson, VerificationKey, VerificationKeyJson, U256};
+use crate::verifier::{check_request, g1_sum, parse_inputs, Verifier, PAIRING_INPUT_LEN};
+
+/// Groth16 verifier with the verification key cached in alt_bn128 byte layout
+///
+/// The backend is not part of the serialized state.
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct PreparedVerifier<B = DefaultBackend> {
+    /// α (G1) followed by β (G2): pair 2 of the pairing input, verbatim
+    alpha_beta: [u8; 192],
+    /// γ (G2)
+    gamma: [u8; 128],
+    /// δ (G2)
+    delta: [u8; 128],
+    /// IC points (G1); IC[0] is the base
+    ic: Vec<[u8; 64]>,
+    #[borsh(skip)]
+    backend: B,
+}
+
+impl PreparedVerifier {
+    /// Encode a verification key; the key is not validated
+    pub fn new(vk: &VerificationKey) -> Self {
+        Self::with_backend(vk, DefaultBackend::default())
+    }
+
+    /// Validate every point of the key, then encode it
+    pub fn try_new(vk: &VerificationKey) -> Result<Self, VkError> {
+        vk.validate()?;
+        Ok(Self::new(vk))
+    }
+
+    /// Parse and validate a snarkjs verification key
+    pub fn from_json_checked(vk_json: &VerificationKeyJson) -> Result<Self, VerifyError> {
+        Ok(Self::try_new(&vk_json.to_vk()?)?)
+    }
+}
+
+impl<B: Bn254Backend> PreparedVerifier<B> {
+    /// Encode a verification key for curve operations on `backend`
+    ///
+    /// The key is not validated; call [`VerificationKey::validate`] first for
+    /// keys that come from users.
+    pub fn with_backend(vk: &VerificationKey, backend: B) -> Self {
+        let mut alpha_beta = [0u8; 192];
+        alpha_beta[..64].copy_from_slice(&vk.alpha.to_precompile_bytes());
+        alpha_beta[64..].copy_from_slice(&vk.beta.to_precompile_bytes());
+
+        Self {
+            alpha_beta,
+            gamma: vk.gamma.to_precompile_bytes(),
+            delta: vk.delta.to_precompile_bytes(),
+            ic: vk.ic.iter().map(G1Point::to_precompile_bytes).collect(),
+            backend,
+        }
+    }
+
+    /// The backend this verifier calls
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Number of public inputs this verification key expects
+    pub fn num_inputs(&self) -> usize {
+        self.ic.len().saturating_sub(1)
+    }
+
+    /// Verify a Groth16 proof
+    pub fn verify(&self, inputs: &[U256], proof: &Proof) -> bool {
+        self.verify_checked(inputs, proof).is_ok()
+    }
+
+    /// Verify a proof with inputs as decimal strings (snarkjs format)
+    pub fn verify_json(&self, proof_json: &ProofJson, inputs: &[String]) -> bool {
+        self.verify_json_checked(proof_json, inputs).is_ok()
+    }
+
+    /// Verify a Groth16 proof, reporting the reason for any failure
+    pub fn verify_checked(&self, inputs: &[U256], proof: &Proof) -> Result<(), VerifyError> {
+        check_request(self.num_inputs(), inputs, proof)?;
+
+        let vk_x = self.compute_vk_x(inputs)?;
+
+        if self.backend.pairing_check(&self.pairing_input(proof, &vk_x)) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingFailed)
+        }
+    }
+
+    /// Verify a proof with decimal-string inputs, reporting the reason for any failure
+    pub fn verify_json_checked(
+        &self,
+        proof_json: &ProofJson,
+        inputs: &[String],
+    ) -> Result<(), VerifyError> {
+        let proof = proof_json.to_proof()?;
+        let inputs = parse_inputs(inputs)?;
+        self.verify_checked(&inputs, &proof)
+    }
+
+    /// vk_x = IC[0] + Σ(input[i] * IC[i+1]), in precompile layout
+    fn compute_vk_x(&self, inputs: &[U256]) -> Result<[u8; 64], VerifyError> {
+        let ic0 = self.ic.first().ok_or(VerifyError::MissingIcPoint { index: 0 })?;
+
+        // Format: point (64 bytes) + scalar (32 bytes), zero inputs skipped
+        let mut multiexp_input = Vec::with_capacity(inputs.len() * 96);
+        for (i, input) in inputs.iter().enumerate() {
+            if input.is_zero() {
+                continue;
+            }
+            let ic = self
+                .ic
+                .get(i + 1)
+                .ok_or(VerifyError::MissingIcPoint { index: i + 1 })?;
+            multiexp_input.extend_from_slice(ic);
+            multiexp_input.extend_from_slice(&input.to_le_bytes());
+        }
+
+        if multiexp_input.is_empty() {
+            return Ok(*ic0);
+        }
+
+        let sum: [u8; 64] = self
+            .backend
+            .g1_multiexp(&multiexp_input)
+            .try_into()
+            .map_err(|_| VerifyError::MultiexpFailed)?;
+
+        g1_sum(&self.backend, ic0, &sum)
+    }
+
+    /// e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ), built from cached bytes
+    pub(crate) fn pairing_input(&self, proof: &Proof, vk_x: &[u8; 64]) -> [u8; PAIRING_INPUT_LEN] {
+        let mut input = [0u8; PAIRING_INPUT_LEN];
+        input[0..64].copy_from_slice(&negate_g1(&proof.a).to_precompile_bytes());
+        input[64..192].copy_from_slice(&proof.b.to_precompile_bytes());
+        input[192..384].copy_from_slice(&self.alpha_beta);
+        input[384..448].copy_from_slice(vk_x);
+        input[448..576].copy_from_slice(&self.gamma);
+        input[576..640].copy_from_slice(&proof.c.to_precompile_bytes());
+        input[640..768].copy_from_slice(&self.delta);
+        input
+    }
+}
+
+impl<B: Bn254Backend + Clone> From<&Verifier<B>> for PreparedVerifier<B> {
+    fn from(verifier: &Verifier<B>) -> Self {
+        Self::with_backend(&verifier.vk, verifier.backend().clone())
+    }
+}
+
+impl<B: Bn254Backend + Clone> Verifier<B> {
+    /// Encode this verifier's key into a [`PreparedVerifier`] on the same backend
+    pub fn prepare(&self) -> PreparedVerifier<B> {
+        PreparedVerifier::from(self)
+    }
+}
@@ -0,0 +1,301 @@
+//! Multi-circuit verifier registry
+//!
+//! A single `verifier: Verifier` field is enough for one circuit, but a pool
+//! with separate deposit, withdraw and transfer circuits needs one key per
+//! circuit. [`VerifierRegistry`] keeps them in contract storage, keyed by a
+//! circuit id:
+//!
+//! - metadata ([`CircuitInfo`]: input count, version, enabled flag) in an
+//!   `IterableMap`, so it can be listed and checked without loading a key
+//! - verifiers in a `LookupMap`, read only when a proof is actually verified
+//!
+//! Registering an id again replaces its key and bumps the version;
+//! deprecating it makes every later verification fail with
+//! [`RegistryError::CircuitDeprecated`] until a new key is registered. Every
+//! change emits a NEP-297 [`RegistryEvent`].
+//!
+//! The registry does no access control: gate `register` / `deprecate`
+//! behind the contract's owner check.
+//!
+//! ```rust,ignore
+//! #[near(contract_state)]
+//! pub struct Pool {
+//!     circuits: VerifierRegistry,
+//! }
+//!
+//! // in `new`: VerifierRegistry::new(b"r")
+//! self.circuits.register_json("withdraw", &vk)?;
+//! self.circuits.verify_json_checked("withdraw", &proof, &public_inputs)?;
+//! ```
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::store::{IterableMap, LookupMap};
+use near_sdk::{near, IntoStorageKey};
+
+use crate::error::{RegistryError, VerifyError};
+use crate::types::{Proof, ProofJson, VerificationKey, VerificationKeyJson, U256};
+use crate::verifier::{parse_inputs, Verifier};
+
+/// Per-circuit metadata, readable without loading the verification key
+#[derive(Clone, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CircuitInfo {
+    /// Number of public inputs the key expects
+    pub num_inputs: u32,
+    /// Starts at 1, incremented every time the id is registered again
+    pub version: u32,
+    /// `false` once the circuit has been deprecated
+    pub enabled: bool,
+}
+
+/// Events emitted on every registry change
+#[near(event_json(standard = "nep297"))]
+pub enum RegistryEvent {
+    #[event_version("1.0.0")]
+    CircuitRegistered {
+        circuit_id: String,
+        version: u32,
+        num_inputs: u32,
+    },
+    #[event_version("1.0.0")]
+    CircuitDeprecated {
+        circuit_id: String,
+        version: u32,
+    },
+}
+
+/// Verification keys for several circuits, keyed by circuit id
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct VerifierRegistry {
+    circuits: IterableMap<String, CircuitInfo>,
+    verifiers: LookupMap<String, Verifier>,
+}
+
+impl VerifierRegistry {
+    /// Create an empty registry; both collections live under `prefix`
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        let prefix = prefix.into_storage_key();
+        Self {
+            circuits: IterableMap::new([prefix.as_slice(), b"c"].concat()),
+            verifiers: LookupMap::new([prefix.as_slice(), b"k"].concat()),
+        }
+    }
+
+    /// Register a validated key under `circuit_id`
+    ///
+    /// An existing id gets the new key, the next version and is enabled
+    /// again, whether or not it was deprecated.
+    pub fn register(
+        &mut self,
+        circuit_id: &str,
+        vk: VerificationKey,
+    ) -> Result<CircuitInfo, RegistryError> {
+        let verifier = Verifier::try_new(vk)?;
+        let info = CircuitInfo {
+            num_inputs: verifier.vk.num_inputs() as u32,
+            version: self.circuits.get(circuit_id).map_or(1, |info| info.version + 1),
+            enabled: true,
+        };
+
+        self.circuits.insert(circuit_id.to_string(), info.clone());
+        self.verifiers.insert(circuit_id.to_string(), verifier);
+
+        RegistryEvent::CircuitRegistered {
+            circuit_id: circuit_id.to_string(),
+            version: info.version,
+            num_inputs: info.num_inputs,
+        }
+        .emit();
+
+        Ok(info)
+    }
+
+    /// [`VerifierRegistry::register`] for a snarkjs JSON key
+    pub fn register_json(
+        &mut self,
+        circuit_id: &str,
+        vk: &VerificationKeyJson,
+    ) -> Result<CircuitInfo, RegistryError> {
+        let vk = vk.to_vk().map_err(RegistryError::MalformedKey)?;
+        self.register(circuit_id, vk)
+    }
+
+    /// Stop accepting proofs for `circuit_id`
+    ///
+    /// The key is kept so that [`VerifierRegistry::verifier`] still returns it
+    /// for audits; registering the id again re-enables it.
+    pub fn deprecate(&mut self, circuit_id: &str) -> Result<(), RegistryError> {
+        let info = self
+            .circuits
+            .get_mut(circuit_id)
+            .ok_or(RegistryError::UnknownCircuit)?;
+        if !info.enabled {
+            return Err(RegistryError::CircuitDeprecated { version: info.version });
+        }
+        info.enabled = false;
+
+        RegistryEvent::CircuitDeprecated {
+            circuit_id: circuit_id.to_string(),
+            version: info.version,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Metadata for `circuit_id`
+    pub fn info(&self, circuit_id: &str) -> Option<&CircuitInfo> {
+        self.circuits.get(circuit_id)
+    }
+
+    /// The verifier registered for `circuit_id`, deprecated or not
+    pub fn verifier(&self, circuit_id: &str) -> Option<&Verifier> {
+        self.verifiers.get(circuit_id)
+    }
+
+    /// All registered circuits, deprecated ones included
+    pub fn circuits(&self) -> impl Iterator<Item = (&String, &CircuitInfo)> {
+        self.circuits.iter()
+    }
+
+    /// Number of registered circuits
+    pub fn len(&self) -> u32 {
+        self.circuits.len()
+    }
+
+    /// `true` if no circuit has been registered
+    pub fn is_empty(&self) -> bool {
+        self.circuits.is_empty()
+    }
+
+    /// Verify a proof against the active key of `circuit_id`
+    pub fn verify(&self, circuit_id: &str, inputs: &[U256], proof: &Proof) -> bool {
+        self.verify_checked(circuit_id, inputs, proof).is_ok()
+    }
+
+    /// Verify a proof against the active key of `circuit_id`, reporting the
+    /// reason for any failure
+    pub fn verify_checked(
+        &self,
+        circuit_id: &str,
+        inputs: &[U256],
+        proof: &Proof,
+    ) -> Result<(), RegistryError> {
+        let verifier = self.active(circuit_id, inputs.len())?;
+        Ok(verifier.verify_checked(inputs, proof)?)
+    }
+
+    /// Verify a snarkjs JSON proof with decimal-string inputs
+    pub fn verify_json(&self, circuit_id: &str, proof: &ProofJson, inputs: &[String]) -> bool {
+        self.verify_json_checked(circuit_id, proof, inputs).is_ok()
+    }
+
+    /// Verify a snarkjs JSON proof, reporting the reason for any failure
+    pub fn verify_json_checked(
+        &self,
+        circuit_id: &str,
+        proof: &ProofJson,
+        inputs: &[String],
+    ) -> Result<(), RegistryError> {
+        let verifier = self.active(circuit_id, inputs.len())?;
+        let proof = proof.to_proof().map_err(VerifyError::from)?;
+        let inputs = parse_inputs(inputs)?;
+        Ok(verifier.verify_checked(&inputs, &proof)?)
+    }
+
+    /// The verifier for an enabled circuit, after checking the input count
+    /// against the metadata so that a bad request never loads the key
+    fn active(&self, circuit_id: &str, num_inputs: usize) -> Result<&Verifier, RegistryError> {
+        let info = self.circuits.get(circuit_id).ok_or(RegistryError::UnknownCircuit)?;
+        if !info.enabled {
+            return Err(RegistryError::CircuitDeprecated { version: info.version });
+        }
+        if num_inputs != info.num_inputs as usize {
+            return Err(VerifyError::InputCountMismatch {
+                expected: info.num_inputs as usize,
+                found: num_inputs,
+            }
+            .into());
+        }
+        Ok(self
+            .verifiers
+            .get(circuit_id)
+            .expect("every registered circuit has a verifier"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::VkError;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+    use near_sdk::test_utils::get_logs;
+
+    #[test]
+    fn test_register_and_verify() {
+        let (verifier, proof, inputs) = fixture();
+        let mut registry = VerifierRegistry::new(b"r");
+        assert!(registry.is_empty());
+
+        let info = registry.register("withdraw", verifier.vk.clone()).unwrap();
+        assert_eq!(info, CircuitInfo { num_inputs: 2, version: 1, enabled: true });
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.info("withdraw"), Some(&info));
+        assert!(get_logs()[0].starts_with("EVENT_JSON:"));
+        assert!(get_logs()[0].contains(r#""event":"circuit_registered""#));
+
+        assert_eq!(registry.verify_json_checked("withdraw", &proof, &inputs), Ok(()));
+        let parsed = parse_inputs(&inputs).unwrap();
+        assert!(registry.verify("withdraw", &parsed, &proof.to_proof().unwrap()));
+        assert_eq!(
+            registry.verify_json_checked("deposit", &proof, &inputs),
+            Err(RegistryError::UnknownCircuit)
+        );
+        assert_eq!(
+            registry.verify_json_checked("withdraw", &proof, &inputs[..1]),
+            Err(RegistryError::Verify(VerifyError::InputCountMismatch { expected: 2, found: 1 }))
+        );
+    }
+
+    #[test]
+    fn test_deprecate_and_reregister() {
+        let (verifier, proof, inputs) = fixture();
+        let mut registry = VerifierRegistry::new(b"r");
+        registry.register("transfer", verifier.vk.clone()).unwrap();
+
+        registry.deprecate("transfer").unwrap();
+        assert!(get_logs()[1].contains(r#""event":"circuit_deprecated""#));
+        assert!(!registry.info("transfer").unwrap().enabled);
+        assert!(registry.verifier("transfer").is_some());
+        assert_eq!(
+            registry.verify_json_checked("transfer", &proof, &inputs),
+            Err(RegistryError::CircuitDeprecated { version: 1 })
+        );
+        assert_eq!(
+            registry.deprecate("transfer"),
+            Err(RegistryError::CircuitDeprecated { version: 1 })
+        );
+        assert_eq!(registry.deprecate("deposit"), Err(RegistryError::UnknownCircuit));
+
+        let info = registry.register("transfer", verifier.vk.clone()).unwrap();
+        assert_eq!((info.version, info.enabled), (2, true));
+        assert!(registry.verify_json("transfer", &proof, &inputs));
+        assert_eq!(registry.circuits().count(), 1);
+    }
+
+    #[test]
+    fn test_invalid_key_rejected() {
+        let (verifier, _, _) = fixture();
+        let mut registry = VerifierRegistry::new(b"r");
+
+        let mut vk = verifier.vk.clone();
+        vk.ic.clear();
+        assert_eq!(
+            registry.register("deposit", vk),
+            Err(RegistryError::InvalidKey(VkError::EmptyIc))
+        );
+        assert!(registry.is_empty());
+        assert!(get_logs().is_empty());
+    }
+}
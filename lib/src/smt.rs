@@ -0,0 +1,558 @@
+//! Poseidon sparse Merkle tree, compatible with circomlib's `SMT*` circuits
+//!
+//! A key/value map whose root commits to both what is in the set and what
+//! is not: revocation lists, blocklists, nullifier accumulators. The layout
+//! is circomlibjs' `SMT`:
+//!
+//! - a leaf is `Poseidon(key, value, 1)`, an internal node
+//!   `Poseidon(left, right)`, an empty subtree `0`
+//! - bit `i` of the key, least significant first, picks the child at depth `i`
+//! - a leaf sits at the shallowest depth where no other key shares its path,
+//!   so roots do not depend on the insertion order
+//!
+//! Proofs feed `SMTVerifier(nLevels)` directly:
+//!
+//! ```rust,ignore
+//! let mut revoked = SparseMerkleTree::new();
+//! revoked.insert(credential_id, U256::one())?;
+//!
+//! let proof = revoked.proof(&holder_credential)?; // non-membership
+//! assert!(!proof.found && verify_smt_proof(&proof));
+//! let witness = near_sdk::serde_json::to_string(&proof.to_json(10)?)?;
+//! ```
+//!
+//! Nodes live in an [`SmtStore`]: a `BTreeMap` off-chain, or a `LookupMap`
+//! in contract storage ([`StoredSparseMerkleTree`]), where replaced nodes are
+//! removed so storage stays proportional to the number of keys.
+//!
+//! **Gas**: an update computes one Poseidon hash per level of the key's path,
+//! about `log2(keys)` on average.
+
+use std::collections::BTreeMap;
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::store::LookupMap;
+use near_sdk::IntoStorageKey;
+
+use crate::error::{MerkleError, ParseError};
+use crate::merkle::hash_pair;
+use crate::poseidon::{poseidon_t4, Fr};
+use crate::types::{parse_field_element, FR_MODULUS, U256};
+
+/// `Poseidon(key, value, 1)`, the hash of a leaf
+pub fn hash_leaf(key: &U256, value: &U256) -> U256 {
+    U256(poseidon_t4(&[Fr { limbs: key.0 }, Fr { limbs: value.0 }, Fr::ONE]).limbs)
+}
+
+/// A stored node, keyed by its hash
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub enum SmtNode {
+    Middle { left: U256, right: U256 },
+    Leaf { key: U256, value: U256 },
+}
+
+/// Where a [`SparseMerkleTree`] keeps its nodes
+pub trait SmtStore {
+    fn get_node(&self, hash: &U256) -> Option<SmtNode>;
+    fn put_node(&mut self, hash: U256, node: SmtNode);
+    fn remove_node(&mut self, hash: &U256);
+}
+
+impl SmtStore for BTreeMap<U256, SmtNode> {
+    fn get_node(&self, hash: &U256) -> Option<SmtNode> {
+        self.get(hash).copied()
+    }
+
+    fn put_node(&mut self, hash: U256, node: SmtNode) {
+        self.insert(hash, node);
+    }
+
+    fn remove_node(&mut self, hash: &U256) {
+        self.remove(hash);
+    }
+}
+
+impl SmtStore for LookupMap<[u8; 32], SmtNode> {
+    fn get_node(&self, hash: &U256) -> Option<SmtNode> {
+        self.get(&hash.to_be_bytes()).copied()
+    }
+
+    fn put_node(&mut self, hash: U256, node: SmtNode) {
+        self.insert(hash.to_be_bytes(), node);
+    }
+
+    fn remove_node(&mut self, hash: &U256) {
+        self.remove(&hash.to_be_bytes());
+    }
+}
+
+/// Sparse Merkle tree in contract storage, one slot per node
+pub type StoredSparseMerkleTree = SparseMerkleTree<LookupMap<[u8; 32], SmtNode>>;
+
+/// Poseidon sparse Merkle tree over BN254 field elements
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct SparseMerkleTree<S = BTreeMap<U256, SmtNode>> {
+    root: U256,
+    nodes: S,
+}
+
+/// Where a key's path ends
+struct Walk {
+    /// Sibling at each depth, root first
+    siblings: Vec<U256>,
+    /// Internal nodes on the path, root first
+    path: Vec<U256>,
+    /// The leaf at the end of the path, or `None` for an empty subtree
+    leaf: Option<(U256, U256)>,
+}
+
+impl SparseMerkleTree {
+    /// Empty in-memory tree
+    pub fn new() -> Self {
+        Self::with_store(BTreeMap::new())
+    }
+}
+
+impl Default for SparseMerkleTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StoredSparseMerkleTree {
+    /// Empty tree whose nodes live in storage under `prefix`
+    pub fn stored<P: IntoStorageKey>(prefix: P) -> Self {
+        Self::with_store(LookupMap::new(prefix))
+    }
+}
+
+impl<S: SmtStore> SparseMerkleTree<S> {
+    /// Empty tree over `nodes`, which must not hold nodes of another tree
+    pub fn with_store(nodes: S) -> Self {
+        Self { root: U256::zero(), nodes }
+    }
+
+    /// Current root, `0` for an empty tree
+    pub fn root(&self) -> U256 {
+        self.root
+    }
+
+    /// Value stored under `key`
+    pub fn get(&self, key: &U256) -> Option<U256> {
+        match self.walk(key).leaf {
+            Some((found, value)) if found == *key => Some(value),
+            _ => None,
+        }
+    }
+
+    /// `true` if the tree holds `key`
+    pub fn contains(&self, key: &U256) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Add a new key
+    pub fn insert(&mut self, key: U256, value: U256) -> Result<(), MerkleError> {
+        check_canonical(&key, &value)?;
+        let Walk { mut siblings, path, leaf } = self.walk(&key);
+        if let Some((old_key, old_value)) = leaf {
+            if old_key == key {
+                return Err(MerkleError::KeyExists);
+            }
+            // Both leaves move down to the first bit where the keys differ
+            while old_key.bit(siblings.len()) == key.bit(siblings.len()) {
+                siblings.push(U256::zero());
+            }
+            siblings.push(hash_leaf(&old_key, &old_value));
+        }
+
+        self.remove_all(&path);
+        let leaf = self.put_leaf(key, value);
+        self.rebuild(&key, &siblings, leaf);
+        Ok(())
+    }
+
+    /// Replace the value of an existing key; returns the old value
+    pub fn update(&mut self, key: U256, value: U256) -> Result<U256, MerkleError> {
+        check_canonical(&key, &value)?;
+        let Walk { siblings, path, leaf } = self.walk(&key);
+        let old_value = match leaf {
+            Some((found, old_value)) if found == key => old_value,
+            _ => return Err(MerkleError::KeyNotFound),
+        };
+
+        self.remove_all(&path);
+        self.nodes.remove_node(&hash_leaf(&key, &old_value));
+        let leaf = self.put_leaf(key, value);
+        self.rebuild(&key, &siblings, leaf);
+        Ok(old_value)
+    }
+
+    /// Remove a key; returns its value
+    pub fn delete(&mut self, key: &U256) -> Result<U256, MerkleError> {
+        let Walk { mut siblings, path, leaf } = self.walk(key);
+        let old_value = match leaf {
+            Some((found, old_value)) if found == *key => old_value,
+            _ => return Err(MerkleError::KeyNotFound),
+        };
+
+        self.remove_all(&path);
+        self.nodes.remove_node(&hash_leaf(key, &old_value));
+
+        // A leaf left alone in its subtree moves up past the empty levels
+        let mut node = U256::zero();
+        if let Some(sibling) = siblings.last() {
+            if let Some(SmtNode::Leaf { .. }) = self.nodes.get_node(sibling) {
+                node = *sibling;
+                siblings.pop();
+                while siblings.last().is_some_and(U256::is_zero) {
+                    siblings.pop();
+                }
+            }
+        }
+        self.rebuild(key, &siblings, node);
+        Ok(old_value)
+    }
+
+    /// Membership proof of `key`, or a non-membership proof if the tree
+    /// does not hold it
+    pub fn proof(&self, key: &U256) -> Result<SmtProof, MerkleError> {
+        if *key >= FR_MODULUS {
+            return Err(MerkleError::NonCanonicalLeaf);
+        }
+        let Walk { siblings, leaf, .. } = self.walk(key);
+        let (found, value, old_leaf) = match leaf {
+            Some((found, value)) if found == *key => (true, value, None),
+            other => (false, U256::zero(), other),
+        };
+        Ok(SmtProof { root: self.root, siblings, key: *key, value, found, old_leaf })
+    }
+
+    fn walk(&self, key: &U256) -> Walk {
+        let mut walk = Walk { siblings: Vec::new(), path: Vec::new(), leaf: None };
+        let mut node = self.root;
+        while !node.is_zero() {
+            match self.node(&node) {
+                SmtNode::Leaf { key, value } => {
+                    walk.leaf = Some((key, value));
+                    break;
+                }
+                SmtNode::Middle { left, right } => {
+                    walk.path.push(node);
+                    if key.bit(walk.siblings.len()) {
+                        walk.siblings.push(left);
+                        node = right;
+                    } else {
+                        walk.siblings.push(right);
+                        node = left;
+                    }
+                }
+            }
+        }
+        walk
+    }
+
+    fn node(&self, hash: &U256) -> SmtNode {
+        self.nodes
+            .get_node(hash)
+            .unwrap_or_else(|| near_sdk::env::panic_str("sparse Merkle tree node missing from store"))
+    }
+
+    fn put_leaf(&mut self, key: U256, value: U256) -> U256 {
+        let hash = hash_leaf(&key, &value);
+        self.nodes.put_node(hash, SmtNode::Leaf { key, value });
+        hash
+    }
+
+    fn remove_all(&mut self, hashes: &[U256]) {
+        for hash in hashes {
+            self.nodes.remove_node(hash);
+        }
+    }
+
+    /// Hash `node` up along `key`'s path and store the new internal nodes
+    fn rebuild(&mut self, key: &U256, siblings: &[U256], mut node: U256) {
+        for (level, sibling) in siblings.iter().enumerate().rev() {
+            let (left, right) = if key.bit(level) { (*sibling, node) } else { (node, *sibling) };
+            node = hash_pair(&left, &right);
+            self.nodes.put_node(node, SmtNode::Middle { left, right });
+        }
+        self.root = node;
+    }
+}
+
+fn check_canonical(key: &U256, value: &U256) -> Result<(), MerkleError> {
+    if *key >= FR_MODULUS || *value >= FR_MODULUS {
+        return Err(MerkleError::NonCanonicalLeaf);
+    }
+    Ok(())
+}
+
+/// Membership or non-membership witness of one key
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmtProof {
+    pub root: U256,
+    /// Sibling at each depth, root first, up to where the key's path ends
+    pub siblings: Vec<U256>,
+    pub key: U256,
+    /// The key's value if `found`, else `0`
+    pub value: U256,
+    /// `true` for a membership proof
+    pub found: bool,
+    /// Non-membership: the other leaf where the key's path ends, or `None`
+    /// if it ends in an empty subtree
+    pub old_leaf: Option<(U256, U256)>,
+}
+
+impl SmtProof {
+    /// Root implied by the siblings and the leaf the path ends at
+    pub fn compute_root(&self) -> U256 {
+        let leaf = if self.found { Some((self.key, self.value)) } else { self.old_leaf };
+        let start = leaf.map_or(U256::zero(), |(key, value)| hash_leaf(&key, &value));
+        self.siblings.iter().enumerate().rev().fold(start, |node, (level, sibling)| {
+            if self.key.bit(level) {
+                hash_pair(sibling, &node)
+            } else {
+                hash_pair(&node, sibling)
+            }
+        })
+    }
+
+    /// `SMTVerifier(levels)` input, siblings padded with zeros
+    ///
+    /// The circuit needs a zero last sibling, so `levels` must exceed the
+    /// number of siblings. `enabled` is left to the caller's circuit.
+    pub fn to_json(&self, levels: usize) -> Result<SmtProofJson, MerkleError> {
+        if self.siblings.len() >= levels {
+            return Err(MerkleError::TooManySiblings { levels, siblings: self.siblings.len() });
+        }
+        let mut siblings: Vec<String> = self.siblings.iter().map(U256::to_string).collect();
+        siblings.resize(levels, "0".to_string());
+        let (old_key, old_value) = self.old_leaf.unwrap_or_default();
+        Ok(SmtProofJson {
+            root: self.root.to_string(),
+            siblings,
+            old_key: old_key.to_string(),
+            old_value: old_value.to_string(),
+            is_old0: (!self.found && self.old_leaf.is_none()) as u8,
+            key: self.key.to_string(),
+            value: self.value.to_string(),
+            fnc: (!self.found) as u8,
+        })
+    }
+}
+
+/// `true` if `proof` leads to its root
+///
+/// A non-membership proof ending at another leaf must show a different key
+/// on the same path, as `SMTVerifier` checks.
+pub fn verify_smt_proof(proof: &SmtProof) -> bool {
+    if proof.found && proof.old_leaf.is_some() {
+        return false;
+    }
+    if let (false, Some((old_key, _))) = (proof.found, proof.old_leaf) {
+        let shares_path = (0..proof.siblings.len()).all(|i| old_key.bit(i) == proof.key.bit(i));
+        if old_key == proof.key || !shares_path {
+            return false;
+        }
+    }
+    proof.compute_root() == proof.root
+}
+
+/// `SMTVerifier` input signals of a [`SmtProof`], as decimal strings
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde", rename_all = "camelCase")]
+pub struct SmtProofJson {
+    pub root: String,
+    pub siblings: Vec<String>,
+    pub old_key: String,
+    pub old_value: String,
+    pub is_old0: u8,
+    pub key: String,
+    pub value: String,
+    /// 0: membership, 1: non-membership
+    pub fnc: u8,
+}
+
+impl SmtProofJson {
+    /// Parse the field elements; trailing zero siblings are dropped
+    pub fn to_proof(&self) -> Result<SmtProof, ParseError> {
+        let mut siblings = self
+            .siblings
+            .iter()
+            .map(|sibling| parse_field_element(sibling))
+            .collect::<Result<Vec<_>, _>>()?;
+        while siblings.last().is_some_and(U256::is_zero) {
+            siblings.pop();
+        }
+        let found = self.fnc == 0;
+        let old_leaf = if found || self.is_old0 == 1 {
+            None
+        } else {
+            Some((parse_field_element(&self.old_key)?, parse_field_element(&self.old_value)?))
+        };
+        Ok(SmtProof {
+            root: parse_field_element(&self.root)?,
+            siblings,
+            key: parse_field_element(&self.key)?,
+            value: parse_field_element(&self.value)?,
+            found,
+            old_leaf,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u(n: u64) -> U256 {
+        U256::from(n)
+    }
+
+    #[test]
+    fn test_root_layout_and_deletion() {
+        // Keys 1 = 0b01, 3 = 0b11 and 2 = 0b10: 2 splits off at bit 0,
+        // 1 and 3 at bit 1
+        let (l1, l2, l3) = (hash_leaf(&u(1), &u(10)), hash_leaf(&u(2), &u(20)), hash_leaf(&u(3), &u(30)));
+        let r1 = l1;
+        let r12 = hash_pair(&l2, &l1);
+        let r123 = hash_pair(&l2, &hash_pair(&l1, &l3));
+        let r13 = hash_pair(&U256::zero(), &hash_pair(&l1, &l3));
+
+        let mut tree = SparseMerkleTree::new();
+        tree.insert(u(1), u(10)).unwrap();
+        assert_eq!(tree.root(), r1);
+        tree.insert(u(2), u(20)).unwrap();
+        assert_eq!(tree.root(), r12);
+        tree.insert(u(3), u(30)).unwrap();
+        assert_eq!(tree.root(), r123);
+
+        let mut reordered = SparseMerkleTree::new();
+        for key in [3, 1, 2] {
+            reordered.insert(u(key), u(key * 10)).unwrap();
+        }
+        assert_eq!(reordered.root(), r123);
+        assert_eq!(reordered.nodes, tree.nodes);
+
+        assert_eq!(tree.delete(&u(2)), Ok(u(20)));
+        assert_eq!(tree.root(), r13);
+        assert_eq!(tree.delete(&u(3)), Ok(u(30)));
+        assert_eq!(tree.root(), r1);
+        assert_eq!(tree.delete(&u(1)), Ok(u(10)));
+        assert_eq!(tree.root(), U256::zero());
+        assert!(tree.nodes.is_empty());
+
+        assert_eq!(reordered.insert(u(2), u(0)), Err(MerkleError::KeyExists));
+        assert_eq!(reordered.delete(&u(4)), Err(MerkleError::KeyNotFound));
+        assert_eq!(reordered.update(u(2), u(21)), Ok(u(20)));
+        assert_eq!(reordered.get(&u(2)), Some(u(21)));
+        assert_eq!(reordered.update(u(2), u(20)), Ok(u(21)));
+        assert_eq!(reordered.root(), r123);
+        assert_eq!(reordered.insert(FR_MODULUS, u(1)), Err(MerkleError::NonCanonicalLeaf));
+    }
+
+    /// Roots and `find` siblings from `scripts/smt_reference.py`, a port of
+    /// circomlibjs `SMT.insert`/`SMT.find` (keys 7, 8, 32 are the ones
+    /// circomlibjs' own tests insert)
+    #[test]
+    fn test_circomlibjs_smt_vectors() {
+        let d = |s: &str| U256::from_dec_str(s).unwrap();
+        let big = (U256::one() << 200) + u(5);
+        let inserts = [
+            (u(7), u(77), "7623680454338960526645764969964785413027269598530557012829785131842723566171"),
+            (u(8), u(88), "13779077928197029089975273178582675427552960599638317786290606987964107072086"),
+            (u(32), u(3232), "16797818670491194348249868563697804441293516695295768428725464454437473025192"),
+            (big, u(1), "10505110345910222532551194515995482763932769317646029335315567822766382197288"),
+        ];
+        let mut tree = SparseMerkleTree::new();
+        for (key, value, root) in inserts {
+            tree.insert(key, value).unwrap();
+            assert_eq!(tree.root(), d(root));
+        }
+
+        let left = d("5543531926395957739709261252615339750548298335715647481507963431695453702258");
+        let proof = tree.proof(&u(32)).unwrap();
+        let siblings = [
+            left,
+            U256::zero(),
+            U256::zero(),
+            d("8199520123371559548495425428157097842501569495702004037304582533739096128775"),
+        ];
+        assert_eq!(proof.siblings, siblings);
+        assert!(proof.found && proof.value == u(3232));
+
+        // 9 ends at the leaf of `big`, 2 in an empty subtree
+        let proof = tree.proof(&u(9)).unwrap();
+        let siblings = [
+            d("3175708756784007584835495801424747224482504115070783937148492422993448606467"),
+            d("7623680454338960526645764969964785413027269598530557012829785131842723566171"),
+        ];
+        assert_eq!(proof.siblings, siblings);
+        assert_eq!(proof.old_leaf, Some((big, u(1))));
+        let proof = tree.proof(&u(2)).unwrap();
+        let siblings = [left, d("5332364101836556510539786187038289331420539727748818927090139851535737808468")];
+        assert_eq!(proof.siblings, siblings);
+        assert_eq!(proof.old_leaf, None);
+    }
+
+    #[test]
+    fn test_membership_and_non_membership_proofs() {
+        let mut tree = SparseMerkleTree::new();
+        for key in [1, 2, 3, 8, 24] {
+            tree.insert(u(key), u(key + 100)).unwrap();
+        }
+
+        let proof = tree.proof(&u(3)).unwrap();
+        assert!(proof.found && proof.value == u(103));
+        assert!(verify_smt_proof(&proof));
+
+        // 5 = 0b101 ends at leaf 1 = 0b001; 4 = 0b100 ends in the empty
+        // subtree next to 8 = 0b01000 and 24 = 0b11000
+        let proof = tree.proof(&u(5)).unwrap();
+        assert_eq!(proof.old_leaf, Some((u(1), u(101))));
+        assert!(!proof.found && verify_smt_proof(&proof));
+        let empty = tree.proof(&u(4)).unwrap();
+        assert!(empty.old_leaf.is_none() && verify_smt_proof(&empty));
+
+        let mut forged = proof.clone();
+        forged.found = true;
+        forged.old_leaf = None;
+        forged.value = u(101);
+        assert!(!verify_smt_proof(&forged));
+        let mut forged = tree.proof(&u(8)).unwrap();
+        forged.value = u(0);
+        assert!(!verify_smt_proof(&forged));
+
+        let json = proof.to_json(10).unwrap();
+        assert_eq!(json.siblings.len(), 10);
+        assert_eq!((json.fnc, json.is_old0, json.old_key.as_str()), (1, 0, "1"));
+        let value = near_sdk::serde_json::to_value(&json).unwrap();
+        assert_eq!(value["isOld0"], 0);
+        assert_eq!(json.to_proof().unwrap(), proof);
+        assert_eq!(empty.to_json(10).unwrap().to_proof().unwrap(), empty);
+        assert_eq!(
+            proof.to_json(proof.siblings.len()),
+            Err(MerkleError::TooManySiblings { levels: proof.siblings.len(), siblings: proof.siblings.len() })
+        );
+    }
+
+    #[test]
+    fn test_stored_tree_matches_in_memory_tree() {
+        let mut memory = SparseMerkleTree::new();
+        let mut stored = StoredSparseMerkleTree::stored(b"r");
+        for key in [7u64, 1 << 40, 19, 3] {
+            memory.insert(u(key), u(1)).unwrap();
+            stored.insert(u(key), u(1)).unwrap();
+        }
+        stored.delete(&u(19)).unwrap();
+        memory.delete(&u(19)).unwrap();
+
+        assert_eq!(stored.root(), memory.root());
+        assert_eq!(stored.get(&u(1 << 40)), Some(u(1)));
+        assert!(!stored.contains(&u(19)));
+        for (hash, node) in &memory.nodes {
+            assert_eq!(stored.nodes.get_node(hash), Some(*node));
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Storage-backed verification keys, loaded lazily
+//!
+//! A [`Verifier`](crate::Verifier) field is Borsh-deserialized with the rest
+//! of the contract state, so a circuit with hundreds of public inputs reads
+//! its whole IC vector on every call, including calls that never verify.
+//! [`StoredVerifier`] keeps the key in its own storage slots instead:
+//!
+//! - alpha, beta, gamma and delta in one [`Lazy`] slot, read on first use
+//! - IC in a [`Vector`], one slot per point
+//!
+//! Deserializing the contract state only reads the two prefixes and the IC
+//! length. A verification reads the four fixed points, IC[0] and IC[i + 1]
+//! for every *non-zero* input; the entries of zero inputs are never loaded.
+//!
+//! ```rust,ignore
+//! #[near(contract_state)]
+//! pub struct Contract {
+//!     verifier: StoredVerifier,
+//! }
+//!
+//! // in `new`:
+//! let verifier = StoredVerifier::from_json_checked(b"v", &vk)?;
+//! ```
+
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::store::{Lazy, Vector};
+use near_sdk::IntoStorageKey;
+
+use crate::backend::{Bn254Backend, DefaultBackend};
+use crate::error::{VerifyError, VkError};
+use crate::types::{G1Point, G2Point, Proof, ProofJson, U256, VerificationKey, VerificationKeyJson};
+use crate::verifier::{check_request, compute_vk_x, pairing_input, parse_inputs};
+
+/// The fixed-size part of a verification key, stored in one slot
+#[derive(Clone, Debug, BorshDeserialize, BorshSerialize)]
+pub struct VkPoints {
+    pub alpha: G1Point,
+    pub beta: G2Point,
+    pub gamma: G2Point,
+    pub delta: G2Point,
+}
+
+/// Groth16 verifier whose key lives in storage, read only as far as needed
+///
+/// The backend is not part of the serialized state.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StoredVerifier<B = DefaultBackend> {
+    points: Lazy<VkPoints>,
+    ic: Vector<G1Point>,
+    #[borsh(skip)]
+    backend: B,
+}
+
+impl StoredVerifier {
+    /// Write `vk` to storage under `prefix`
+    ///
+    /// The key is not validated; prefer [`StoredVerifier::try_new`] for keys
+    /// that come from users.
+    pub fn new<S: IntoStorageKey>(prefix: S, vk: VerificationKey) -> Self {
+        Self::with_backend(prefix, vk, DefaultBackend::default())
+    }
+
+    /// Write `vk` to storage after checking every point of the key
+    pub fn try_new<S: IntoStorageKey>(prefix: S, vk: VerificationKey) -> Result<Self, VkError> {
+        vk.validate()?;
+        Ok(Self::new(prefix, vk))
+    }
+
+    /// Parse and validate a JSON verification key, then write it to storage
+    pub fn from_json_checked<S: IntoStorageKey>(
+        prefix: S,
+        vk_json: &VerificationKeyJson,
+    ) -> Result<Self, VerifyError> {
+        Ok(Self::try_new(prefix, vk_json.to_vk()?)?)
+    }
+}
+
+impl<B: Bn254Backend> StoredVerifier<B> {
+    /// Write `vk` to storage under `prefix`, verifying on `backend`
+    pub fn with_backend<S: IntoStorageKey>(prefix: S, vk: VerificationKey, backend: B) -> Self {
+        let prefix = prefix.into_storage_key();
+        let VerificationKey { alpha, beta, gamma, delta, ic: points } = vk;
+        let mut ic = Vector::new([prefix.as_slice(), b"i"].concat());
+        ic.extend(points);
+        Self {
+            points: Lazy::new([prefix.as_slice(), b"p"].concat(), VkPoints { alpha, beta, gamma, delta }),
+            ic,
+            backend,
+        }
+    }
+
+    /// Replace the stored key, e.g. in `update_verification_key`
+    ///
+    /// Removes every old IC entry, so the cost grows with both key sizes.
+    pub fn replace(&mut self, vk: VerificationKey) -> Result<(), VkError> {
+        vk.validate()?;
+        let VerificationKey { alpha, beta, gamma, delta, ic } = vk;
+        self.points.set(VkPoints { alpha, beta, gamma, delta });
+        self.ic.clear();
+        self.ic.extend(ic);
+        Ok(())
+    }
+
+    /// Number of public inputs the key expects; reads no storage
+    pub fn num_inputs(&self) -> usize {
+        (self.ic.len() as usize).saturating_sub(1)
+    }
+
+    /// Load the whole key into memory
+    pub fn to_vk(&self) -> VerificationKey {
+        let points = self.points.get().clone();
+        VerificationKey {
+            alpha: points.alpha,
+            beta: points.beta,
+            gamma: points.gamma,
+            delta: points.delta,
+            ic: self.ic.iter().cloned().collect(),
+        }
+    }
+
+    /// Verify a Groth16 proof; see [`StoredVerifier::verify_checked`]
+    pub fn verify(&self, inputs: &[U256], proof: &Proof) -> bool {
+        self.verify_checked(inputs, proof).is_ok()
+    }
+
+    /// Verify a Groth16 proof, reporting the reason for any failure
+    ///
+    /// Rejects malformed requests before reading any part of the key.
+    pub fn verify_checked(&self, inputs: &[U256], proof: &Proof) -> Result<(), VerifyError> {
+        check_request(self.num_inputs(), inputs, proof)?;
+
+        let vk_x = compute_vk_x(&self.backend, self.ic.len() as usize, inputs, |i| {
+            self.ic[i as u32].clone()
+        })?;
+
+        let points = self.points.get();
+        let input = pairing_input(&points.alpha, &points.beta, &points.gamma, &points.delta, proof, &vk_x);
+        if self.backend.pairing_check(&input) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingFailed)
+        }
+    }
+
+    /// Verify a proof with inputs as decimal strings (snarkjs format)
+    pub fn verify_json(&self, proof_json: &ProofJson, inputs: &[String]) -> bool {
+        self.verify_json_checked(proof_json, inputs).is_ok()
+    }
+
+    /// Verify a proof with decimal-string inputs, reporting the reason for any failure
+    pub fn verify_json_checked(&self, proof_json: &ProofJson, inputs: &[String]) -> Result<(), VerifyError> {
+        let proof = proof_json.to_proof()?;
+        let inputs = parse_inputs(inputs)?;
+        self.verify_checked(&inputs, &proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groth16_tests::groth16_integration_tests::fixture;
+    use crate::backend::{BackendCall, RecordingBackend};
+    use near_sdk::env;
+
+    /// Flush `verifier` and load it back as a new call would, with empty caches
+    fn reload(mut verifier: StoredVerifier) -> StoredVerifier {
+        verifier.points.flush();
+        verifier.ic.flush();
+        let state = near_sdk::borsh::to_vec(&verifier).unwrap();
+        StoredVerifier::try_from_slice(&state).unwrap()
+    }
+
+    #[test]
+    fn test_stored_verifier_matches_verifier() {
+        let (verifier, proof, inputs) = fixture();
+        let stored = reload(StoredVerifier::try_new(b"v", verifier.vk.clone()).unwrap());
+
+        assert_eq!(stored.num_inputs(), 2);
+        assert_eq!(stored.verify_json_checked(&proof, &inputs), Ok(()));
+        assert_eq!(stored.to_vk().ic.len(), verifier.vk.ic.len());
+
+        let mut wrong = inputs.clone();
+        wrong[0] = "1".to_string();
+        assert_eq!(stored.verify_json_checked(&proof, &wrong), Err(VerifyError::PairingFailed));
+        assert_eq!(
+            stored.verify_json_checked(&proof, &inputs[..1]),
+            Err(VerifyError::InputCountMismatch { expected: 2, found: 1 })
+        );
+    }
+
+    #[test]
+    fn test_zero_inputs_not_read() {
+        let (verifier, proof, mut inputs) = fixture();
+        let stored = reload(StoredVerifier::new(b"v", verifier.vk.clone()));
+
+        // Drop IC[2] from storage: a zero second input must never read it
+        assert!(env::storage_remove(&[b"vi".as_slice(), &2u32.to_le_bytes()].concat()));
+        inputs[1] = "0".to_string();
+        assert_eq!(
+            stored.verify_json_checked(&proof, &inputs),
+            verifier.verify_json_checked(&proof, &inputs)
+        );
+
+        let recording = StoredVerifier {
+            points: stored.points,
+            ic: stored.ic,
+            backend: RecordingBackend::<DefaultBackend>::default(),
+        };
+        let _ = recording.verify_json_checked(&proof, &inputs);
+        // One (point, scalar) pair in the multiexp
+        assert!(matches!(
+            &recording.backend.calls()[0],
+            BackendCall::G1Multiexp(input) if input.len() == 96
+        ));
+    }
+
+    #[test]
+    fn test_replace_key() {
+        let (verifier, proof, inputs) = fixture();
+        let mut vk = verifier.vk.clone();
+        vk.ic.push(vk.ic[0].clone());
+        let mut stored = StoredVerifier::new(b"v", vk);
+        assert_eq!(stored.num_inputs(), 3);
+
+        let mut bad = verifier.vk.clone();
+        bad.ic.clear();
+        assert_eq!(stored.replace(bad), Err(VkError::EmptyIc));
+
+        stored.replace(verifier.vk.clone()).unwrap();
+        let stored = reload(stored);
+        assert_eq!(stored.num_inputs(), 2);
+        assert!(stored.verify_json(&proof, &inputs));
+    }
+}
@@ -1,49 +1,64 @@
-//! Fiat-Shamir Transcript for PLONK verification
-//!
-//! This module implements a Fiat-Shamir transcript compatible with snarkjs PLONK.
-//!
-//! ## Important Notes
+//! Keccak256 Fiat-Shamir transcript used by the snarkjs PLONK and fflonk verifiers
 //!
 //! **snarkjs uses raw Keccak256 hashing, NOT STROBE-based Merlin transcripts.**
+//! A challenge is `keccak256(state) mod r`, with G1 points appended as
+//! `x ‖ y` and scalars as 32 big-endian bytes. Squeezing a challenge clears
+//! the state; snarkjs then usually hashes the previous challenge before the
+//! next proof elements, so the challenges form a chain.
 //!
-//! The challenge derivation follows this order:
-//! 1. Hash wire commitments (A, B, C) → derive β, γ
-//! 2. Hash permutation commitment (Z) → derive α
-//! 3. Hash quotient commitments (T1, T2, T3) → derive ξ (zeta)
-//! 4. Hash polynomial evaluations → derive v
-//! 5. Hash opening proofs → derive u
+//! This module only provides the hashing. The order of what gets hashed is
+//! protocol specific and lives next to each verifier:
+//! [`PlonkChallenges::snarkjs`] and [`FflonkChallenges::snarkjs`] follow
+//! snarkjs `plonk_verify` and `fflonk_verify` exactly.
 //!
-//! All field elements are serialized in **big-endian** format.
+//! Hashing goes through a [`Bn254Backend`] (the NEAR host by default), so a
+//! [`RecordingBackend`](crate::backend::RecordingBackend) can capture every
+//! hashed payload.
 
-use near_sdk::env;
+use crate::backend::{Bn254Backend, DefaultBackend};
 use crate::types::{G1Point, U256};
 
 /// BN254 scalar field modulus (r)
 const FR_MODULUS: &str = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
 
-/// Fiat-Shamir transcript for snarkjs-compatible PLONK
+/// Keccak256 Fiat-Shamir transcript
 ///
-/// Uses Keccak256 hashing to derive challenges from proof elements.
+/// Accumulates bytes and squeezes challenges; see the module docs for the
+/// encoding.
 #[derive(Clone, Debug)]
-pub struct FiatShamirTranscript {
+pub struct FiatShamirTranscript<B = DefaultBackend> {
     /// Accumulated data to be hashed
     state: Vec<u8>,
+    backend: B,
 }
 
-impl Default for FiatShamirTranscript {
+impl<B: Bn254Backend + Default> Default for FiatShamirTranscript<B> {
     fn default() -> Self {
-        Self::new()
+        Self::with_backend(B::default())
     }
 }
 
 impl FiatShamirTranscript {
     /// Create a new empty transcript
     pub fn new() -> Self {
+        Self::with_backend(DefaultBackend::default())
+    }
+}
+
+impl<B: Bn254Backend> FiatShamirTranscript<B> {
+    /// Create a new empty transcript that hashes with `backend`
+    pub fn with_backend(backend: B) -> Self {
         Self {
             state: Vec::with_capacity(1024),
+            backend,
         }
     }
 
+    /// The backend used for hashing
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
     /// Clear the transcript state
     pub fn reset(&mut self) {
         self.state.clear();
@@ -87,7 +102,7 @@ impl FiatShamirTranscript {
     /// Returns the hash reduced modulo the scalar field order.
     /// Does NOT clear the state (allows chaining).
     pub fn challenge(&self) -> U256 {
-        let hash = env::keccak256(&self.state);
+        let hash = self.backend.keccak256(&self.state);
         hash_to_field(&hash)
     }
 
@@ -98,115 +113,10 @@ impl FiatShamirTranscript {
         challenge
     }
 
-    /// Generate a challenge from double hashing (for gamma in snarkjs)
-    ///
-    /// snarkjs derives gamma as: keccak256(keccak256(state))
-    pub fn challenge_double_hash(&self) -> U256 {
-        let hash1 = env::keccak256(&self.state);
-        let hash2 = env::keccak256(&hash1);
-        hash_to_field(&hash2)
-    }
-
     /// Get current state length (for debugging)
     pub fn state_len(&self) -> usize {
         self.state.len()
     }
-
-    // ==========================================================================
-    // PLONK-specific challenge derivation (snarkjs compatible)
-    // ==========================================================================
-
-    /// Round 1: Derive beta and gamma from wire commitments
-    ///
-    /// ```text
-    /// beta  = keccak256(A || B || C) mod r
-    /// gamma = keccak256(keccak256(A || B || C)) mod r
-    /// ```
-    pub fn round1_challenges(
-        &mut self,
-        a: &G1Point,
-        b: &G1Point,
-        c: &G1Point,
-    ) -> (U256, U256) {
-        self.reset();
-        self.append_g1(a);
-        self.append_g1(b);
-        self.append_g1(c);
-
-        let beta = self.challenge();
-        let gamma = self.challenge_double_hash();
-
-        (beta, gamma)
-    }
-
-    /// Round 2: Derive alpha from permutation commitment Z
-    ///
-    /// ```text
-    /// alpha = keccak256(Z) mod r
-    /// ```
-    pub fn round2_challenge(&mut self, z: &G1Point) -> U256 {
-        self.reset();
-        self.append_g1(z);
-        self.squeeze_challenge()
-    }
-
-    /// Round 3: Derive zeta from quotient commitments
-    ///
-    /// ```text
-    /// zeta = keccak256(T1 || T2 || T3) mod r
-    /// ```
-    pub fn round3_challenge(
-        &mut self,
-        t1: &G1Point,
-        t2: &G1Point,
-        t3: &G1Point,
-    ) -> U256 {
-        self.reset();
-        self.append_g1(t1);
-        self.append_g1(t2);
-        self.append_g1(t3);
-        self.squeeze_challenge()
-    }
-
-    /// Round 4: Derive v from polynomial evaluations
-    ///
-    /// ```text
-    /// v = keccak256(a_eval || b_eval || c_eval || s1_eval || s2_eval || z_shifted_eval) mod r
-    /// ```
-    pub fn round4_challenge(
-        &mut self,
-        a_eval: &U256,
-        b_eval: &U256,
-        c_eval: &U256,
-        s1_eval: &U256,
-        s2_eval: &U256,
-        z_shifted_eval: &U256,
-    ) -> U256 {
-        self.reset();
-        self.append_scalar(a_eval);
-        self.append_scalar(b_eval);
-        self.append_scalar(c_eval);
-        self.append_scalar(s1_eval);
-        self.append_scalar(s2_eval);
-        self.append_scalar(z_shifted_eval);
-        self.squeeze_challenge()
-    }
-
-    /// Round 5: Derive u from opening proofs
-    ///
-    /// ```text
-    /// u = keccak256(W_xi || W_xi_omega) mod r
-    /// ```
-    pub fn round5_challenge(
-        &mut self,
-        w_xi: &G1Point,
-        w_xi_omega: &G1Point,
-    ) -> U256 {
-        self.reset();
-        self.append_g1(w_xi);
-        self.append_g1(w_xi_omega);
-        self.squeeze_challenge()
-    }
 }
 
 /// Convert a 32-byte hash to a field element
@@ -218,9 +128,9 @@ pub fn hash_to_field(hash: &[u8]) -> U256 {
     value % fr_modulus
 }
 
-/// Full PLONK challenge derivation
+/// PLONK challenges
 ///
-/// This struct encapsulates all challenges needed for PLONK verification.
+/// Derived by [`PlonkChallenges::snarkjs`].
 #[derive(Clone, Debug)]
 pub struct PlonkChallenges {
     pub beta: U256,
@@ -231,60 +141,18 @@ pub struct PlonkChallenges {
     pub u: U256,
 }
 
-impl PlonkChallenges {
-    /// Derive all PLONK challenges from proof components
-    ///
-    /// # Arguments
-    /// * `a, b, c` - Wire commitments (Round 1)
-    /// * `z` - Permutation commitment (Round 2)
-    /// * `t1, t2, t3` - Quotient commitments (Round 3)
-    /// * `evaluations` - Polynomial evaluations [a, b, c, s1, s2, z_shifted] (Round 4)
-    /// * `w_xi, w_xi_omega` - Opening proofs (Round 5)
-    pub fn derive(
-        a: &G1Point,
-        b: &G1Point,
-        c: &G1Point,
-        z: &G1Point,
-        t1: &G1Point,
-        t2: &G1Point,
-        t3: &G1Point,
-        evaluations: &[U256; 6],
-        w_xi: &G1Point,
-        w_xi_omega: &G1Point,
-    ) -> Self {
-        let mut transcript = FiatShamirTranscript::new();
-
-        // Round 1: beta, gamma
-        let (beta, gamma) = transcript.round1_challenges(a, b, c);
-
-        // Round 2: alpha
-        let alpha = transcript.round2_challenge(z);
-
-        // Round 3: zeta
-        let zeta = transcript.round3_challenge(t1, t2, t3);
-
-        // Round 4: v
-        let v = transcript.round4_challenge(
-            &evaluations[0],
-            &evaluations[1],
-            &evaluations[2],
-            &evaluations[3],
-            &evaluations[4],
-            &evaluations[5],
-        );
-
-        // Round 5: u
-        let u = transcript.round5_challenge(w_xi, w_xi_omega);
-
-        Self {
-            beta,
-            gamma,
-            alpha,
-            zeta,
-            v,
-            u,
-        }
-    }
+/// fflonk challenge derivation
+///
+/// fflonk squeezes fewer challenges than PLONK: ξ is not hashed directly but
+/// derived from `xi_seed` (ξ = xi_seed²⁴) so that the opening roots are known
+/// powers of the seed.
+#[derive(Clone, Debug)]
+pub struct FflonkChallenges {
+    pub beta: U256,
+    pub gamma: U256,
+    pub xi_seed: U256,
+    pub alpha: U256,
+    pub y: U256,
 }
 
 // ==========================================================================
@@ -293,15 +161,23 @@ impl PlonkChallenges {
 
 /// Simple transcript that accumulates everything and hashes at the end
 ///
-/// Use this for debugging when you need to match the exact snarkjs behavior.
+/// Unlike [`FiatShamirTranscript`] it never resets; use it to hash a
+/// hand-built payload when debugging a transcript mismatch.
 #[derive(Clone, Debug, Default)]
-pub struct SimpleTranscript {
+pub struct SimpleTranscript<B = DefaultBackend> {
     data: Vec<u8>,
+    backend: B,
 }
 
 impl SimpleTranscript {
     pub fn new() -> Self {
-        Self { data: Vec::new() }
+        Self::with_backend(DefaultBackend::default())
+    }
+}
+
+impl<B: Bn254Backend> SimpleTranscript<B> {
+    pub fn with_backend(backend: B) -> Self {
+        Self { data: Vec::new(), backend }
     }
 
     pub fn append(&mut self, bytes: &[u8]) {
@@ -317,7 +193,7 @@ impl SimpleTranscript {
     }
 
     pub fn hash(&self) -> [u8; 32] {
-        env::keccak256(&self.data).try_into().unwrap()
+        self.backend.keccak256(&self.data)
     }
 
     pub fn challenge(&self) -> U256 {
@@ -355,6 +231,36 @@ mod tests {
         assert_eq!(transcript.state_len(), 96);
     }
 
+    #[test]
+    fn test_transcript_hashes_through_backend() {
+        use crate::backend::{BackendCall, NearBackend, RecordingBackend};
+
+        let mut transcript = FiatShamirTranscript::with_backend(RecordingBackend::new(NearBackend));
+        transcript.append_scalar(&U256::from(7u64));
+        let state = U256::from(7u64).to_be_bytes().to_vec();
+
+        let beta = transcript.challenge();
+        assert_eq!(transcript.squeeze_challenge(), beta);
+        assert_eq!(transcript.state_len(), 0);
+
+        // snarkjs chains rounds by hashing the previous challenge: γ = H(β)
+        transcript.append_scalar(&beta);
+        let gamma = transcript.squeeze_challenge();
+        assert_ne!(beta, gamma);
+        assert_eq!(
+            transcript.backend().take_calls(),
+            vec![
+                BackendCall::Keccak256(state.clone()),
+                BackendCall::Keccak256(state.clone()),
+                BackendCall::Keccak256(beta.to_be_bytes().to_vec()),
+            ]
+        );
+
+        let mut simple = SimpleTranscript::with_backend(RecordingBackend::new(NearBackend));
+        simple.append_scalar(&U256::from(7u64));
+        assert_eq!(simple.challenge(), beta);
+    }
+
     #[test]
     fn test_fr_modulus() {
         let modulus = U256::from_dec_str(FR_MODULUS).unwrap();
@@ -6,6 +6,9 @@ use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 pub use uint::construct_uint;
 
+use crate::curve::{g1_is_on_curve, g2_is_in_subgroup, g2_is_on_curve};
+use crate::error::{ParseError, PointError, VerifyError, VkError};
+
 // Define U256 type for field elements
 construct_uint! {
     /// 256-bit unsigned integer for field elements
@@ -26,6 +29,65 @@ impl U256 {
     pub fn from_be_bytes(bytes: &[u8]) -> Self {
         U256::from_big_endian(bytes)
     }
+
+    /// Convert to little-endian 32-byte array (NEAR alt_bn128 encoding)
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.to_little_endian(&mut bytes);
+        bytes
+    }
+
+    /// Create from little-endian bytes
+    pub fn from_le_bytes(bytes: &[u8]) -> Self {
+        U256::from_little_endian(bytes)
+    }
+}
+
+/// BN254 scalar field modulus r (order of G1/G2)
+///
+/// Public inputs, nullifiers and commitments are elements of this field and
+/// must be strictly below it to have a unique encoding.
+pub const FR_MODULUS: U256 = U256([
+    0x43e1f593f0000001,
+    0x2833e84879b97091,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+/// BN254 base field modulus q (coordinate field of G1/G2)
+pub const FQ_MODULUS: U256 = U256([
+    0x3c208c16d87cfd47,
+    0x97816a916871ca8d,
+    0xb85045b68181585d,
+    0x30644e72e131a029,
+]);
+
+/// Parse a canonical BN254 scalar field element
+///
+/// Accepts a decimal string (snarkjs format) or a `0x`-prefixed hex string.
+/// Values `>= r` are rejected rather than reduced, so every field element has
+/// exactly one accepted encoding. Use this for anything stored for uniqueness
+/// checks (nullifiers, commitments): otherwise `x` and `x + r` would be two
+/// distinct keys for the same value.
+pub fn parse_field_element(s: &str) -> Result<U256, ParseError> {
+    let value = parse_u256(s)?;
+    if value >= FR_MODULUS {
+        return Err(ParseError::NonCanonicalFieldElement);
+    }
+    Ok(value)
+}
+
+/// Parse a decimal or `0x`-prefixed hex string into any 256-bit value
+pub(crate) fn parse_u256(s: &str) -> Result<U256, ParseError> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) if !hex.is_empty() && hex.bytes().all(|c| c.is_ascii_hexdigit()) => {
+            U256::from_str_radix(hex, 16).map_err(|_| ParseError::InvalidFieldElement)
+        }
+        None if !s.is_empty() && s.bytes().all(|c| c.is_ascii_digit()) => {
+            U256::from_dec_str(s).map_err(|_| ParseError::InvalidFieldElement)
+        }
+        _ => Err(ParseError::InvalidFieldElement),
+    }
 }
 
 /// G1 point on BN254 curve (affine coordinates)
@@ -51,7 +113,7 @@ impl G1Point {
         self.x.is_zero() && self.y.is_zero()
     }
 
-    /// Serialize to 64 bytes (big-endian format for NEAR precompiles)
+    /// Serialize to 64 bytes, big-endian (EVM / snarkjs transcript layout)
     pub fn to_bytes(&self) -> [u8; 64] {
         let mut result = [0u8; 64];
         result[..32].copy_from_slice(&self.x.to_be_bytes());
@@ -59,7 +121,7 @@ impl G1Point {
         result
     }
 
-    /// Deserialize from 64 bytes
+    /// Deserialize from 64 big-endian bytes
     pub fn from_bytes(bytes: &[u8; 64]) -> Self {
         Self {
             x: U256::from_be_bytes(&bytes[..32]),
@@ -67,17 +129,65 @@ impl G1Point {
         }
     }
 
+    /// Serialize to 64 bytes in NEAR alt_bn128 layout: x || y, each little-endian
+    pub fn to_precompile_bytes(&self) -> [u8; 64] {
+        let mut result = [0u8; 64];
+        result[..32].copy_from_slice(&self.x.to_le_bytes());
+        result[32..].copy_from_slice(&self.y.to_le_bytes());
+        result
+    }
+
+    /// Deserialize from NEAR alt_bn128 layout (64 bytes, little-endian coordinates)
+    pub fn from_precompile_bytes(bytes: &[u8; 64]) -> Self {
+        Self {
+            x: U256::from_le_bytes(&bytes[..32]),
+            y: U256::from_le_bytes(&bytes[32..]),
+        }
+    }
+
+    /// Check that the point satisfies `y² = x³ + 3` with coordinates below q
+    ///
+    /// G1 has cofactor 1, so this also implies subgroup membership.
+    /// The identity `(0, 0)` is considered on the curve.
+    pub fn is_on_curve(&self) -> bool {
+        g1_is_on_curve(self)
+    }
+
+    /// Reject points the pairing host function would abort on, and the identity
+    pub fn validate(&self) -> Result<(), PointError> {
+        if self.is_zero() {
+            return Err(PointError::Identity);
+        }
+        if !self.is_on_curve() {
+            return Err(PointError::NotOnCurve);
+        }
+        Ok(())
+    }
+
     /// Create from snarkjs JSON array format ["x", "y", "1"]
-    /// The third element is always "1" for affine coordinates
-    pub fn from_json_array(arr: &[String]) -> Result<Self, &'static str> {
+    /// The third element is "1" for affine points and "0" for the point at
+    /// infinity (snarkjs writes `["0", "1", "0"]`), which maps to `(0, 0)`.
+    pub fn from_json_array(arr: &[String]) -> Result<Self, ParseError> {
         if arr.len() < 2 {
-            return Err("G1 point requires at least 2 coordinates");
+            return Err(ParseError::G1MissingCoordinates);
+        }
+        if arr.get(2).map(String::as_str) == Some("0") {
+            return Ok(Self::zero());
         }
         Ok(Self {
-            x: U256::from_dec_str(&arr[0]).map_err(|_| "Invalid decimal string for x")?,
-            y: U256::from_dec_str(&arr[1]).map_err(|_| "Invalid decimal string for y")?,
+            x: U256::from_dec_str(&arr[0]).map_err(|_| ParseError::InvalidCoordinate("x"))?,
+            y: U256::from_dec_str(&arr[1]).map_err(|_| ParseError::InvalidCoordinate("y"))?,
         })
     }
+
+    /// Convert to snarkjs JSON array format ["x", "y", "1"]
+    /// The identity is written as `["0", "1", "0"]`, as snarkjs does.
+    pub fn to_json_array(&self) -> Vec<String> {
+        if self.is_zero() {
+            return vec!["0".to_string(), "1".to_string(), "0".to_string()];
+        }
+        vec![self.x.to_string(), self.y.to_string(), "1".to_string()]
+    }
 }
 
 /// G2 point on BN254 curve (affine coordinates over Fq2)
@@ -107,8 +217,8 @@ impl G2Point {
         self.y[0].is_zero() && self.y[1].is_zero()
     }
 
-    /// Serialize to 128 bytes (big-endian format for NEAR precompiles)
-    /// Note: NEAR uses (x1, x0, y1, y0) ordering for G2 points
+    /// Serialize to 128 bytes, big-endian (EVM / EIP-197 layout)
+    /// Note: EIP-197 uses (x1, x0, y1, y0) ordering for G2 points
     pub fn to_bytes(&self) -> [u8; 128] {
         let mut result = [0u8; 128];
         // x coordinate (Fq2): high part first, then low part
@@ -120,7 +230,7 @@ impl G2Point {
         result
     }
 
-    /// Deserialize from 128 bytes
+    /// Deserialize from 128 big-endian bytes (EIP-197 layout)
     pub fn from_bytes(bytes: &[u8; 128]) -> Self {
         Self {
             x: [
@@ -134,25 +244,92 @@ impl G2Point {
         }
     }
 
+    /// Serialize to 128 bytes in NEAR alt_bn128 layout
+    /// Note: NEAR uses (x0, x1, y0, y1) ordering, each coordinate little-endian
+    pub fn to_precompile_bytes(&self) -> [u8; 128] {
+        let mut result = [0u8; 128];
+        result[0..32].copy_from_slice(&self.x[0].to_le_bytes());
+        result[32..64].copy_from_slice(&self.x[1].to_le_bytes());
+        result[64..96].copy_from_slice(&self.y[0].to_le_bytes());
+        result[96..128].copy_from_slice(&self.y[1].to_le_bytes());
+        result
+    }
+
+    /// Deserialize from NEAR alt_bn128 layout (128 bytes)
+    pub fn from_precompile_bytes(bytes: &[u8; 128]) -> Self {
+        Self {
+            x: [
+                U256::from_le_bytes(&bytes[0..32]),
+                U256::from_le_bytes(&bytes[32..64]),
+            ],
+            y: [
+                U256::from_le_bytes(&bytes[64..96]),
+                U256::from_le_bytes(&bytes[96..128]),
+            ],
+        }
+    }
+
+    /// Check that the point satisfies the twist equation `y² = x³ + 3/(9+u)`
+    /// with every coordinate below q. The identity `(0, 0)` is on the curve.
+    pub fn is_on_curve(&self) -> bool {
+        g2_is_on_curve(self)
+    }
+
+    /// Check that the point is on the curve and in the order-r subgroup
+    ///
+    /// The twist has a large cofactor, so `is_on_curve` alone is not enough:
+    /// the pairing host function aborts on points outside the subgroup.
+    /// This costs a 63-bit scalar multiplication and a few Frobenius maps
+    /// (about 11 TGas in a contract).
+    pub fn is_in_subgroup(&self) -> bool {
+        g2_is_in_subgroup(self)
+    }
+
+    /// Reject points the pairing host function would abort on, and the identity
+    pub fn validate(&self) -> Result<(), PointError> {
+        if self.is_zero() {
+            return Err(PointError::Identity);
+        }
+        if !self.is_on_curve() {
+            return Err(PointError::NotOnCurve);
+        }
+        if !self.is_in_subgroup() {
+            return Err(PointError::NotInSubgroup);
+        }
+        Ok(())
+    }
+
     /// Create from snarkjs JSON array format [["x0", "x1"], ["y0", "y1"], ["1", "0"]]
-    pub fn from_json_array(arr: &[Vec<String>]) -> Result<Self, &'static str> {
-        if arr.len() < 2 {
-            return Err("G2 point requires at least 2 coordinate pairs");
+    /// A third pair of `["0", "0"]` denotes the point at infinity, mapped to `(0, 0)`.
+    pub fn from_json_array(arr: &[Vec<String>]) -> Result<Self, ParseError> {
+        if arr.len() < 2 || arr[0].len() < 2 || arr[1].len() < 2 {
+            return Err(ParseError::G2MissingCoordinates);
         }
-        if arr[0].len() < 2 || arr[1].len() < 2 {
-            return Err("Each G2 coordinate requires 2 elements");
+        if arr.get(2).is_some_and(|z| z.iter().all(|c| c == "0")) {
+            return Ok(Self::zero());
         }
         Ok(Self {
             x: [
-                U256::from_dec_str(&arr[0][0]).map_err(|_| "Invalid decimal string for x0")?,
-                U256::from_dec_str(&arr[0][1]).map_err(|_| "Invalid decimal string for x1")?,
+                U256::from_dec_str(&arr[0][0]).map_err(|_| ParseError::InvalidCoordinate("x0"))?,
+                U256::from_dec_str(&arr[0][1]).map_err(|_| ParseError::InvalidCoordinate("x1"))?,
             ],
             y: [
-                U256::from_dec_str(&arr[1][0]).map_err(|_| "Invalid decimal string for y0")?,
-                U256::from_dec_str(&arr[1][1]).map_err(|_| "Invalid decimal string for y1")?,
+                U256::from_dec_str(&arr[1][0]).map_err(|_| ParseError::InvalidCoordinate("y0"))?,
+                U256::from_dec_str(&arr[1][1]).map_err(|_| ParseError::InvalidCoordinate("y1"))?,
             ],
         })
     }
+
+    /// Convert to snarkjs JSON array format [["x0", "x1"], ["y0", "y1"], ["1", "0"]]
+    /// The identity is written as `[["0", "0"], ["1", "0"], ["0", "0"]]`.
+    pub fn to_json_array(&self) -> Vec<Vec<String>> {
+        let pair = |c: &[U256; 2]| vec![c[0].to_string(), c[1].to_string()];
+        if self.is_zero() {
+            let zero = vec!["0".to_string(), "0".to_string()];
+            return vec![zero.clone(), vec!["1".to_string(), "0".to_string()], zero];
+        }
+        vec![pair(&self.x), pair(&self.y), vec!["1".to_string(), "0".to_string()]]
+    }
 }
 
 /// Groth16 proof structure
@@ -174,7 +351,7 @@ impl Proof {
         pi_a: &[String],
         pi_b: &[Vec<String>],
         pi_c: &[String],
-    ) -> Result<Self, &'static str> {
+    ) -> Result<Self, ParseError> {
         Ok(Self {
             a: G1Point::from_json_array(pi_a)?,
             b: G2Point::from_json_array(pi_b)?,
@@ -182,15 +359,59 @@ impl Proof {
         })
     }
 
-    /// Serialize proof for pairing check
+    /// Check every proof point before it reaches the pairing host function
+    ///
+    /// A and C must be on G1, B must be on G2 and in the subgroup, and none
+    /// of them may be the identity.
+    pub fn validate(&self) -> Result<(), VerifyError> {
+        let points = [
+            ("A", self.a.validate()),
+            ("B", self.b.validate()),
+            ("C", self.c.validate()),
+        ];
+        for (element, result) in points {
+            result.map_err(|reason| VerifyError::InvalidProofPoint { element, reason })?;
+        }
+        Ok(())
+    }
+
+    /// Serialize to 256 bytes in NEAR alt_bn128 layout: A ‖ B ‖ C
+    pub fn to_precompile_bytes(&self) -> [u8; 256] {
+        let mut result = [0u8; 256];
+        result[0..64].copy_from_slice(&self.a.to_precompile_bytes());
+        result[64..192].copy_from_slice(&self.b.to_precompile_bytes());
+        result[192..256].copy_from_slice(&self.c.to_precompile_bytes());
+        result
+    }
+
+    /// Deserialize from NEAR alt_bn128 layout (256 bytes)
+    pub fn from_precompile_bytes(bytes: &[u8; 256]) -> Self {
+        Self {
+            a: G1Point::from_precompile_bytes(bytes[0..64].try_into().expect("64 bytes")),
+            b: G2Point::from_precompile_bytes(bytes[64..192].try_into().expect("128 bytes")),
+            c: G1Point::from_precompile_bytes(bytes[192..256].try_into().expect("64 bytes")),
+        }
+    }
+
+    /// [`Proof::from_precompile_bytes`] for a slice of unchecked length,
+    /// e.g. a `Base64VecU8` contract argument
+    pub fn from_precompile_slice(bytes: &[u8]) -> Result<Self, ParseError> {
+        match bytes.len() {
+            256 => Ok(Self::from_precompile_bytes(bytes.try_into().expect("256 bytes"))),
+            len if len < 256 => Err(ParseError::UnexpectedEnd),
+            len => Err(ParseError::TrailingBytes { count: len - 256 }),
+        }
+    }
+
+    /// Serialize proof for pairing check (NEAR alt_bn128 layout)
     /// Returns (A_neg, B, C) where A is negated for the pairing equation
     pub fn to_pairing_bytes(&self, negate_a: bool) -> ([u8; 64], [u8; 128], [u8; 64]) {
         let a_bytes = if negate_a {
-            negate_g1(&self.a).to_bytes()
+            negate_g1(&self.a).to_precompile_bytes()
         } else {
-            self.a.to_bytes()
+            self.a.to_precompile_bytes()
         };
-        (a_bytes, self.b.to_bytes(), self.c.to_bytes())
+        (a_bytes, self.b.to_precompile_bytes(), self.c.to_precompile_bytes())
     }
 }
 
@@ -201,13 +422,8 @@ pub fn negate_g1(p: &G1Point) -> G1Point {
         return p.clone();
     }
     
-    // BN254 base field modulus
-    let field_modulus = U256::from_dec_str(
-        "21888242871839275222246405745257275088696311157297823662689037894645226208583"
-    ).unwrap();
-    
     // -y = p - y (mod p)
-    let neg_y = field_modulus - p.y;
+    let neg_y = FQ_MODULUS - p.y;
     
     G1Point {
         x: p.x,
@@ -241,12 +457,15 @@ impl VerificationKey {
         vk_gamma_2: &[Vec<String>],
         vk_delta_2: &[Vec<String>],
         ic: &[Vec<String>],
-    ) -> Result<Self, &'static str> {
+    ) -> Result<Self, ParseError> {
         let ic_points: Result<Vec<G1Point>, _> = ic
             .iter()
-            .map(|p| G1Point::from_json_array(p))
+            .enumerate()
+            .map(|(index, p)| {
+                G1Point::from_json_array(p).map_err(|_| ParseError::InvalidIcPoint { index })
+            })
             .collect();
-        
+
         Ok(Self {
             alpha: G1Point::from_json_array(vk_alpha_1)?,
             beta: G2Point::from_json_array(vk_beta_2)?,
@@ -260,6 +479,29 @@ impl VerificationKey {
     pub fn num_inputs(&self) -> usize {
         self.ic.len().saturating_sub(1)
     }
+
+    /// Check every point of the key
+    ///
+    /// alpha must be a non-identity G1 point; beta, gamma and delta must be
+    /// non-identity points in the G2 subgroup; every IC point must be on G1
+    /// (IC points may be the identity). Run this once when a key is stored:
+    /// an invalid key makes every later verification abort in the host.
+    pub fn validate(&self) -> Result<(), VkError> {
+        if self.ic.is_empty() {
+            return Err(VkError::EmptyIc);
+        }
+        let invalid = |element| move |reason| VkError::InvalidPoint { element, reason };
+        self.alpha.validate().map_err(invalid("alpha"))?;
+        self.beta.validate().map_err(invalid("beta"))?;
+        self.gamma.validate().map_err(invalid("gamma"))?;
+        self.delta.validate().map_err(invalid("delta"))?;
+        for (index, point) in self.ic.iter().enumerate() {
+            if !point.is_on_curve() {
+                return Err(VkError::InvalidIcPoint { index, reason: PointError::NotOnCurve });
+            }
+        }
+        Ok(())
+    }
 }
 
 /// JSON format for snarkjs proof files
@@ -275,9 +517,20 @@ pub struct ProofJson {
 
 impl ProofJson {
     /// Convert to Proof struct
-    pub fn to_proof(&self) -> Result<Proof, &'static str> {
+    pub fn to_proof(&self) -> Result<Proof, ParseError> {
         Proof::from_json(&self.pi_a, &self.pi_b, &self.pi_c)
     }
+
+    /// Convert a Proof struct to snarkjs JSON format
+    pub fn from_proof(proof: &Proof) -> Self {
+        Self {
+            pi_a: proof.a.to_json_array(),
+            pi_b: proof.b.to_json_array(),
+            pi_c: proof.c.to_json_array(),
+            protocol: Some("groth16".to_string()),
+            curve: Some("bn128".to_string()),
+        }
+    }
 }
 
 /// JSON format for snarkjs verification_key.json
@@ -298,7 +551,7 @@ pub struct VerificationKeyJson {
 
 impl VerificationKeyJson {
     /// Convert to VerificationKey struct
-    pub fn to_vk(&self) -> Result<VerificationKey, &'static str> {
+    pub fn to_vk(&self) -> Result<VerificationKey, ParseError> {
         VerificationKey::from_json(
             &self.vk_alpha_1,
             &self.vk_beta_2,
@@ -307,6 +560,20 @@ impl VerificationKeyJson {
             &self.ic,
         )
     }
+
+    /// Convert a VerificationKey struct to snarkjs JSON format
+    pub fn from_vk(vk: &VerificationKey) -> Self {
+        Self {
+            protocol: Some("groth16".to_string()),
+            curve: Some("bn128".to_string()),
+            n_public: u32::try_from(vk.num_inputs()).ok(),
+            vk_alpha_1: vk.alpha.to_json_array(),
+            vk_beta_2: vk.beta.to_json_array(),
+            vk_gamma_2: vk.gamma.to_json_array(),
+            vk_delta_2: vk.delta.to_json_array(),
+            ic: vk.ic.iter().map(G1Point::to_json_array).collect(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -338,4 +605,109 @@ mod tests {
         assert_eq!(p.y[0], p2.y[0]);
         assert_eq!(p.y[1], p2.y[1]);
     }
+
+    #[test]
+    fn test_precompile_layout_is_little_endian() {
+        let p = G2Point {
+            x: [U256::from(1u64), U256::from(2u64)],
+            y: [U256::from(3u64), U256::from(4u64)],
+        };
+        let bytes = p.to_precompile_bytes();
+        assert_eq!((bytes[0], bytes[32], bytes[64], bytes[96]), (1, 2, 3, 4));
+        let p2 = G2Point::from_precompile_bytes(&bytes);
+        assert_eq!(p.x, p2.x);
+        assert_eq!(p.y, p2.y);
+
+        let g = G1Point { x: U256::from(0x0102u64), y: U256::from(7u64) };
+        let bytes = g.to_precompile_bytes();
+        assert_eq!(&bytes[..2], &[0x02, 0x01]);
+        assert_eq!(G1Point::from_precompile_bytes(&bytes).x, g.x);
+    }
+
+    #[test]
+    fn test_json_array_roundtrip() {
+        let g1 = G1Point { x: U256::from(1u64), y: U256::from(2u64) };
+        assert_eq!(g1.to_json_array(), vec!["1", "2", "1"]);
+        assert!(G1Point::from_json_array(&G1Point::zero().to_json_array()).unwrap().is_zero());
+
+        let g2 = G2Point {
+            x: [U256::from(1u64), U256::from(2u64)],
+            y: [U256::from(3u64), U256::from(4u64)],
+        };
+        let back = G2Point::from_json_array(&g2.to_json_array()).unwrap();
+        assert_eq!((back.x, back.y), (g2.x, g2.y));
+        assert!(G2Point::from_json_array(&G2Point::zero().to_json_array()).unwrap().is_zero());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            G1Point::from_json_array(&["1".to_string()]).unwrap_err(),
+            ParseError::G1MissingCoordinates
+        );
+        assert_eq!(
+            G1Point::from_json_array(&["1".to_string(), "y".to_string()]).unwrap_err(),
+            ParseError::InvalidCoordinate("y")
+        );
+        let pair = vec!["1".to_string(), "2".to_string()];
+        assert_eq!(
+            G2Point::from_json_array(&[pair.clone(), vec!["3".to_string()]]).unwrap_err(),
+            ParseError::G2MissingCoordinates
+        );
+        assert_eq!(
+            G2Point::from_json_array(&[vec!["1".to_string(), "-".to_string()], pair.clone()]).unwrap_err(),
+            ParseError::InvalidCoordinate("x1")
+        );
+
+        let g1 = vec!["1".to_string(), "2".to_string()];
+        let err = VerificationKey::from_json(
+            &g1,
+            &[pair.clone(), pair.clone()],
+            &[pair.clone(), pair.clone()],
+            &[pair.clone(), pair],
+            &[g1.clone(), vec!["bad".to_string(), "2".to_string()]],
+        )
+        .unwrap_err();
+        assert_eq!(err, ParseError::InvalidIcPoint { index: 1 });
+    }
+
+    #[test]
+    fn test_modulus_constants() {
+        assert_eq!(
+            FR_MODULUS,
+            U256::from_dec_str(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            FQ_MODULUS,
+            U256::from_dec_str(
+                "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_field_element() {
+        assert_eq!(parse_field_element("255"), Ok(U256::from(255u64)));
+        assert_eq!(parse_field_element("0xff"), Ok(U256::from(255u64)));
+        assert_eq!(parse_field_element("0XFF"), Ok(U256::from(255u64)));
+
+        let r_minus_1 = FR_MODULUS - U256::one();
+        assert_eq!(parse_field_element(&r_minus_1.to_string()), Ok(r_minus_1));
+
+        // r and anything above it has a smaller equivalent, so it is rejected
+        let r = FR_MODULUS.to_string();
+        assert_eq!(parse_field_element(&r), Err(ParseError::NonCanonicalFieldElement));
+        assert_eq!(
+            parse_field_element(&format!("{:#x}", FR_MODULUS)),
+            Err(ParseError::NonCanonicalFieldElement)
+        );
+
+        for bad in ["", "0x", "-1", "+1", " 1", "12ab", "0xzz", "0x-1"] {
+            assert_eq!(parse_field_element(bad), Err(ParseError::InvalidFieldElement), "{:?}", bad);
+        }
+    }
 }
@@ -16,34 +16,85 @@
 //! ```text
 //! e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1
 //! ```
+//!
+//! The curve operations go through a [`Bn254Backend`]: the NEAR host
+//! functions by default, or the pure-Rust backend with the `standalone`
+//! feature. See [`crate::backend`].
 
 use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
-use near_sdk::env;
 
-use crate::types::{G1Point, G2Point, Proof, U256, VerificationKey, negate_g1};
+use crate::error::{ParseError, VerifyError, VkError};
+use crate::backend::{Bn254Backend, DefaultBackend};
+use crate::compact::CompactProof;
+use crate::types::{
+    parse_field_element, G1Point, G2Point, Proof, U256, VerificationKey, FR_MODULUS, negate_g1,
+};
+
+/// Size of the 4-pair Groth16 pairing input (4 × (64 + 128) bytes)
+pub(crate) const PAIRING_INPUT_LEN: usize = 4 * 192;
 
 /// Groth16 Verifier
 ///
-/// Stores the verification key and provides verification methods.
+/// Stores the verification key and provides verification methods. The
+/// backend is not part of the serialized state.
 #[derive(Clone, Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
-pub struct Verifier {
+pub struct Verifier<B = DefaultBackend> {
     /// The verification key
     pub vk: VerificationKey,
+    #[borsh(skip)]
+    #[serde(skip)]
+    backend: B,
 }
 
 impl Verifier {
     /// Create a new verifier with the given verification key
+    ///
+    /// The key is not validated; prefer [`Verifier::try_new`] for keys that
+    /// come from users.
     pub fn new(vk: VerificationKey) -> Self {
-        Self { vk }
+        Self::with_backend(vk, DefaultBackend::default())
+    }
+
+    /// Create a verifier after checking every point of the key
+    pub fn try_new(vk: VerificationKey) -> Result<Self, VkError> {
+        vk.validate()?;
+        Ok(Self::new(vk))
     }
 
     /// Create verifier from JSON-formatted verification key
-    pub fn from_json(vk_json: &crate::types::VerificationKeyJson) -> Result<Self, &'static str> {
+    ///
+    /// Only parses the key; see [`Verifier::from_json_checked`].
+    pub fn from_json(vk_json: &crate::types::VerificationKeyJson) -> Result<Self, ParseError> {
         Ok(Self::new(vk_json.to_vk()?))
     }
 
+    /// Parse and validate a JSON verification key
+    ///
+    /// Use this in `new` / `update_verification_key`: a key with off-curve or
+    /// out-of-subgroup points would make every later verification abort.
+    pub fn from_json_checked(
+        vk_json: &crate::types::VerificationKeyJson,
+    ) -> Result<Self, VerifyError> {
+        Ok(Self::try_new(vk_json.to_vk()?)?)
+    }
+}
+
+impl<B: Bn254Backend> Verifier<B> {
+    /// Create a verifier that runs its curve operations on `backend`
+    ///
+    /// The key is not validated; call [`VerificationKey::validate`] first for
+    /// keys that come from users.
+    pub fn with_backend(vk: VerificationKey, backend: B) -> Self {
+        Self { vk, backend }
+    }
+
+    /// The backend this verifier calls
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
     /// Verify a Groth16 proof
     ///
     /// # Arguments
@@ -51,154 +102,274 @@ impl Verifier {
     /// * `proof` - The Groth16 proof
     ///
     /// # Returns
-    /// `true` if the proof is valid, `false` otherwise
+    /// `true` if the proof is valid, `false` otherwise.
+    /// Use [`Verifier::verify_checked`] to learn why a proof was rejected.
     pub fn verify(&self, inputs: Vec<U256>, proof: Proof) -> bool {
-        // Check input count matches verification key
-        if inputs.len() != self.vk.num_inputs() {
-            return false;
-        }
+        self.verify_checked(&inputs, &proof).is_ok()
+    }
 
+    /// Verify a proof with inputs as decimal strings (snarkjs format)
+    ///
+    /// `0x`-prefixed hex inputs are accepted as well; see [`parse_field_element`].
+    pub fn verify_json(
+        &self,
+        proof_json: &crate::types::ProofJson,
+        inputs: &[String],
+    ) -> bool {
+        self.verify_json_checked(proof_json, inputs).is_ok()
+    }
+
+    /// Verify a Groth16 proof, reporting the reason for any failure
+    pub fn verify_checked(&self, inputs: &[U256], proof: &Proof) -> Result<(), VerifyError> {
+        self.check_request(inputs, proof)?;
+        self.verify_well_formed(inputs, proof)
+    }
+
+    /// Everything that can be rejected without touching the pairing
+    pub(crate) fn check_request(&self, inputs: &[U256], proof: &Proof) -> Result<(), VerifyError> {
+        check_request(self.vk.num_inputs(), inputs, proof)
+    }
+
+    /// vk_x and pairing check for a request that passed [`Verifier::check_request`]
+    pub(crate) fn verify_well_formed(&self, inputs: &[U256], proof: &Proof) -> Result<(), VerifyError> {
         // Compute vk_x = IC[0] + Σ(input[i] * IC[i+1])
         // Using alt_bn128_g1_multiexp for efficient scalar multiplication
-        let vk_x = match self.compute_vk_x(&inputs) {
-            Some(p) => p,
-            None => return false,
-        };
+        let vk_x = self.compute_vk_x(inputs)?;
 
         // Perform pairing check:
         // e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1
-        self.pairing_check(&proof, &vk_x)
+        if self.pairing_check(proof, &vk_x) {
+            Ok(())
+        } else {
+            Err(VerifyError::PairingFailed)
+        }
     }
 
-    /// Verify a proof with inputs as decimal strings (snarkjs format)
-    pub fn verify_json(
+    /// Verify a proof with decimal-string inputs, reporting the reason for any failure
+    pub fn verify_json_checked(
         &self,
         proof_json: &crate::types::ProofJson,
         inputs: &[String],
-    ) -> bool {
+    ) -> Result<(), VerifyError> {
         // Parse proof
-        let proof = match proof_json.to_proof() {
-            Ok(p) => p,
-            Err(_) => return false,
-        };
+        let proof = proof_json.to_proof()?;
 
-        // Parse inputs
-        let inputs: Result<Vec<U256>, _> = inputs
-            .iter()
-            .map(|s| U256::from_dec_str(s))
-            .collect();
-        
-        let inputs = match inputs {
-            Ok(i) => i,
-            Err(_) => return false,
-        };
+        self.verify_decoded_checked(&proof, inputs)
+    }
 
-        self.verify(inputs, proof)
+    /// Verify a [`CompactProof`] with decimal-string inputs
+    pub fn verify_compact(&self, proof: &CompactProof, inputs: &[String]) -> bool {
+        self.verify_compact_checked(proof, inputs).is_ok()
     }
 
-    /// Compute vk_x = IC[0] + Σ(input[i] * IC[i+1])
+    /// Verify a [`CompactProof`], reporting the reason for any failure
     ///
-    /// Uses alt_bn128_g1_multiexp for efficient multi-scalar multiplication
-    fn compute_vk_x(&self, inputs: &[U256]) -> Option<G1Point> {
-        if self.vk.ic.is_empty() {
-            return None;
-        }
-
-        // Start with IC[0]
-        let mut result = self.vk.ic[0].clone();
+    /// Decompressing the three points costs a square root each (two in Fq,
+    /// one in Fq2); the rest is identical to [`Verifier::verify_json_checked`].
+    pub fn verify_compact_checked(
+        &self,
+        proof: &CompactProof,
+        inputs: &[String],
+    ) -> Result<(), VerifyError> {
+        let proof = proof.to_proof()?;
 
-        if inputs.is_empty() {
-            return Some(result);
-        }
+        self.verify_decoded_checked(&proof, inputs)
+    }
 
-        // Build multiexp input: [(scalar, point), ...]
-        // Format: scalar (32 bytes LE) + point (64 bytes)
-        let mut multiexp_input = Vec::with_capacity(inputs.len() * 96);
+    /// Verify a proof and inputs given in NEAR alt_bn128 layout
+    ///
+    /// `proof` is `A ‖ B ‖ C` as in [`Proof::to_precompile_bytes`] and each
+    /// input is a little-endian scalar, so nothing is parsed from decimal.
+    pub fn verify_bytes(&self, proof: &[u8; 256], inputs: &[[u8; 32]]) -> bool {
+        self.verify_bytes_checked(proof, inputs).is_ok()
+    }
 
-        for (i, input) in inputs.iter().enumerate() {
-            if i + 1 >= self.vk.ic.len() {
-                return None; // Not enough IC points
-            }
+    /// [`Verifier::verify_bytes`], reporting the reason for any failure
+    pub fn verify_bytes_checked(&self, proof: &[u8; 256], inputs: &[[u8; 32]]) -> Result<(), VerifyError> {
+        let proof = Proof::from_precompile_bytes(proof);
+        let inputs: Vec<U256> = inputs.iter().map(|x| U256::from_le_bytes(x)).collect();
 
-            // Skip zero inputs (optimization)
-            if input.is_zero() {
-                continue;
-            }
+        self.verify_checked(&inputs, &proof)
+    }
 
-            // Scalar in little-endian format for NEAR precompile
-            let mut scalar_le = [0u8; 32];
-            input.to_little_endian(&mut scalar_le);
-            multiexp_input.extend_from_slice(&scalar_le);
+    /// Verify an already-decoded proof with decimal-string inputs
+    ///
+    /// Lets a contract accept several proof encodings, decode each at the
+    /// method boundary and share the rest of the verification path.
+    pub fn verify_decoded_checked(&self, proof: &Proof, inputs: &[String]) -> Result<(), VerifyError> {
+        let inputs = parse_inputs(inputs)?;
 
-            // Point in big-endian format
-            multiexp_input.extend_from_slice(&self.vk.ic[i + 1].to_bytes());
-        }
+        self.verify_checked(&inputs, proof)
+    }
 
-        // If all inputs were zero, just return IC[0]
-        if multiexp_input.is_empty() {
-            return Some(result);
-        }
+    /// Compute vk_x = IC[0] + Σ(input[i] * IC[i+1])
+    ///
+    /// Uses alt_bn128_g1_multiexp for efficient multi-scalar multiplication
+    fn compute_vk_x(&self, inputs: &[U256]) -> Result<G1Point, VerifyError> {
+        compute_vk_x(&self.backend, self.vk.ic.len(), inputs, |i| self.vk.ic[i].clone())
+    }
 
-        // Perform multi-scalar multiplication
-        let multiexp_result = env::alt_bn128_g1_multiexp(&multiexp_input);
-        
-        if multiexp_result.len() != 64 {
-            return None;
-        }
+    /// Perform the pairing check using the alt_bn128_pairing_check operation
+    ///
+    /// Checks: e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1
+    fn pairing_check(&self, proof: &Proof, vk_x: &G1Point) -> bool {
+        // The pairing check returns true if the product of pairings equals 1
+        self.backend.pairing_check(&self.pairing_input(proof, vk_x))
+    }
 
-        let sum_point = G1Point::from_bytes(&multiexp_result.try_into().ok()?);
+    /// Build pairing input: [(G1_1, G2_1), (G1_2, G2_2), ...]
+    pub(crate) fn pairing_input(&self, proof: &Proof, vk_x: &G1Point) -> [u8; PAIRING_INPUT_LEN] {
+        let vk = &self.vk;
+        pairing_input(&vk.alpha, &vk.beta, &vk.gamma, &vk.delta, proof, vk_x)
+    }
+}
 
-        // Add IC[0] + multiexp result using alt_bn128_g1_sum
-        let sum_result = self.add_g1_points(&result, &sum_point)?;
-        
-        Some(sum_result)
+/// Compute vk_x = IC[0] + Σ(input[i] * IC[i+1]) for a key with `ic_len` IC points
+///
+/// `ic(i)` is only called for IC[0] and for the points whose input is
+/// non-zero, so a storage-backed key reads nothing else.
+pub(crate) fn compute_vk_x<B: Bn254Backend>(
+    backend: &B,
+    ic_len: usize,
+    inputs: &[U256],
+    ic: impl Fn(usize) -> G1Point,
+) -> Result<G1Point, VerifyError> {
+    // Start with IC[0]
+    if ic_len == 0 {
+        return Err(VerifyError::MissingIcPoint { index: 0 });
+    }
+    if inputs.len() >= ic_len {
+        return Err(VerifyError::MissingIcPoint { index: ic_len });
     }
+    let result = ic(0);
 
-    /// Add two G1 points using NEAR's alt_bn128_g1_sum precompile
-    fn add_g1_points(&self, p1: &G1Point, p2: &G1Point) -> Option<G1Point> {
-        // Format: num_points (1 byte) + point1 (64 bytes) + point2 (64 bytes)
-        // Actually NEAR expects: point1 || point2 without length prefix
-        let mut input = Vec::with_capacity(128);
-        input.extend_from_slice(&p1.to_bytes());
-        input.extend_from_slice(&p2.to_bytes());
+    // Build multiexp input: [(point, scalar), ...]
+    // Format: point (64 bytes) + scalar (32 bytes), all little-endian
+    let mut multiexp_input = Vec::with_capacity(inputs.len() * 96);
 
-        let result = env::alt_bn128_g1_sum(&input);
-        
-        if result.len() != 64 {
-            return None;
+    for (i, input) in inputs.iter().enumerate() {
+        // Skip zero inputs (optimization)
+        if input.is_zero() {
+            continue;
         }
 
-        Some(G1Point::from_bytes(&result.try_into().ok()?))
+        multiexp_input.extend_from_slice(&ic(i + 1).to_precompile_bytes());
+        multiexp_input.extend_from_slice(&input.to_le_bytes());
     }
 
-    /// Perform the pairing check using NEAR's alt_bn128_pairing_check precompile
-    ///
-    /// Checks: e(-A, B) · e(α, β) · e(vk_x, γ) · e(C, δ) = 1
-    fn pairing_check(&self, proof: &Proof, vk_x: &G1Point) -> bool {
-        // Build pairing input: [(G1_1, G2_1), (G1_2, G2_2), ...]
-        // Format: G1 (64 bytes) + G2 (128 bytes) per pair
-        let mut pairing_input = Vec::with_capacity(4 * 192); // 4 pairs × 192 bytes
+    // If all inputs were zero, just return IC[0]
+    if multiexp_input.is_empty() {
+        return Ok(result);
+    }
+
+    // Perform multi-scalar multiplication
+    let multiexp_result: [u8; 64] = backend
+        .g1_multiexp(&multiexp_input)
+        .try_into()
+        .map_err(|_| VerifyError::MultiexpFailed)?;
+
+    // Add IC[0] + multiexp result using alt_bn128_g1_sum
+    let sum = g1_sum(backend, &result.to_precompile_bytes(), &multiexp_result)?;
+    Ok(G1Point::from_precompile_bytes(&sum))
+}
 
-        // Pair 1: (-A, B) - negate A for the equation transformation
-        let neg_a = negate_g1(&proof.a);
-        pairing_input.extend_from_slice(&neg_a.to_bytes());
-        pairing_input.extend_from_slice(&proof.b.to_bytes());
+/// Build pairing input: [(G1_1, G2_1), (G1_2, G2_2), ...]
+/// Format: G1 (64 bytes) + G2 (128 bytes) per pair, little-endian coordinates
+pub(crate) fn pairing_input(
+    alpha: &G1Point,
+    beta: &G2Point,
+    gamma: &G2Point,
+    delta: &G2Point,
+    proof: &Proof,
+    vk_x: &G1Point,
+) -> [u8; PAIRING_INPUT_LEN] {
+    let mut input = [0u8; PAIRING_INPUT_LEN];
 
-        // Pair 2: (α, β)
-        pairing_input.extend_from_slice(&self.vk.alpha.to_bytes());
-        pairing_input.extend_from_slice(&self.vk.beta.to_bytes());
+    // Pair 1: (-A, B) - negate A for the equation transformation
+    input[0..64].copy_from_slice(&negate_g1(&proof.a).to_precompile_bytes());
+    input[64..192].copy_from_slice(&proof.b.to_precompile_bytes());
 
-        // Pair 3: (vk_x, γ)
-        pairing_input.extend_from_slice(&vk_x.to_bytes());
-        pairing_input.extend_from_slice(&self.vk.gamma.to_bytes());
+    // Pair 2: (α, β)
+    input[192..256].copy_from_slice(&alpha.to_precompile_bytes());
+    input[256..384].copy_from_slice(&beta.to_precompile_bytes());
 
-        // Pair 4: (C, δ)
-        pairing_input.extend_from_slice(&proof.c.to_bytes());
-        pairing_input.extend_from_slice(&self.vk.delta.to_bytes());
+    // Pair 3: (vk_x, γ)
+    input[384..448].copy_from_slice(&vk_x.to_precompile_bytes());
+    input[448..576].copy_from_slice(&gamma.to_precompile_bytes());
 
-        // The pairing check returns true if the product of pairings equals 1
-        env::alt_bn128_pairing_check(&pairing_input)
+    // Pair 4: (C, δ)
+    input[576..640].copy_from_slice(&proof.c.to_precompile_bytes());
+    input[640..768].copy_from_slice(&delta.to_precompile_bytes());
+
+    input
+}
+
+/// `p1 + p2` via alt_bn128_g1_sum, on points already in precompile layout
+pub(crate) fn g1_sum<B: Bn254Backend>(
+    backend: &B,
+    p1: &[u8; 64],
+    p2: &[u8; 64],
+) -> Result<[u8; 64], VerifyError> {
+    // Format: per point, a sign byte (0 = add, 1 = subtract) + point (64 bytes)
+    let mut input = [0u8; 130];
+    input[1..65].copy_from_slice(p1);
+    input[66..130].copy_from_slice(p2);
+
+    backend
+        .g1_sum(&input)
+        .try_into()
+        .map_err(|_| VerifyError::G1SumFailed)
+}
+
+/// Reject a request before any host function is called
+///
+/// Checks the input count against the key, that every input is a canonical
+/// scalar, and that the proof points are valid.
+pub(crate) fn check_request(
+    num_inputs: usize,
+    inputs: &[U256],
+    proof: &Proof,
+) -> Result<(), VerifyError> {
+    // Check input count matches verification key
+    if inputs.len() != num_inputs {
+        return Err(VerifyError::InputCountMismatch {
+            expected: num_inputs,
+            found: inputs.len(),
+        });
+    }
+
+    // Reject non-canonical scalars: x and x + r would otherwise both verify
+    if let Some(index) = inputs.iter().position(|x| *x >= FR_MODULUS) {
+        return Err(VerifyError::NonCanonicalInput { index });
+    }
+
+    // Invalid points make the host functions abort instead of returning false
+    proof.validate()
+}
+
+/// Parse decimal / hex input strings, mapping failures to the input index
+pub fn parse_inputs(inputs: &[String]) -> Result<Vec<U256>, VerifyError> {
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(index, s)| {
+            parse_field_element(s).map_err(|e| match e {
+                ParseError::NonCanonicalFieldElement => VerifyError::NonCanonicalInput { index },
+                _ => VerifyError::InvalidInput { index },
+            })
+        })
+        .collect()
+}
+
+/// Split concatenated little-endian 32-byte scalars, as passed in a
+/// `Base64VecU8` contract argument
+///
+/// Canonicity (`< r`) is checked later with the rest of the request.
+pub fn parse_le_inputs(bytes: &[u8]) -> Result<Vec<U256>, VerifyError> {
+    if !bytes.len().is_multiple_of(32) {
+        return Err(ParseError::UnexpectedEnd.into());
     }
+    Ok(bytes.chunks_exact(32).map(U256::from_le_bytes).collect())
 }
 
 /// Standalone verification function (for use without Verifier struct)
@@ -214,10 +385,10 @@ pub fn verify_proof(
 #[cfg(all(test, feature = "standalone"))]
 mod standalone_tests {
     use super::*;
+    use crate::types::G2Point;
 
-    // These tests require a mock environment and are only run
-    // with the standalone feature enabled
-    
+    // Only run with the standalone feature enabled, where the alt_bn128
+    // calls go to the native backend instead of the mocked host
     #[test]
     fn test_verifier_creation() {
         let vk = VerificationKey {
@@ -40,6 +40,7 @@
  * - **wallet**: Wallet connection and transaction formatting
  * - **prover**: Proof generation with snarkjs
  * - **merkle**: Client-side Merkle tree implementation
+ * - **inputs**: Public input encodings shared with the contract
  * - **notes**: Note management and UTXO tracking
  *
  * ## Architecture
@@ -163,6 +164,9 @@ export {
     type MerkleProof
 } from './merkle';
 
+// Public input encodings
+export { encodePoseidonAccount } from './inputs';
+
 // Note management
 export {
     saveNote,
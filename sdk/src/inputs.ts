@@ -0,0 +1,59 @@
+/**
+ * Public Input Encodings
+ *
+ * Contracts built with `#[derive(PublicInputs)]` read some fields through a
+ * named encoding (`#[field(encoding = "...")]`) instead of the raw value.
+ * The prover must put the same field element in the witness, so each
+ * encoding the contract can check has a twin here.
+ *
+ * CRITICAL: The circuit must compute the encoding the same way, or pass
+ * it in as a public signal computed with these helpers.
+ */
+
+type PoseidonHashFunction = (inputs: bigint[]) => bigint;
+
+/**
+ * `poseidon_account` encoding of a NEAR account id
+ *
+ * Same as `PoseidonAccount` in the Rust crate and `near-zk encode-account`:
+ * the id's bytes are split into 31-byte chunks (the last one zero-padded
+ * on the right), each read as a big-endian field element, and folded with
+ * the 2-input Poseidon starting from the byte length:
+ *
+ * ```text
+ * h = len
+ * h = Poseidon(h, chunk_i)   for each chunk
+ * ```
+ *
+ * @param accountId - NEAR account id (e.g. "alice.near")
+ * @param hasher - circomlib Poseidon hash function
+ * @returns The public input as a field element
+ *
+ * EXAMPLE:
+ * ```typescript
+ * const { buildPoseidon } = require("circomlibjs");
+ * const hasher = await buildPoseidon();
+ * const poseidon = (inputs: bigint[]) => hasher.F.toObject(hasher(inputs));
+ *
+ * const recipient = encodePoseidonAccount("alice.near", poseidon);
+ * const witnessInput = { ..., recipient: recipient.toString() };
+ * ```
+ */
+export function encodePoseidonAccount(
+    accountId: string,
+    hasher: PoseidonHashFunction
+): bigint {
+    // Account ids are ASCII, so bytes and characters coincide
+    const bytes = new TextEncoder().encode(accountId);
+
+    let h = BigInt(bytes.length);
+    for (let start = 0; start < bytes.length; start += 31) {
+        let word = 0n;
+        for (let i = start; i < start + 31; i++) {
+            word = (word << 8n) | BigInt(i < bytes.length ? bytes[i] : 0);
+        }
+        h = hasher([h, word]);
+    }
+
+    return h;
+}
@@ -166,17 +166,19 @@ export class IncrementalMerkleTree {
         let currentValue = leaf;
 
         for (let level = 0; level < this.depth; level++) {
+            // Siblings come from the current level, not from the leaves
+            const currentLevel = this.levels.get(level) || [];
             const levelNodes = this.levels.get(level + 1) || [];
             const isRightNode = currentIndex % 2 === 1;
 
             if (isRightNode) {
                 // Right node - sibling is to the left
-                const leftSibling = leaves[currentIndex - 1];
+                const leftSibling = currentLevel[currentIndex - 1];
                 currentValue = this.hasher([leftSibling, currentValue]);
             } else {
                 // Left node - sibling is zero or existing right node
-                const rightSibling = (currentIndex + 1 < leaves.length)
-                    ? leaves[currentIndex + 1]
+                const rightSibling = (currentIndex + 1 < currentLevel.length)
+                    ? currentLevel[currentIndex + 1]
                     : this.zeros[level];
                 currentValue = this.hasher([currentValue, rightSibling]);
             }
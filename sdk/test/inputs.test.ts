@@ -0,0 +1,37 @@
+/**
+ * Cross-checks the SDK encodings against the Rust `inputs::encoding`
+ * (`test_poseidon_account_vectors` in lib/src/inputs.rs pins the same
+ * values, and `near-zk encode-account` prints them).
+ */
+import { encodePoseidonAccount } from '../src/inputs';
+
+// circomlibjs ships no type declarations
+const { buildPoseidon } = require('circomlibjs');
+
+/** A partial chunk, exactly one full chunk, three chunks */
+const POSEIDON_ACCOUNTS: [string, bigint][] = [
+    ['alice.near', 8778125842548807958193322087000312670245895272044483519524001245225809719431n],
+    ['abcdefghijklmnopqrstuvwxyz.near', 18653267240135196620277531259493146812472663294260401187258426703737034071678n],
+    [
+        '0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef',
+        10539481583516436834325637726350711868622939599347597841586045252112824219914n,
+    ],
+];
+
+let poseidon: (inputs: bigint[]) => bigint;
+
+beforeAll(async () => {
+    const hasher = await buildPoseidon();
+    poseidon = (inputs) => hasher.F.toObject(hasher(inputs));
+});
+
+describe('encodePoseidonAccount', () => {
+    test.each(POSEIDON_ACCOUNTS)('%s matches the Rust encoding', (accountId, encoded) => {
+        expect(encodePoseidonAccount(accountId, poseidon)).toBe(encoded);
+    });
+
+    test('single chunk is Poseidon(len, chunk)', () => {
+        const chunk = BigInt('0x' + Buffer.from('alice.near').toString('hex')) << BigInt(8 * 21);
+        expect(encodePoseidonAccount('alice.near', poseidon)).toBe(poseidon([10n, chunk]));
+    });
+});
@@ -0,0 +1,47 @@
+/**
+ * Cross-checks the SDK tree against the Rust `MerkleTree` and the on-chain
+ * `IncrementalMerkleTree` (`test_sdk_roots` in lib/src/merkle.rs pins the
+ * same roots).
+ */
+import { IncrementalMerkleTree, verifyMerkleProof } from '../src/merkle';
+
+// circomlibjs ships no type declarations
+const { buildPoseidon } = require('circomlibjs');
+
+/** Roots over leaves `i * 1000 + 7` for `i = 1..=n`, depth 20 */
+const RUST_ROOTS: [number, bigint][] = [
+    [3, 8195400888830981910601246489126998430755625839197294666455421976640144083276n],
+    [5, 1020791116174676611517720155168092884247281460124522095558744697616005116087n],
+];
+
+const leaves = (count: number): bigint[] =>
+    Array.from({ length: count }, (_, i) => BigInt(i + 1) * 1000n + 7n);
+
+let poseidon: (inputs: bigint[]) => bigint;
+
+beforeAll(async () => {
+    const hasher = await buildPoseidon();
+    poseidon = (inputs) => hasher.F.toObject(hasher(inputs));
+});
+
+describe('IncrementalMerkleTree', () => {
+    test.each(RUST_ROOTS)('root over %i leaves matches the Rust trees', (count, root) => {
+        const tree = new IncrementalMerkleTree(20, poseidon);
+        for (const leaf of leaves(count)) {
+            tree.insert(leaf);
+        }
+        expect(tree.getRoot()).toBe(root);
+
+        expect(IncrementalMerkleTree.fromCommitments(leaves(count), 20, poseidon).getRoot()).toBe(root);
+        expect(IncrementalMerkleTree.importState(tree.exportState(), poseidon).getRoot()).toBe(root);
+    });
+
+    test.each(RUST_ROOTS)('proofs over %i leaves verify against the Rust root', (count, root) => {
+        const tree = IncrementalMerkleTree.fromCommitments(leaves(count), 20, poseidon);
+        for (let i = 0; i < count; i++) {
+            const proof = tree.getProof(i);
+            expect(proof.root).toBe(root);
+            expect(verifyMerkleProof(proof, poseidon)).toBe(true);
+        }
+    });
+});
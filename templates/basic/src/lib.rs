@@ -49,8 +49,11 @@
 //! what you're proving - it just verifies the math. Circuit semantics are enforced
 //! off-chain by your application logic.
 
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::{near, env, PanicOnDefault};
-use near_groth16_verifier::{Verifier, ProofJson, VerificationKeyJson};
+use near_groth16_verifier::{
+    parse_le_inputs, CompactProof, Proof, ProofJson, VerificationKeyJson, Verifier, VerifyError,
+};
 
 #[near(contract_state)]
 #[derive(PanicOnDefault)]
@@ -72,7 +75,8 @@ impl BasicVerifier {
     /// ```
     #[init]
     pub fn new(vk: VerificationKeyJson) -> Self {
-        let verifier = Verifier::from_json(&vk).expect("Invalid verification key format");
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
         env::log_str(&format!("Verifier initialized with {} public inputs", verifier.vk.num_inputs()));
         Self { verifier }
     }
@@ -84,7 +88,7 @@ impl BasicVerifier {
     /// * `public_inputs` - Public inputs as decimal strings
     ///
     /// # Returns
-    /// `true` if proof is valid, panics otherwise
+    /// `true` if proof is valid, panics with the failure reason otherwise
     ///
     /// # Example
     /// ```bash
@@ -94,14 +98,28 @@ impl BasicVerifier {
     /// }' --accountId user.testnet
     /// ```
     pub fn verify(&self, proof: ProofJson, public_inputs: Vec<String>) -> bool {
-        let is_valid = self.verifier.verify_json(&proof, &public_inputs);
+        verified(self.verifier.verify_json_checked(&proof, &public_inputs))
+    }
 
-        if is_valid {
-            env::log_str("Proof verified successfully");
-            true
-        } else {
-            env::panic_str("Proof verification failed")
-        }
+    /// Verify a proof in compact encoding
+    ///
+    /// Same as [`verify`](Self::verify), but `proof` is the 128-byte
+    /// compressed encoding as base64 (`near-zk format-proof --compact`).
+    pub fn verify_compact(&self, proof: CompactProof, public_inputs: Vec<String>) -> bool {
+        verified(self.verifier.verify_compact_checked(&proof, &public_inputs))
+    }
+
+    /// Verify a proof given in the alt_bn128 precompile layout
+    ///
+    /// Skips decimal parsing entirely, the cheapest entry point.
+    ///
+    /// # Arguments
+    /// * `proof` - `A ‖ B ‖ C`, 256 bytes, little-endian coordinates
+    /// * `public_inputs` - 32 little-endian bytes per input, concatenated
+    ///
+    /// `near-zk call-args` prints the arguments in this format.
+    pub fn verify_bytes(&self, proof: Base64VecU8, public_inputs: Base64VecU8) -> bool {
+        verified(self.verify_bytes_checked(&proof, &public_inputs))
     }
 
     /// Check proof without state changes (view method)
@@ -111,10 +129,37 @@ impl BasicVerifier {
         self.verifier.verify_json(&proof, &public_inputs)
     }
 
+    /// Check a compact proof without state changes (view method)
+    pub fn check_proof_compact(&self, proof: CompactProof, public_inputs: Vec<String>) -> bool {
+        self.verifier.verify_compact(&proof, &public_inputs)
+    }
+
+    /// Check a precompile-layout proof without state changes (view method)
+    pub fn check_proof_bytes(&self, proof: Base64VecU8, public_inputs: Base64VecU8) -> bool {
+        self.verify_bytes_checked(&proof, &public_inputs).is_ok()
+    }
+
     /// Get the number of expected public inputs
     pub fn num_public_inputs(&self) -> usize {
         self.verifier.vk.num_inputs()
     }
+
+    fn verify_bytes_checked(&self, proof: &Base64VecU8, public_inputs: &Base64VecU8) -> Result<(), VerifyError> {
+        let proof = Proof::from_precompile_slice(&proof.0)?;
+        let inputs = parse_le_inputs(&public_inputs.0)?;
+        self.verifier.verify_checked(&inputs, &proof)
+    }
+}
+
+/// Log success, or panic with the failure reason
+fn verified(result: Result<(), VerifyError>) -> bool {
+    match result {
+        Ok(()) => {
+            env::log_str("Proof verified successfully");
+            true
+        }
+        Err(e) => env::panic_str(&format!("Proof verification failed: {}", e)),
+    }
 }
 
 #[cfg(test)]
@@ -6,6 +6,7 @@
 ///!
 ///! **Shared Merkle Tree**: All assets use ONE tree for commitments, creating a
 ///! larger anonymity set. Tree can contain: [NEAR deposit, USDC deposit, NEAR deposit, ...]
+///! The contract keeps the tree's recent roots; proofs against any other root are rejected.
 ///!
 ///! **Asset IDs**: Each token gets a unique Poseidon hash:
 ///! - NEAR: assetId = 0
@@ -42,12 +43,26 @@
 ///!
 ///! Anonymity set = deposits with same asset+amount. Use standard denominations
 ///! (1, 10, 100 USDC) for better privacy.
+///!
+///! **Not bound by the proof**: with the default `["nullifier", "root", "commitment", "asset"]`
+///! layout, the withdraw methods' `recipient` and `amount` arguments are not public inputs.
+///! Whoever submits a valid proof first chooses where the funds go and how much leaves the
+///! pool. Name them `"recipient"` and `"amount"` in the layout to have withdrawals reject
+///! arguments that differ from the proven values.
 
 use near_sdk::borsh::BorshSerialize;
 use near_sdk::store::{LookupSet, UnorderedMap, Vector};
 use near_sdk::{near, env, require, AccountId, PanicOnDefault, NearToken, Gas, PromiseOrValue, PromiseResult, ext_contract};
-use near_sdk::json_types::U128;
-use near_groth16_verifier::{Verifier, ProofJson, VerificationKeyJson, U256};
+use near_sdk::json_types::{Base64VecU8, U128};
+use near_groth16_verifier::{
+    parse_field_element, parse_inputs, parse_le_inputs, CompactProof, IncrementalMerkleTree,
+    InputRole, LayoutInputs, Proof, ProofJson, PublicInputLayout, VerificationKeyJson, Verifier,
+    VerifyError, DEFAULT_ROOT_HISTORY, U256,
+};
+use near_groth16_verifier::inputs::encoding::{Encoding, PoseidonAccount};
+
+/// Commitment tree depth, as in `Withdraw(20)` (~1M notes)
+const TREE_DEPTH: u8 = 20;
 
 /// External FT contract interface (NEP-141)
 #[ext_contract(ext_ft)]
@@ -104,6 +119,9 @@ pub struct MultiAssetPool {
     /// Groth16 verifier for ZK proofs
     verifier: Verifier,
 
+    /// Role of each public input (checked against the VK at init)
+    layout: PublicInputLayout,
+
     /// Spent nullifiers (shared across all assets to prevent cross-asset double-spend)
     nullifiers: LookupSet<[u8; 32]>,
 
@@ -113,6 +131,9 @@ pub struct MultiAssetPool {
     /// Ordered commitment list (Merkle tree leaves)
     commitments_list: Vector<[u8; 32]>,
 
+    /// Poseidon Merkle tree over `commitments_list` (frontier and recent roots only)
+    tree: IncrementalMerkleTree,
+
     /// Per-asset balances: assetId → amount locked
     ///
     /// **Why needed?** Prevents inflation attacks where someone deposits 1 USDC
@@ -147,10 +168,37 @@ impl MultiAssetPool {
     ///
     /// **Circuit requirements**: Must include assetId as public input and enforce
     /// that all inputs/outputs use the same assetId (no asset mixing).
+    ///
+    /// **Layout**: role of each public input, default
+    /// `["nullifier", "root", "commitment", "asset"]`. Must name a nullifier, a root and an
+    /// asset; `transfer` also needs a commitment. An `amount` and a `recipient` (the
+    /// `PoseidonAccount` encoding of the account id) bind the withdraw arguments to the proof.
+    /// Circuits spending or creating several notes repeat `"nullifier"` / `"commitment"`;
+    /// every nullifier is spent and every commitment added to the tree.
     #[init]
-    pub fn new(vk: VerificationKeyJson) -> Self {
-        let verifier = Verifier::from_json(&vk)
-            .expect("Invalid verification key");
+    pub fn new(vk: VerificationKeyJson, layout: Option<PublicInputLayout>) -> Self {
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
+
+        // SECURITY: A layout without an asset input would let proofs for one
+        // asset withdraw another; one without a root, proofs against a self-made tree
+        let layout = layout.unwrap_or_else(|| {
+            PublicInputLayout::new(vec![
+                InputRole::Nullifier,
+                InputRole::Root,
+                InputRole::Commitment,
+                InputRole::Asset,
+            ])
+            .expect("roles are distinct")
+        });
+        if let Err(e) = layout
+            .check_vk(&verifier.vk)
+            .and_then(|()| layout.require(&InputRole::Nullifier))
+            .and_then(|_| layout.require(&InputRole::Root))
+            .and_then(|_| layout.require(&InputRole::Asset))
+        {
+            env::panic_str(&format!("Invalid public input layout: {}", e));
+        }
 
         env::log_str(&format!(
             "Multi-asset pool initialized for {} public inputs",
@@ -159,9 +207,12 @@ impl MultiAssetPool {
 
         Self {
             verifier,
+            layout,
             nullifiers: LookupSet::new(b"n"),
             commitments: LookupSet::new(b"c"),
             commitments_list: Vector::new(b"l"),
+            tree: IncrementalMerkleTree::new(TREE_DEPTH, DEFAULT_ROOT_HISTORY)
+                .expect("valid tree parameters"),
             asset_balances: UnorderedMap::new(b"a"),
             registered_tokens: LookupSet::new(b"r"),
             token_asset_ids: UnorderedMap::new(b"t"),
@@ -197,6 +248,8 @@ impl MultiAssetPool {
     ///
     /// **Public inputs**: [nullifier_hash, asset_id, amount, ...]
     /// **Security**: Verifies assetId is 0 and pool has sufficient NEAR balance
+    /// **Not checked by default**: `recipient` and `amount` are only bound to the proof when
+    /// the layout names them (see the crate docs).
     /// **Gas**: ~120-150 TGas (Groth16 verification is expensive!)
     #[payable]
     pub fn withdraw_near(
@@ -206,19 +259,62 @@ impl MultiAssetPool {
         recipient: AccountId,
         amount: String,
     ) {
-        require!(
-            public_inputs.len() >= 2,
-            "Must include nullifier and assetId"
-        );
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw_near(proof, inputs, recipient, amount)
+    }
+
+    /// [`withdraw_near`](Self::withdraw_near) with the proof in compact encoding
+    ///
+    /// 128 bytes of base64 instead of ~1 KB of decimal strings; produce it with
+    /// `near-zk format-proof --compact`.
+    #[payable]
+    pub fn withdraw_near_compact(
+        &mut self,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+        recipient: AccountId,
+        amount: String,
+    ) {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw_near(proof, inputs, recipient, amount)
+    }
+
+    /// [`withdraw_near`](Self::withdraw_near) with proof and inputs in the
+    /// alt_bn128 precompile layout (`near-zk call-args`)
+    ///
+    /// `proof` is 256 bytes; `public_inputs` is 32 little-endian bytes per input.
+    #[payable]
+    pub fn withdraw_near_bytes(
+        &mut self,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+        recipient: AccountId,
+        amount: String,
+    ) {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw_near(proof, inputs, recipient, amount)
+    }
+
+    fn internal_withdraw_near(
+        &mut self,
+        proof: Proof,
+        public_inputs: Vec<U256>,
+        recipient: AccountId,
+        amount: String,
+    ) {
+        let inputs = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e));
+        let asset_id = inputs.asset().expect("layout has an asset");
 
         // Verify assetId is 0 (NEAR)
         require!(
-            public_inputs[1] == "0",
+            asset_id.is_zero(),
             "AssetId must be 0 for NEAR withdrawal"
         );
 
-        let withdraw_amount = U256::from_dec_str(&amount)
-            .expect("Invalid amount");
+        let withdraw_amount = check_withdraw_arguments(&inputs, &amount, &recipient);
         let withdraw_yocto = u128::try_from(withdraw_amount)
             .expect("Amount too large");
 
@@ -343,6 +439,9 @@ impl MultiAssetPool {
     /// **Security**: Verifies asset_id in proof matches Poseidon hash of token_contract.
     /// This ensures you can't prove ownership of USDC deposit but withdraw USDT.
     ///
+    /// **Not checked by default**: `recipient` and `amount` are only bound to the proof when
+    /// the layout names them (see the crate docs).
+    ///
     /// **Gas**: ~140-180 TGas (verification + FT transfer + callback)
     #[payable]
     pub fn withdraw_ft(
@@ -353,10 +452,52 @@ impl MultiAssetPool {
         amount: String,
         token_contract: AccountId,
     ) {
-        require!(
-            public_inputs.len() >= 2,
-            "Must include nullifier and assetId"
-        );
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw_ft(proof, inputs, recipient, amount, token_contract)
+    }
+
+    /// [`withdraw_ft`](Self::withdraw_ft) with the proof in compact encoding
+    #[payable]
+    pub fn withdraw_ft_compact(
+        &mut self,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+        recipient: AccountId,
+        amount: String,
+        token_contract: AccountId,
+    ) {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw_ft(proof, inputs, recipient, amount, token_contract)
+    }
+
+    /// [`withdraw_ft`](Self::withdraw_ft) with proof and inputs in the
+    /// alt_bn128 precompile layout
+    #[payable]
+    pub fn withdraw_ft_bytes(
+        &mut self,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+        recipient: AccountId,
+        amount: String,
+        token_contract: AccountId,
+    ) {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw_ft(proof, inputs, recipient, amount, token_contract)
+    }
+
+    fn internal_withdraw_ft(
+        &mut self,
+        proof: Proof,
+        public_inputs: Vec<U256>,
+        recipient: AccountId,
+        amount: String,
+        token_contract: AccountId,
+    ) {
+        let inputs = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e));
+        let asset_id = inputs.asset().expect("layout has an asset");
 
         // SECURITY FIX (CRITICAL-1): Verify assetId matches token contract's Poseidon hash
         let expected_asset_id = self.token_asset_ids.get(&token_contract)
@@ -368,15 +509,14 @@ impl MultiAssetPool {
             }).clone();
 
         require!(
-            public_inputs[1] == expected_asset_id,
+            parse_field_element(&expected_asset_id) == Ok(asset_id),
             format!(
                 "AssetId mismatch: expected {} for {}. Ensure client uses correct Poseidon hash.",
                 expected_asset_id, token_contract
             )
         );
 
-        let withdraw_amount = U256::from_dec_str(&amount)
-            .expect("Invalid amount");
+        let withdraw_amount = check_withdraw_arguments(&inputs, &amount, &recipient);
         let withdraw_tokens = u128::try_from(withdraw_amount)
             .expect("Amount too large");
 
@@ -476,19 +616,51 @@ impl MultiAssetPool {
         &mut self,
         proof: ProofJson,
         public_inputs: Vec<String>,
+    ) {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_transfer(proof, inputs)
+    }
+
+    /// [`transfer`](Self::transfer) with the proof in compact encoding
+    #[payable]
+    pub fn transfer_compact(
+        &mut self,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+    ) {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_transfer(proof, inputs)
+    }
+
+    /// [`transfer`](Self::transfer) with proof and inputs in the alt_bn128
+    /// precompile layout
+    #[payable]
+    pub fn transfer_bytes(
+        &mut self,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+    ) {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_transfer(proof, inputs)
+    }
+
+    fn internal_transfer(
+        &mut self,
+        proof: Proof,
+        public_inputs: Vec<U256>,
     ) {
         require!(
-            public_inputs.len() >= 2,
-            "Must include nullifier and commitment"
+            self.layout.position(&InputRole::Commitment).is_some(),
+            "Circuit layout has no commitment input"
         );
 
         self.internal_verify_and_register(&proof, &public_inputs);
 
-        let asset_id = if public_inputs.len() > 2 {
-            public_inputs[2].clone()
-        } else {
-            "0".to_string()  // Default to NEAR
-        };
+        let asset_id = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e))
+            .asset().expect("layout has an asset").to_string();
 
         env::log_str(&format!(
             "Private transfer completed for asset {}",
@@ -505,7 +677,7 @@ impl MultiAssetPool {
         asset_id: String,
         amount: u128,
     ) {
-        let commitment_u256 = U256::from_dec_str(&commitment)
+        let commitment_u256 = parse_field_element(&commitment)
             .expect("Invalid commitment");
         let commitment_bytes = commitment_u256.to_be_bytes();
 
@@ -521,9 +693,7 @@ impl MultiAssetPool {
         );
 
         // Add commitment to tree
-        self.commitments.insert(commitment_bytes);
-        let leaf_index = self.commitments_list.len() as u64;
-        self.commitments_list.push(commitment_bytes);
+        let leaf_index = self.add_leaf(commitment_u256);
 
         // Update asset balance
         let current_balance = self.asset_balances.get(&asset_id).copied().unwrap_or(0);
@@ -539,30 +709,37 @@ impl MultiAssetPool {
     /// Internal proof verification and state update
     fn internal_verify_and_register(
         &mut self,
-        proof: &ProofJson,
-        public_inputs: &Vec<String>,
+        proof: &Proof,
+        public_inputs: &[U256],
     ) {
-        // Parse nullifier
-        let nullifier_u256 = U256::from_dec_str(&public_inputs[0])
-            .expect("Invalid nullifier");
-        let nullifier_bytes = nullifier_u256.to_be_bytes();
+        let inputs = self.layout.bind(public_inputs).unwrap_or_else(|e| reject(e));
+        // Nullifier, root and asset are required by the layout at init; a circuit
+        // spending or creating several notes repeats the nullifier and commitment roles
+        let nullifiers = inputs.nullifiers();
+        let root = inputs.root().expect("layout has a root");
+        let commitments = inputs.commitments();
+        let asset_id = inputs.asset().expect("layout has an asset").to_string();
+
+        // SECURITY: The spent note must be in this pool's tree, not a self-made one
+        require!(
+            self.tree.is_known_root(&root),
+            "Unknown Merkle root - proof is not against a recent pool root"
+        );
 
         // SECURITY: Prevent double-spend
         // Nullifier is derived from your secret, so only you know it.
         // Once revealed during withdrawal, it's marked spent forever.
-        require!(
-            !self.nullifiers.contains(&nullifier_bytes),
-            "Nullifier already used"
-        );
-
-        // If creating new commitment (transfer), check uniqueness
-        if public_inputs.len() > 1 && !public_inputs[1].is_empty() {
-            let commitment_u256 = U256::from_dec_str(&public_inputs[1])
-                .expect("Invalid commitment");
-            let commitment_bytes = commitment_u256.to_be_bytes();
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            require!(
+                !nullifiers[..i].contains(nullifier) && !self.nullifiers.contains(&nullifier.to_be_bytes()),
+                "Nullifier already used"
+            );
+        }
 
+        // If creating new commitments (transfer), check uniqueness
+        for (i, commitment) in commitments.iter().enumerate() {
             require!(
-                !self.commitments.contains(&commitment_bytes),
+                !commitments[..i].contains(commitment) && !self.commitments.contains(&commitment.to_be_bytes()),
                 "Commitment already exists - cannot reuse commitments"
             );
         }
@@ -573,50 +750,46 @@ impl MultiAssetPool {
         // 2. Commitment C exists in the current Merkle tree
         // 3. Nullifier hash is correctly computed
         // 4. Public inputs (amount, recipient, etc) match the function call
-        let is_valid = self.verifier.verify_json(proof, public_inputs);
-        require!(is_valid, "Proof verification failed");
+        if let Err(e) = self.verifier.verify_checked(inputs.as_slice(), proof) {
+            reject(e);
+        }
 
-        // Mark nullifier spent
-        self.nullifiers.insert(nullifier_bytes);
+        // Mark nullifiers spent
+        for nullifier in &nullifiers {
+            self.nullifiers.insert(nullifier.to_be_bytes());
+        }
         self.proof_count += 1;
 
-        // If creating new commitment, add it to tree
-        if public_inputs.len() > 1 && !public_inputs[1].is_empty() {
-            let commitment_u256 = U256::from_dec_str(&public_inputs[1])
-                .expect("Invalid commitment");
-            let commitment_bytes = commitment_u256.to_be_bytes();
-
-            self.commitments.insert(commitment_bytes);
-            let leaf_index = self.commitments_list.len() as u64;
-            self.commitments_list.push(commitment_bytes);
-
-            let asset_id = if public_inputs.len() > 2 {
-                public_inputs[2].clone()
-            } else {
-                "0".to_string()
-            };
+        // If creating new commitments, add them to tree
+        for commitment in &commitments {
+            let leaf_index = self.add_leaf(*commitment);
 
             MultiAssetEvent::CommitmentAdded {
-                commitment: public_inputs[1].clone(),
+                commitment: commitment.to_string(),
                 asset_id: asset_id.clone(),
                 leaf_index,
             }.emit();
         }
 
-        MultiAssetEvent::ProofVerified {
-            nullifier: public_inputs[0].clone(),
-            commitment: if public_inputs.len() > 1 {
-                public_inputs[1].clone()
-            } else {
-                String::new()
-            },
-            asset_id: if public_inputs.len() > 2 {
-                public_inputs[2].clone()
-            } else {
-                "0".to_string()
-            },
-            caller: env::predecessor_account_id(),
-        }.emit();
+        // One event per spent note, paired with the commitment at the same position
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            MultiAssetEvent::ProofVerified {
+                nullifier: nullifier.to_string(),
+                commitment: commitments.get(i).map(|c| c.to_string()).unwrap_or_default(),
+                asset_id: asset_id.clone(),
+                caller: env::predecessor_account_id(),
+            }.emit();
+        }
+    }
+
+    /// Record a new note: commitment set, leaf list and Merkle tree
+    fn add_leaf(&mut self, commitment: U256) -> u64 {
+        let commitment_bytes = commitment.to_be_bytes();
+        self.commitments.insert(commitment_bytes);
+        self.commitments_list.push(commitment_bytes);
+        self.tree
+            .insert(commitment)
+            .unwrap_or_else(|e| env::panic_str(&format!("Cannot add commitment: {}", e)))
     }
 
     /// DEPRECATED: Do not use - SHA256 != Poseidon
@@ -644,6 +817,16 @@ impl MultiAssetPool {
         U128(self.asset_balances.get(&asset_id).copied().unwrap_or(0))
     }
 
+    /// Current Merkle root (decimal); prove against this or a recent root
+    pub fn get_merkle_root(&self) -> String {
+        self.tree.root().to_string()
+    }
+
+    /// Check if `root` is one of the recent Merkle roots withdrawals accept
+    pub fn is_known_root(&self, root: String) -> bool {
+        parse_field_element(&root).is_ok_and(|root| self.tree.is_known_root(&root))
+    }
+
     /// Get commitment at index
     pub fn get_commitment_at(&self, index: u64) -> String {
         let bytes = self.commitments_list.get(index as u32)
@@ -662,7 +845,7 @@ impl MultiAssetPool {
 
     /// Check if nullifier used (double-spend check)
     pub fn is_nullifier_used(&self, nullifier: String) -> bool {
-        let nullifier_u256 = U256::from_dec_str(&nullifier)
+        let nullifier_u256 = parse_field_element(&nullifier)
             .expect("Invalid nullifier");
         self.nullifiers.contains(&nullifier_u256.to_be_bytes())
     }
@@ -723,9 +906,9 @@ impl MultiAssetPool {
             "Only owner can set asset IDs"
         );
 
-        // Validate asset_id is a valid decimal number
-        U256::from_dec_str(&asset_id)
-            .expect("asset_id must be a valid decimal number");
+        // Validate asset_id is a canonical field element (same check the proof inputs get)
+        parse_field_element(&asset_id)
+            .expect("asset_id must be a canonical field element");
 
         env::log_str(&format!(
             "Set asset ID for {}: {}",
@@ -765,3 +948,212 @@ impl MultiAssetPool {
         self.owner = new_owner;
     }
 }
+
+/// Panic with the reason a proof was rejected
+fn reject(e: VerifyError) -> ! {
+    env::panic_str(&format!("Proof verification failed: {}", e))
+}
+
+/// Parse a withdrawal amount and compare it and the recipient with the proof
+///
+/// Amounts `>= r` are rejected, not reduced. Only the roles the layout names
+/// are compared; see the crate docs.
+fn check_withdraw_arguments(inputs: &LayoutInputs, amount: &str, recipient: &AccountId) -> U256 {
+    let withdraw_amount = parse_field_element(amount).unwrap_or_else(|e| reject(e.into()));
+
+    // SECURITY: Without these roles anyone holding the proof picks amount and recipient
+    if let Some(proven) = inputs.amount() {
+        require!(proven == withdraw_amount, "Amount does not match the proof");
+    }
+    if let Some(proven) = inputs.recipient() {
+        require!(
+            proven == PoseidonAccount::encode(recipient),
+            "Recipient does not match the proof"
+        );
+    }
+    withdraw_amount
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const G2: [[&str; 2]; 2] = [
+        [
+            "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+            "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+        ],
+        [
+            "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+            "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+        ],
+    ];
+
+    fn g1() -> Vec<String> {
+        vec!["1".into(), "2".into(), "1".into()]
+    }
+
+    fn g2() -> Vec<Vec<String>> {
+        let mut point: Vec<Vec<String>> = G2.iter().map(|c| c.iter().map(|s| s.to_string()).collect()).collect();
+        point.push(vec!["1".into(), "0".into()]);
+        point
+    }
+
+    /// A well-formed key with `inputs` public inputs; no proof verifies
+    /// against it, so these tests only reach the checks before the pairing
+    fn vk(inputs: usize) -> VerificationKeyJson {
+        VerificationKeyJson {
+            protocol: None,
+            curve: None,
+            n_public: None,
+            vk_alpha_1: g1(),
+            vk_beta_2: g2(),
+            vk_gamma_2: g2(),
+            vk_delta_2: g2(),
+            ic: vec![g1(); inputs + 1],
+        }
+    }
+
+    fn proof() -> ProofJson {
+        ProofJson { pi_a: g1(), pi_b: g2(), pi_c: g1(), protocol: None, curve: None }
+    }
+
+    const TOKEN_ASSET_ID: &str = "77";
+
+    /// A pool whose layout also names an amount and a recipient, with
+    /// `accounts(3)` configured as a token
+    fn bound_pool() -> MultiAssetPool {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let layout = PublicInputLayout::new(vec![
+            InputRole::Nullifier,
+            InputRole::Root,
+            InputRole::Commitment,
+            InputRole::Asset,
+            InputRole::Amount,
+            InputRole::Recipient,
+        ])
+        .unwrap();
+        let mut pool = MultiAssetPool::new(vk(6), Some(layout));
+        pool.set_token_asset_id(accounts(3), TOKEN_ASSET_ID.into());
+        pool
+    }
+
+    fn bound_inputs(pool: &MultiAssetPool, asset: &str, amount: u64, recipient: &AccountId) -> Vec<String> {
+        vec![
+            "1".into(),
+            pool.get_merkle_root(),
+            "2".into(),
+            asset.into(),
+            amount.to_string(),
+            PoseidonAccount::encode(recipient).to_string(),
+        ]
+    }
+
+    /// A pool for a 2-in/2-out transfer circuit, and its inputs with the given
+    /// nullifiers and commitments
+    fn two_in_two_out(nullifiers: [u64; 2], commitments: [u64; 2]) -> (MultiAssetPool, Vec<String>) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let layout = PublicInputLayout::new(vec![
+            InputRole::Root,
+            InputRole::Asset,
+            InputRole::Nullifier,
+            InputRole::Nullifier,
+            InputRole::Commitment,
+            InputRole::Commitment,
+        ])
+        .unwrap();
+        let pool = MultiAssetPool::new(vk(6), Some(layout));
+        let mut inputs = vec![pool.get_merkle_root(), "0".into()];
+        inputs.extend(nullifiers.iter().chain(&commitments).map(|x| x.to_string()));
+        (pool, inputs)
+    }
+
+    #[test]
+    #[should_panic(expected = "Nullifier already used")]
+    fn test_transfer_rejects_repeated_nullifier() {
+        let (mut pool, inputs) = two_in_two_out([1, 1], [2, 3]);
+        pool.transfer(proof(), inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment already exists")]
+    fn test_transfer_rejects_repeated_commitment() {
+        let (mut pool, inputs) = two_in_two_out([1, 4], [2, 2]);
+        pool.transfer(proof(), inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof verification failed")]
+    fn test_transfer_distinct_notes_reach_verification() {
+        let (mut pool, inputs) = two_in_two_out([1, 4], [2, 3]);
+        pool.transfer(proof(), inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount does not match the proof")]
+    fn test_withdraw_near_rejects_unproven_amount() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, "0", 5, &accounts(1));
+        pool.withdraw_near(proof(), inputs, accounts(1), "6".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Recipient does not match the proof")]
+    fn test_withdraw_near_rejects_unproven_recipient() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, "0", 0, &accounts(1));
+        pool.withdraw_near(proof(), inputs, accounts(2), "0".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof verification failed")]
+    fn test_withdraw_near_proven_arguments_reach_verification() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, "0", 0, &accounts(1));
+        pool.withdraw_near(proof(), inputs, accounts(1), "0".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "field element is not below the BN254 scalar modulus")]
+    fn test_withdraw_near_rejects_non_canonical_amount() {
+        let mut pool = bound_pool();
+        let r = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        let inputs = bound_inputs(&pool, "0", 0, &accounts(1));
+        pool.withdraw_near(proof(), inputs, accounts(1), r.into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount does not match the proof")]
+    fn test_withdraw_ft_rejects_unproven_amount() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, TOKEN_ASSET_ID, 5, &accounts(1));
+        pool.withdraw_ft(proof(), inputs, accounts(1), "6".into(), accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Recipient does not match the proof")]
+    fn test_withdraw_ft_rejects_unproven_recipient() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, TOKEN_ASSET_ID, 0, &accounts(1));
+        pool.withdraw_ft(proof(), inputs, accounts(2), "0".into(), accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof verification failed")]
+    fn test_withdraw_ft_proven_arguments_reach_verification() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, TOKEN_ASSET_ID, 0, &accounts(1));
+        pool.withdraw_ft(proof(), inputs, accounts(1), "0".into(), accounts(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "field element is not below the BN254 scalar modulus")]
+    fn test_withdraw_ft_rejects_non_canonical_amount() {
+        let mut pool = bound_pool();
+        let r = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        let inputs = bound_inputs(&pool, TOKEN_ASSET_ID, 0, &accounts(1));
+        pool.withdraw_ft(proof(), inputs, accounts(1), r.into(), accounts(3));
+    }
+}
@@ -11,6 +11,7 @@
 ///! **Nullifier**: Unique spending key prevents double-spending. Revealed once during withdrawal.
 ///!
 ///! **Merkle Tree**: All commitments stored in a tree. Prove membership without revealing which leaf.
+///! The contract keeps the tree's recent roots and rejects proofs made against any other root.
 ///!
 ///! **ZK Proof**: Proves you know (nullifier, secret, amount) that match a commitment in the tree.
 ///!
@@ -28,6 +29,13 @@
 ///! **Private**: Which deposit funded which withdrawal
 ///! **Public**: Deposit/withdrawal amounts, timing, tree size
 ///!
+///! **Not bound by the proof**: with the default `["nullifier", "root", "commitment"]` layout,
+///! `withdraw`'s `recipient` and `amount` arguments are not public inputs, so the proof does
+///! not commit to them. Whoever submits a valid proof first chooses where the funds go and
+///! how much leaves the pool. Before real use, make them public inputs of the circuit and
+///! name them `"recipient"` and `"amount"` in the layout; `withdraw` then rejects arguments
+///! that differ from the proven values.
+///!
 ///! **Best practices**: Wait before withdrawing, use common amounts (1, 10 NEAR),
 ///! use relayer to hide your account.
 ///!
@@ -36,9 +44,18 @@
 ///! Deposit: ~20 TGas (~0.002 NEAR) | Withdraw: ~120 TGas (~0.012 NEAR)
 
 use near_sdk::borsh::BorshSerialize;
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::store::{LookupSet, Vector};
 use near_sdk::{near, env, require, AccountId, PanicOnDefault, NearToken};
-use near_groth16_verifier::{Verifier, ProofJson, VerificationKeyJson, U256};
+use near_groth16_verifier::{
+    parse_field_element, parse_inputs, parse_le_inputs, CompactProof, IncrementalMerkleTree,
+    InputRole, Proof, ProofJson, PublicInputLayout, VerificationKeyJson, Verifier, VerifyError,
+    DEFAULT_ROOT_HISTORY, U256,
+};
+use near_groth16_verifier::inputs::encoding::{Encoding, PoseidonAccount};
+
+/// Commitment tree depth, as in `Withdraw(20)` (~1M notes)
+const TREE_DEPTH: u8 = 20;
 
 /// Events for off-chain indexing (wallets, explorers)
 /// PRIVACY WARNING: Events are public! Don't emit secrets or nullifiers before use.
@@ -80,7 +97,7 @@ pub enum NearPrivacyEvent {
 /// Privacy Pool Contract - NEAR-only implementation
 ///
 /// **State**: Verifier (proof checker), nullifiers (spent notes), commitments (valid notes),
-/// commitments_list (Merkle tree), owner, balances
+/// commitments_list (Merkle tree leaves), tree (recent roots), owner, balances
 ///
 /// **How it works**: ZK proof lets you prove "I own one of these 1000 notes" without
 /// revealing which one. Nullifiers prevent double-spending. Larger anonymity set =
@@ -91,6 +108,9 @@ pub struct NearPrivacyPool {
     /// Groth16 verifier (proof checker from circuit verification key)
     verifier: Verifier,
 
+    /// Role of each public input (checked against the VK at init)
+    layout: PublicInputLayout,
+
     /// Spent nullifiers (prevents double-spending)
     nullifiers: LookupSet<[u8; 32]>,
 
@@ -100,6 +120,9 @@ pub struct NearPrivacyPool {
     /// Ordered commitments (Merkle tree leaves - clients use this to build tree)
     commitments_list: Vector<[u8; 32]>,
 
+    /// Poseidon Merkle tree over `commitments_list` (frontier and recent roots only)
+    tree: IncrementalMerkleTree,
+
     /// Contract owner (can update VK and transfer ownership, cannot steal funds)
     owner: AccountId,
 
@@ -117,12 +140,25 @@ impl NearPrivacyPool {
     /// VK generated via: `circom → snarkjs groth16 setup → snarkjs zkey export verificationkey`
     ///
     /// **CRITICAL**: VK must match your circuit. Changing VK after initialization breaks all notes!
+    /// **Layout**: role of each public input, default `["nullifier", "root", "commitment"]`.
+    /// Must name a nullifier and a root; `transfer` also needs a commitment. An `amount` and
+    /// a `recipient` (the `PoseidonAccount` encoding of the account id) bind `withdraw`'s
+    /// arguments to the proof.
+    /// Circuits spending or creating several notes repeat `"nullifier"` / `"commitment"`;
+    /// every nullifier is spent and every `transfer` commitment added to the tree.
     /// **Gas**: ~300 TGas (~0.03 NEAR)
     #[init]
-    pub fn new(vk: VerificationKeyJson) -> Self {
+    pub fn new(vk: VerificationKeyJson, layout: Option<PublicInputLayout>) -> Self {
         // Parse and validate verification key
-        let verifier = Verifier::from_json(&vk)
-            .expect("Invalid verification key format");
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
+
+        // SECURITY: Reordered or missing circuit signals fail here, not at withdrawal
+        let layout = layout.unwrap_or_else(|| {
+            PublicInputLayout::new(vec![InputRole::Nullifier, InputRole::Root, InputRole::Commitment])
+                .expect("roles are distinct")
+        });
+        check_layout(&layout, &verifier);
 
         env::log_str(&format!(
             "Privacy pool initialized with {} public inputs",
@@ -131,10 +167,13 @@ impl NearPrivacyPool {
 
         Self {
             verifier,
+            layout,
             // Storage key prefixes for NEAR SDK 5.x
             nullifiers: LookupSet::new(b"n"),
             commitments: LookupSet::new(b"c"),
             commitments_list: Vector::new(b"l"),
+            tree: IncrementalMerkleTree::new(TREE_DEPTH, DEFAULT_ROOT_HISTORY)
+                .expect("valid tree parameters"),
             owner: env::predecessor_account_id(),
             total_balance: NearToken::from_yoctonear(0),
             proof_count: 0,
@@ -150,7 +189,7 @@ impl NearPrivacyPool {
     /// **Private**: Nullifier and secret (keep these to withdraw!)
     ///
     /// **Security**: Commitment must be unique, must attach NEAR
-    /// **Gas**: ~20 TGas (~0.002 NEAR)
+    /// **Gas**: ~20 TGas plus one Poseidon hash per tree level
     #[payable]
     pub fn deposit(&mut self, commitment: String) {
         let deposit_amount = env::attached_deposit();
@@ -161,10 +200,10 @@ impl NearPrivacyPool {
             "Must attach NEAR to deposit"
         );
 
-        // Parse commitment from decimal string to bytes
-        // The commitment should be a BN254 field element (< 254 bits)
-        let commitment_u256 = U256::from_dec_str(&commitment)
-            .expect("Commitment must be a decimal string");
+        // Parse commitment (decimal or 0x-hex) to bytes
+        // Must be a canonical BN254 field element (< r) so it has a single encoding
+        let commitment_u256 = parse_field_element(&commitment)
+            .expect("Commitment must be a canonical field element");
         let commitment_bytes = commitment_u256.to_be_bytes();
 
         // SECURITY: Prevent commitment reuse (could deposit 0.1 NEAR with old commitment, withdraw 1 NEAR)
@@ -173,10 +212,8 @@ impl NearPrivacyPool {
             "Commitment already exists - must be unique"
         );
 
-        // Add to commitment tracking structures
-        self.commitments.insert(commitment_bytes);
-        let leaf_index = self.commitments_list.len() as u64;
-        self.commitments_list.push(commitment_bytes);
+        // Add to commitment tracking structures and the Merkle tree
+        let leaf_index = self.add_leaf(commitment_u256);
 
         // Update total pool balance
         self.total_balance = NearToken::from_yoctonear(
@@ -213,9 +250,13 @@ impl NearPrivacyPool {
     ///
     /// **Security checks**:
     /// - Proof verification (BN254 pairing)
+    /// - Root input is a recent root of this pool's tree
     /// - Nullifier not used before
     /// - Pool has sufficient balance
     ///
+    /// **Not checked by default**: `recipient` and `amount` are only bound to the proof when
+    /// the layout names them (see the crate docs).
+    ///
     /// **Gas**: ~120-150 TGas (~0.012 NEAR)
     #[payable]
     pub fn withdraw(
@@ -225,15 +266,67 @@ impl NearPrivacyPool {
         recipient: AccountId,
         amount: String,
     ) {
-        // Validate we have required public inputs
-        require!(
-            public_inputs.len() >= 1,
-            "Public inputs must include nullifier hash"
-        );
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw(&proof, inputs, recipient, amount)
+    }
+
+    /// [`withdraw`](Self::withdraw) with the proof in compact encoding
+    ///
+    /// 128 bytes of base64 instead of ~1 KB of decimal strings; produce it with
+    /// `near-zk format-proof --compact`.
+    #[payable]
+    pub fn withdraw_compact(
+        &mut self,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+        recipient: AccountId,
+        amount: String,
+    ) {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw(&proof, inputs, recipient, amount)
+    }
+
+    /// [`withdraw`](Self::withdraw) with proof and inputs in the alt_bn128
+    /// precompile layout (`near-zk call-args`)
+    ///
+    /// `proof` is 256 bytes; `public_inputs` is 32 little-endian bytes per input.
+    #[payable]
+    pub fn withdraw_bytes(
+        &mut self,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+        recipient: AccountId,
+        amount: String,
+    ) {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_withdraw(&proof, inputs, recipient, amount)
+    }
 
-        // Parse withdrawal amount from decimal string
-        let withdraw_amount = U256::from_dec_str(&amount)
-            .expect("Amount must be a decimal string");
+    fn internal_withdraw(
+        &mut self,
+        proof: &Proof,
+        public_inputs: Vec<U256>,
+        recipient: AccountId,
+        amount: String,
+    ) {
+        let inputs = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e));
+
+        // Parse withdrawal amount; values >= r are rejected, not reduced
+        let withdraw_amount = parse_field_element(&amount).unwrap_or_else(|e| reject(e.into()));
+
+        // SECURITY: Without these roles anyone holding the proof picks amount and recipient
+        if let Some(proven) = inputs.amount() {
+            require!(proven == withdraw_amount, "Amount does not match the proof");
+        }
+        if let Some(proven) = inputs.recipient() {
+            require!(
+                proven == PoseidonAccount::encode(&recipient),
+                "Recipient does not match the proof"
+            );
+        }
         let withdraw_yocto = u128::try_from(withdraw_amount)
             .expect("Amount too large for u128");
 
@@ -243,25 +336,21 @@ impl NearPrivacyPool {
             "Insufficient pool balance"
         );
 
-        // Parse nullifier (Poseidon(nullifier, leafIndex) - ensures one-time spend)
-        let nullifier_u256 = U256::from_dec_str(&public_inputs[0])
-            .expect("Nullifier must be a decimal string");
-        let nullifier_bytes = nullifier_u256.to_be_bytes();
+        // SECURITY: The proof must be against this pool's tree, not a self-made one
+        check_root(&self.tree, inputs.root().expect("layout has a root"));
 
-        // SECURITY: Double-spend protection
-        require!(
-            !self.nullifiers.contains(&nullifier_bytes),
-            "Nullifier already used - note already spent"
-        );
+        // Nullifier is Poseidon(nullifier, leafIndex) - ensures one-time spend
+        let nullifiers = inputs.nullifiers();
+        self.check_unspent(&nullifiers);
 
         // VERIFY ZK PROOF (~80 TGas)
         // Checks: commitment knowledge, Merkle proof, nullifier derivation, public input binding
-        let is_valid = self.verifier.verify_json(&proof, &public_inputs);
-        require!(is_valid, "Proof verification failed - invalid proof or wrong circuit");
+        if let Err(e) = self.verifier.verify_checked(inputs.as_slice(), proof) {
+            reject(e);
+        }
 
-        // Mark nullifier as used (prevents double-spending)
-        self.nullifiers.insert(nullifier_bytes);
-        self.proof_count += 1;
+        // Mark nullifiers as used (prevents double-spending)
+        self.spend(&nullifiers);
 
         // Update pool balance
         self.total_balance = NearToken::from_yoctonear(
@@ -279,7 +368,7 @@ impl NearPrivacyPool {
         }.emit();
 
         env::log_str(&format!(
-            "Withdrew {} yoctoNEAR to {}, nullifier marked spent (pool balance: {})",
+            "Withdrew {} yoctoNEAR to {}, nullifiers marked spent (pool balance: {})",
             withdraw_yocto,
             recipient,
             self.total_balance.as_yoctonear()
@@ -302,74 +391,140 @@ impl NearPrivacyPool {
         proof: ProofJson,
         public_inputs: Vec<String>,
     ) {
-        require!(
-            public_inputs.len() >= 2,
-            "Must have at least nullifier and commitment"
-        );
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_transfer(&proof, inputs)
+    }
 
-        // Parse input nullifier (note being spent)
-        let nullifier_u256 = U256::from_dec_str(&public_inputs[0])
-            .expect("Nullifier must be a decimal string");
-        let nullifier_bytes = nullifier_u256.to_be_bytes();
+    /// [`transfer`](Self::transfer) with the proof in compact encoding
+    #[payable]
+    pub fn transfer_compact(
+        &mut self,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+    ) {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_transfer(&proof, inputs)
+    }
 
-        // SECURITY: Double-spend check
-        require!(
-            !self.nullifiers.contains(&nullifier_bytes),
-            "Nullifier already used - input note already spent"
-        );
+    /// [`transfer`](Self::transfer) with proof and inputs in the alt_bn128
+    /// precompile layout
+    #[payable]
+    pub fn transfer_bytes(
+        &mut self,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+    ) {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_transfer(&proof, inputs)
+    }
 
-        // VERIFY ZK PROOF (proves note ownership, value conservation, correct outputs)
-        let is_valid = self.verifier.verify_json(&proof, &public_inputs);
-        require!(is_valid, "Proof verification failed");
+    fn internal_transfer(&mut self, proof: &Proof, public_inputs: Vec<U256>) {
+        let inputs = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e));
+        // Input nullifiers (notes being spent) and output commitments (new notes)
+        let nullifiers = inputs.nullifiers();
+        let commitments = inputs.commitments();
+        require!(!commitments.is_empty(), "Circuit layout has no commitment input");
 
-        // Mark input note as spent
-        self.nullifiers.insert(nullifier_bytes);
-        self.proof_count += 1;
+        // SECURITY: The spent notes must be in this pool's tree
+        check_root(&self.tree, inputs.root().expect("layout has a root"));
 
-        // Add output commitment (new note)
-        let commitment_u256 = U256::from_dec_str(&public_inputs[1])
-            .expect("Commitment must be a decimal string");
-        let commitment_bytes = commitment_u256.to_be_bytes();
+        // SECURITY: Double-spend check
+        self.check_unspent(&nullifiers);
 
         // SECURITY: Prevent commitment reuse (would create unbacked notes in tree)
-        require!(
-            !self.commitments.contains(&commitment_bytes),
-            "Commitment already exists - cannot reuse commitments"
-        );
+        for (i, commitment) in commitments.iter().enumerate() {
+            require!(
+                !commitments[..i].contains(commitment) && !self.commitments.contains(&commitment.to_be_bytes()),
+                "Commitment already exists - cannot reuse commitments"
+            );
+        }
 
-        // Add new commitment to tree
-        self.commitments.insert(commitment_bytes);
-        let leaf_index = self.commitments_list.len() as u64;
-        self.commitments_list.push(commitment_bytes);
+        // VERIFY ZK PROOF (proves note ownership, value conservation, correct outputs)
+        if let Err(e) = self.verifier.verify_checked(inputs.as_slice(), proof) {
+            reject(e);
+        }
 
-        // Emit events for indexers
-        NearPrivacyEvent::ProofVerified {
-            nullifier: public_inputs[0].clone(),
-            commitment: public_inputs[1].clone(),
-            caller: env::predecessor_account_id(),
-        }.emit();
+        // Mark input notes as spent
+        self.spend(&nullifiers);
+
+        // Add new commitments to tree and emit events for indexers
+        let mut leaf_indices = Vec::with_capacity(commitments.len());
+        for commitment in &commitments {
+            let leaf_index = self.add_leaf(*commitment);
+            NearPrivacyEvent::CommitmentAdded {
+                commitment: commitment.to_string(),
+                leaf_index,
+            }.emit();
+            leaf_indices.push(leaf_index);
+        }
 
-        NearPrivacyEvent::CommitmentAdded {
-            commitment: public_inputs[1].clone(),
-            leaf_index,
-        }.emit();
+        // One event per spent note, paired with the commitment at the same position
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            NearPrivacyEvent::ProofVerified {
+                nullifier: nullifier.to_string(),
+                commitment: commitments.get(i).map(|c| c.to_string()).unwrap_or_default(),
+                caller: env::predecessor_account_id(),
+            }.emit();
+        }
 
         env::log_str(&format!(
-            "Private transfer completed: nullifier spent, new commitment at index {}",
-            leaf_index
+            "Private transfer completed: {} nullifiers spent, new commitments at indices {:?}",
+            nullifiers.len(),
+            leaf_indices
         ));
     }
 
+    /// Panic if a nullifier was spent before or repeats within the proof
+    fn check_unspent(&self, nullifiers: &[U256]) {
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            require!(
+                !nullifiers[..i].contains(nullifier) && !self.nullifiers.contains(&nullifier.to_be_bytes()),
+                "Nullifier already used - note already spent"
+            );
+        }
+    }
+
+    /// Mark the nullifiers of one verified proof as spent
+    fn spend(&mut self, nullifiers: &[U256]) {
+        for nullifier in nullifiers {
+            self.nullifiers.insert(nullifier.to_be_bytes());
+        }
+        self.proof_count += 1;
+    }
+
+    /// Record a new note: commitment set, leaf list and Merkle tree
+    fn add_leaf(&mut self, commitment: U256) -> u64 {
+        let commitment_bytes = commitment.to_be_bytes();
+        self.commitments.insert(commitment_bytes);
+        self.commitments_list.push(commitment_bytes);
+        self.tree
+            .insert(commitment)
+            .unwrap_or_else(|e| env::panic_str(&format!("Cannot add commitment: {}", e)))
+    }
+
     // ========== VIEW METHODS (Read-only, no gas cost) ==========
 
     /// Check if nullifier has been used (note is spent)
     /// Use before generating proof to verify note is still unspent.
     pub fn is_nullifier_used(&self, nullifier: String) -> bool {
-        let nullifier_u256 = U256::from_dec_str(&nullifier)
+        let nullifier_u256 = parse_field_element(&nullifier)
             .expect("Invalid nullifier");
         self.nullifiers.contains(&nullifier_u256.to_be_bytes())
     }
 
+    /// Current Merkle root (decimal); prove against this or a recent root
+    pub fn get_merkle_root(&self) -> String {
+        self.tree.root().to_string()
+    }
+
+    /// Check if `root` is one of the recent Merkle roots withdrawals accept
+    pub fn is_known_root(&self, root: String) -> bool {
+        parse_field_element(&root).is_ok_and(|root| self.tree.is_known_root(&root))
+    }
+
     /// Get commitment at specific index in Merkle tree
     ///
     /// Used for debugging or verifying Merkle tree reconstruction.
@@ -426,8 +581,10 @@ impl NearPrivacyPool {
             env::predecessor_account_id() == self.owner,
             "Only owner can update verification key"
         );
-        self.verifier = Verifier::from_json(&vk)
-            .expect("Invalid verification key");
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
+        check_layout(&self.layout, &verifier);
+        self.verifier = verifier;
         env::log_str("⚠️ Verification key updated - existing notes may be unspendable!");
     }
 
@@ -442,3 +599,223 @@ impl NearPrivacyPool {
         env::log_str(&format!("Ownership transferred from {} to {}", old_owner, new_owner));
     }
 }
+
+/// Panic unless `layout` matches the verification key and names a nullifier
+/// and a root
+fn check_layout(layout: &PublicInputLayout, verifier: &Verifier) {
+    if let Err(e) = layout
+        .check_vk(&verifier.vk)
+        .and_then(|()| layout.require(&InputRole::Nullifier))
+        .and_then(|_| layout.require(&InputRole::Root))
+    {
+        env::panic_str(&format!("Invalid public input layout: {}", e));
+    }
+}
+
+/// Panic unless `root` is a recent root of `tree`
+fn check_root(tree: &IncrementalMerkleTree, root: U256) {
+    require!(
+        tree.is_known_root(&root),
+        "Unknown Merkle root - proof is not against a recent pool root"
+    );
+}
+
+/// Panic with the reason a proof was rejected
+fn reject(e: VerifyError) -> ! {
+    env::panic_str(&format!("Proof verification failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    const G2: [[&str; 2]; 2] = [
+        [
+            "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+            "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+        ],
+        [
+            "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+            "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+        ],
+    ];
+
+    fn g1() -> Vec<String> {
+        vec!["1".into(), "2".into(), "1".into()]
+    }
+
+    fn g2() -> Vec<Vec<String>> {
+        let mut point: Vec<Vec<String>> = G2.iter().map(|c| c.iter().map(|s| s.to_string()).collect()).collect();
+        point.push(vec!["1".into(), "0".into()]);
+        point
+    }
+
+    /// A well-formed key with `inputs` public inputs; no proof verifies
+    /// against it, so these tests only reach the checks before the pairing
+    fn vk(inputs: usize) -> VerificationKeyJson {
+        VerificationKeyJson {
+            protocol: None,
+            curve: None,
+            n_public: None,
+            vk_alpha_1: g1(),
+            vk_beta_2: g2(),
+            vk_gamma_2: g2(),
+            vk_delta_2: g2(),
+            ic: vec![g1(); inputs + 1],
+        }
+    }
+
+    fn proof() -> ProofJson {
+        ProofJson { pi_a: g1(), pi_b: g2(), pi_c: g1(), protocol: None, curve: None }
+    }
+
+    /// A pool with the default layout, and inputs against a root it never had
+    fn unknown_root_call() -> (NearPrivacyPool, Proof, Vec<U256>) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let pool = NearPrivacyPool::new(vk(3), None);
+        let inputs = vec![U256::from(1u64), U256::from(12345u64), U256::from(2u64)];
+        assert!(!pool.is_known_root(inputs[1].to_string()));
+        (pool, proof().to_proof().unwrap(), inputs)
+    }
+
+    fn compact(proof: &Proof) -> CompactProof {
+        CompactProof::from_proof(proof).unwrap()
+    }
+
+    fn precompile_bytes(proof: &Proof, inputs: &[U256]) -> (Base64VecU8, Base64VecU8) {
+        let inputs = inputs.iter().flat_map(|x| x.to_le_bytes()).collect();
+        (Base64VecU8(proof.to_precompile_bytes().to_vec()), Base64VecU8(inputs))
+    }
+
+    fn strings(inputs: &[U256]) -> Vec<String> {
+        inputs.iter().map(|x| x.to_string()).collect()
+    }
+
+    /// A pool whose layout also names an amount and a recipient
+    fn bound_pool() -> NearPrivacyPool {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let layout = PublicInputLayout::new(vec![
+            InputRole::Nullifier,
+            InputRole::Root,
+            InputRole::Commitment,
+            InputRole::Amount,
+            InputRole::Recipient,
+        ])
+        .unwrap();
+        NearPrivacyPool::new(vk(5), Some(layout))
+    }
+
+    fn bound_inputs(pool: &NearPrivacyPool, amount: u64, recipient: &AccountId) -> Vec<String> {
+        vec![
+            "1".into(),
+            pool.get_merkle_root(),
+            "2".into(),
+            amount.to_string(),
+            PoseidonAccount::encode(recipient).to_string(),
+        ]
+    }
+
+    #[test]
+    #[should_panic(expected = "Amount does not match the proof")]
+    fn test_withdraw_rejects_unproven_amount() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, 5, &accounts(1));
+        pool.withdraw(proof(), inputs, accounts(1), "6".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Recipient does not match the proof")]
+    fn test_withdraw_rejects_unproven_recipient() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, 0, &accounts(1));
+        pool.withdraw(proof(), inputs, accounts(2), "0".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof verification failed")]
+    fn test_withdraw_proven_arguments_reach_verification() {
+        let mut pool = bound_pool();
+        let inputs = bound_inputs(&pool, 0, &accounts(1));
+        pool.withdraw(proof(), inputs, accounts(1), "0".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "field element is not below the BN254 scalar modulus")]
+    fn test_withdraw_rejects_non_canonical_amount() {
+        let mut pool = bound_pool();
+        let r = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+        let inputs = bound_inputs(&pool, 0, &accounts(1));
+        pool.withdraw(proof(), inputs, accounts(1), r.into());
+    }
+
+    /// A pool for a 2-in/2-out transfer circuit, and its inputs with the given
+    /// nullifiers and commitments
+    fn two_in_two_out(nullifiers: [u64; 2], commitments: [u64; 2]) -> (NearPrivacyPool, Vec<String>) {
+        testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(0)).build());
+        let layout = PublicInputLayout::new(vec![
+            InputRole::Root,
+            InputRole::Nullifier,
+            InputRole::Nullifier,
+            InputRole::Commitment,
+            InputRole::Commitment,
+        ])
+        .unwrap();
+        let pool = NearPrivacyPool::new(vk(5), Some(layout));
+        let mut inputs = vec![pool.get_merkle_root()];
+        inputs.extend(nullifiers.iter().chain(&commitments).map(|x| x.to_string()));
+        (pool, inputs)
+    }
+
+    #[test]
+    #[should_panic(expected = "Nullifier already used")]
+    fn test_transfer_rejects_repeated_nullifier() {
+        let (mut pool, inputs) = two_in_two_out([1, 1], [2, 3]);
+        pool.transfer(proof(), inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Commitment already exists")]
+    fn test_transfer_rejects_repeated_commitment() {
+        let (mut pool, inputs) = two_in_two_out([1, 4], [2, 2]);
+        pool.transfer(proof(), inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proof verification failed")]
+    fn test_transfer_distinct_notes_reach_verification() {
+        let (mut pool, inputs) = two_in_two_out([1, 4], [2, 3]);
+        pool.transfer(proof(), inputs);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown Merkle root")]
+    fn test_withdraw_compact_rejects_unknown_root() {
+        let (mut pool, proof, inputs) = unknown_root_call();
+        pool.withdraw_compact(compact(&proof), strings(&inputs), accounts(1), "0".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown Merkle root")]
+    fn test_withdraw_bytes_rejects_unknown_root() {
+        let (mut pool, proof, inputs) = unknown_root_call();
+        let (proof, inputs) = precompile_bytes(&proof, &inputs);
+        pool.withdraw_bytes(proof, inputs, accounts(1), "0".into());
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown Merkle root")]
+    fn test_transfer_compact_rejects_unknown_root() {
+        let (mut pool, proof, inputs) = unknown_root_call();
+        pool.transfer_compact(compact(&proof), strings(&inputs));
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown Merkle root")]
+    fn test_transfer_bytes_rejects_unknown_root() {
+        let (mut pool, proof, inputs) = unknown_root_call();
+        let (proof, inputs) = precompile_bytes(&proof, &inputs);
+        pool.transfer_bytes(proof, inputs);
+    }
+}
@@ -15,8 +15,8 @@
 //!
 //! ## How This Template Works
 //!
-//! 1. **verify_and_register()**: Verifies proof, extracts nullifier from public_inputs[0],
-//!    marks it as "used", optionally registers a new commitment from public_inputs[1].
+//! 1. **verify_and_register()**: Verifies proof, extracts the nullifier input, marks it
+//!    as "used", and registers the commitment input if the circuit has one.
 //!
 //! 2. **Nullifier check**: If nullifier already used → reject (prevents double-spend)
 //!
@@ -24,6 +24,10 @@
 //!
 //! 4. **State update**: Record nullifier, optionally add commitment, increment counter
 //!
+//! Which input is the nullifier and which the commitment is set by the
+//! [`PublicInputLayout`] passed to `new` (default `["nullifier", "commitment"]`),
+//! and checked against the verification key there.
+//!
 //! ## What You Can Build
 //!
 //! **Privacy pool**: Nullifier = spent note, Commitment = new deposit
@@ -43,9 +47,13 @@
 //! - Commitment uniqueness checks
 
 use near_sdk::borsh::BorshSerialize;
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::store::LookupSet;
 use near_sdk::{near, env, require, AccountId, PanicOnDefault};
-use near_groth16_verifier::{Verifier, ProofJson, VerificationKeyJson, U256};
+use near_groth16_verifier::{
+    parse_field_element, parse_inputs, parse_le_inputs, CompactProof, InputRole, Proof, ProofJson,
+    PublicInputLayout, VerificationKeyJson, Verifier, VerifyError, U256,
+};
 
 /// Events for off-chain indexing
 #[near(event_json(standard = "nep297"))]
@@ -69,6 +77,8 @@ pub enum PrivacyEvent {
 pub struct PrivacyContract {
     /// Groth16 verifier
     verifier: Verifier,
+    /// Role of each public input
+    layout: PublicInputLayout,
     /// Used nullifiers (prevent double-spending)
     nullifiers: LookupSet<[u8; 32]>,
     /// Valid commitments (for set membership proofs)
@@ -85,12 +95,22 @@ impl PrivacyContract {
     ///
     /// # Arguments
     /// * `vk` - Verification key in snarkjs JSON format
+    /// * `layout` - Role of each public input; must name a nullifier.
+    ///   Defaults to `["nullifier", "commitment"]`. Circuits spending or creating several
+    ///   notes repeat `"nullifier"` / `"commitment"`; every one is checked and recorded
     #[init]
-    pub fn new(vk: VerificationKeyJson) -> Self {
-        let verifier = Verifier::from_json(&vk).expect("Invalid verification key");
+    pub fn new(vk: VerificationKeyJson, layout: Option<PublicInputLayout>) -> Self {
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
+        let layout = layout.unwrap_or_else(|| {
+            PublicInputLayout::new(vec![InputRole::Nullifier, InputRole::Commitment])
+                .expect("roles are distinct")
+        });
+        check_layout(&layout, &verifier);
 
         Self {
             verifier,
+            layout,
             nullifiers: LookupSet::new(b"n"),
             commitments: LookupSet::new(b"c"),
             owner: env::predecessor_account_id(),
@@ -100,12 +120,12 @@ impl PrivacyContract {
 
     /// Verify proof and register nullifier
     ///
-    /// The first public input is treated as the nullifier.
-    /// The second public input (if present) is treated as a new commitment.
+    /// The nullifier input is marked as used; the commitment input, if the
+    /// layout has one, is registered as a new commitment.
     ///
     /// # Arguments
     /// * `proof` - Groth16 proof
-    /// * `public_inputs` - [nullifier, commitment?, ...]
+    /// * `public_inputs` - All public inputs, in layout order
     ///
     /// # Panics
     /// - If nullifier already used
@@ -116,66 +136,104 @@ impl PrivacyContract {
         proof: ProofJson,
         public_inputs: Vec<String>,
     ) -> bool {
-        require!(
-            !public_inputs.is_empty(),
-            "At least one public input (nullifier) required"
-        );
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_verify_and_register(&proof, inputs)
+    }
 
-        // Parse nullifier (first input)
-        let nullifier_u256 = U256::from_dec_str(&public_inputs[0])
-            .expect("Invalid nullifier format");
-        let nullifier_bytes = nullifier_u256.to_be_bytes();
+    /// [`verify_and_register`](Self::verify_and_register) for a proof in
+    /// compact encoding (128 bytes, base64)
+    #[payable]
+    pub fn verify_and_register_compact(
+        &mut self,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+    ) -> bool {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_verify_and_register(&proof, inputs)
+    }
 
-        // Check nullifier hasn't been used
-        require!(
-            !self.nullifiers.contains(&nullifier_bytes),
-            "Nullifier already used - possible double-spend attempt"
-        );
+    /// [`verify_and_register`](Self::verify_and_register) with proof and
+    /// inputs in the alt_bn128 precompile layout (`near-zk call-args`)
+    ///
+    /// `proof` is 256 bytes; `public_inputs` is 32 little-endian bytes per input.
+    #[payable]
+    pub fn verify_and_register_bytes(
+        &mut self,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+    ) -> bool {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_verify_and_register(&proof, inputs)
+    }
+
+    fn internal_verify_and_register(&mut self, proof: &Proof, public_inputs: Vec<U256>) -> bool {
+        let inputs = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e));
+
+        // Required by the layout at init; a circuit spending several notes has one per note
+        let nullifiers = inputs.nullifiers();
+        let commitments = inputs.commitments();
+
+        // Check no nullifier has been used, here or earlier in the same proof
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            require!(
+                !nullifiers[..i].contains(nullifier) && !self.nullifiers.contains(&nullifier.to_be_bytes()),
+                "Nullifier already used - possible double-spend attempt"
+            );
+        }
+        for (i, commitment) in commitments.iter().enumerate() {
+            require!(
+                !commitments[..i].contains(commitment) && !self.commitments.contains(&commitment.to_be_bytes()),
+                "Commitment already exists - cannot reuse commitments"
+            );
+        }
 
         // Verify the proof
-        let is_valid = self.verifier.verify_json(&proof, &public_inputs);
-        require!(is_valid, "Proof verification failed");
+        if let Err(e) = self.verifier.verify_checked(inputs.as_slice(), proof) {
+            reject(e);
+        }
 
-        // Register nullifier
-        self.nullifiers.insert(nullifier_bytes);
+        // Register nullifiers
+        for nullifier in &nullifiers {
+            self.nullifiers.insert(nullifier.to_be_bytes());
+        }
         self.proof_count += 1;
 
-        // If there's a second input, register it as a commitment
-        let commitment_str = if public_inputs.len() > 1 {
-            let commitment_u256 = U256::from_dec_str(&public_inputs[1])
-                .expect("Invalid commitment format");
-            let commitment_bytes = commitment_u256.to_be_bytes();
-            self.commitments.insert(commitment_bytes);
+        // If the circuit outputs commitments, register them
+        for commitment in &commitments {
+            self.commitments.insert(commitment.to_be_bytes());
 
             PrivacyEvent::CommitmentAdded {
-                commitment: public_inputs[1].clone(),
+                commitment: commitment.to_string(),
             }.emit();
+        }
 
-            public_inputs[1].clone()
-        } else {
-            String::new()
-        };
-
-        // Emit verification event
-        PrivacyEvent::ProofVerified {
-            nullifier: public_inputs[0].clone(),
-            commitment: commitment_str,
-            caller: env::predecessor_account_id(),
-        }.emit();
+        // Emit one verification event per spent nullifier, paired with the
+        // commitment at the same position (every commitment has its own
+        // CommitmentAdded event)
+        for (i, nullifier) in nullifiers.iter().enumerate() {
+            PrivacyEvent::ProofVerified {
+                nullifier: nullifier.to_string(),
+                commitment: commitments.get(i).map(|c| c.to_string()).unwrap_or_default(),
+                caller: env::predecessor_account_id(),
+            }.emit();
+        }
 
         true
     }
 
     /// Check if a nullifier has been used
     pub fn is_nullifier_used(&self, nullifier: String) -> bool {
-        let nullifier_u256 = U256::from_dec_str(&nullifier)
+        let nullifier_u256 = parse_field_element(&nullifier)
             .expect("Invalid nullifier format");
         self.nullifiers.contains(&nullifier_u256.to_be_bytes())
     }
 
     /// Check if a commitment exists
     pub fn commitment_exists(&self, commitment: String) -> bool {
-        let commitment_u256 = U256::from_dec_str(&commitment)
+        let commitment_u256 = parse_field_element(&commitment)
             .expect("Invalid commitment format");
         self.commitments.contains(&commitment_u256.to_be_bytes())
     }
@@ -189,7 +247,7 @@ impl PrivacyContract {
             "Only owner can add commitments directly"
         );
 
-        let commitment_u256 = U256::from_dec_str(&commitment)
+        let commitment_u256 = parse_field_element(&commitment)
             .expect("Invalid commitment format");
         self.commitments.insert(commitment_u256.to_be_bytes());
 
@@ -201,6 +259,19 @@ impl PrivacyContract {
         self.verifier.verify_json(&proof, &public_inputs)
     }
 
+    /// Verify a compact proof without registering (view method for testing)
+    pub fn verify_only_compact(&self, proof: CompactProof, public_inputs: Vec<String>) -> bool {
+        self.verifier.verify_compact(&proof, &public_inputs)
+    }
+
+    /// Verify a precompile-layout proof without registering (view method for testing)
+    pub fn verify_only_bytes(&self, proof: Base64VecU8, public_inputs: Base64VecU8) -> bool {
+        match (Proof::from_precompile_slice(&proof.0), parse_le_inputs(&public_inputs.0)) {
+            (Ok(proof), Ok(inputs)) => self.verifier.verify_checked(&inputs, &proof).is_ok(),
+            _ => false,
+        }
+    }
+
     /// Get contract statistics
     pub fn get_stats(&self) -> (u64, usize, AccountId) {
         (
@@ -216,7 +287,10 @@ impl PrivacyContract {
             env::predecessor_account_id() == self.owner,
             "Only owner can update verification key"
         );
-        self.verifier = Verifier::from_json(&vk).expect("Invalid verification key");
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
+        check_layout(&self.layout, &verifier);
+        self.verifier = verifier;
         env::log_str("Verification key updated");
     }
 
@@ -229,3 +303,15 @@ impl PrivacyContract {
         self.owner = new_owner;
     }
 }
+
+/// Panic unless `layout` matches the verification key and names a nullifier
+fn check_layout(layout: &PublicInputLayout, verifier: &Verifier) {
+    if let Err(e) = layout.check_vk(&verifier.vk).and_then(|()| layout.require(&InputRole::Nullifier)) {
+        env::panic_str(&format!("Invalid public input layout: {}", e));
+    }
+}
+
+/// Panic with the reason a proof was rejected
+fn reject(e: VerifyError) -> ! {
+    env::panic_str(&format!("Proof verification failed: {}", e))
+}
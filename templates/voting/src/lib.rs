@@ -64,9 +64,14 @@
 
 use near_sdk::borsh::{BorshSerialize, BorshDeserialize};
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::json_types::Base64VecU8;
 use near_sdk::store::{LookupMap, LookupSet};
 use near_sdk::{near, env, require, AccountId, PanicOnDefault};
-use near_groth16_verifier::{Verifier, ProofJson, VerificationKeyJson, U256};
+use near_groth16_verifier::{
+    parse_field_element, parse_inputs, parse_le_inputs, CompactProof, InputRole, Proof, ProofJson,
+    PublicInputLayout,
+    VerificationKeyJson, Verifier, VerifyError, U256,
+};
 
 /// Poll status
 #[derive(Clone, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
@@ -123,6 +128,8 @@ pub enum VotingEvent {
 pub struct VotingContract {
     /// Groth16 verifier for vote proofs
     verifier: Verifier,
+    /// Role of each public input (checked against the VK at init)
+    layout: PublicInputLayout,
     /// All polls (poll_id -> Poll)
     polls: LookupMap<u64, Poll>,
     /// Used nullifiers per poll (poll_id, nullifier_bytes)
@@ -139,20 +146,36 @@ impl VotingContract {
     ///
     /// # Arguments
     /// * `vk` - Verification key for the voting circuit
+    /// * `layout` - Role of each public input; must name the nullifier, the voter
+    ///   root and the poll id (`external_nullifier`), each exactly once.
+    ///   Defaults to `["nullifier", "root", "external_nullifier"]`
     #[init]
-    pub fn new(vk: VerificationKeyJson) -> Self {
-        let verifier = Verifier::from_json(&vk).expect("Invalid verification key");
+    pub fn new(vk: VerificationKeyJson, layout: Option<PublicInputLayout>) -> Self {
+        let verifier = Verifier::from_json_checked(&vk)
+            .unwrap_or_else(|e| env::panic_str(&format!("Invalid verification key: {}", e)));
 
         // PRIVACY FIX: Voting circuit now has 3 public inputs (vote removed):
         // [nullifier, voterTreeRoot, pollId]
         // The vote is determined by which method is called (vote_yes/vote_no)
-        require!(
-            verifier.vk.num_inputs() == 3,
-            "Voting circuit must have exactly 3 public inputs (nullifier, voterTreeRoot, pollId)"
-        );
+        let layout = layout.unwrap_or_else(|| {
+            PublicInputLayout::new(vec![
+                InputRole::Nullifier,
+                InputRole::Root,
+                InputRole::ExternalNullifier,
+            ])
+            .expect("roles are distinct")
+        });
+        let required = [InputRole::Nullifier, InputRole::Root, InputRole::ExternalNullifier];
+        if let Err(e) = layout
+            .check_vk(&verifier.vk)
+            .and_then(|()| required.iter().try_for_each(|role| layout.require_unique(role).map(drop)))
+        {
+            env::panic_str(&format!("Invalid public input layout: {}", e));
+        }
 
         Self {
             verifier,
+            layout,
             polls: LookupMap::new(b"p"),
             nullifiers: LookupSet::new(b"n"),
             next_poll_id: 1,
@@ -216,7 +239,40 @@ impl VotingContract {
         proof: ProofJson,
         public_inputs: Vec<String>,
     ) -> bool {
-        self.internal_vote(poll_id, proof, public_inputs, true)
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_vote(poll_id, proof, inputs, true)
+    }
+
+    /// [`vote_yes`](Self::vote_yes) with the proof in compact encoding
+    ///
+    /// 128 bytes of base64 instead of ~1 KB of decimal strings; produce it with
+    /// `near-zk format-proof --compact`.
+    pub fn vote_yes_compact(
+        &mut self,
+        poll_id: u64,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+    ) -> bool {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_vote(poll_id, proof, inputs, true)
+    }
+
+    /// [`vote_yes`](Self::vote_yes) with proof and inputs in the alt_bn128
+    /// precompile layout (`near-zk call-args`)
+    ///
+    /// `proof` is 256 bytes; `public_inputs` is 32 little-endian bytes per input.
+    /// Nothing is parsed from decimal, so this is the cheapest way to vote.
+    pub fn vote_yes_bytes(
+        &mut self,
+        poll_id: u64,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+    ) -> bool {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_vote(poll_id, proof, inputs, true)
     }
 
     /// Cast a NO vote with ZK proof
@@ -234,22 +290,55 @@ impl VotingContract {
         proof: ProofJson,
         public_inputs: Vec<String>,
     ) -> bool {
-        self.internal_vote(poll_id, proof, public_inputs, false)
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_vote(poll_id, proof, inputs, false)
+    }
+
+    /// [`vote_no`](Self::vote_no) with the proof in compact encoding
+    ///
+    /// 128 bytes of base64 instead of ~1 KB of decimal strings; produce it with
+    /// `near-zk format-proof --compact`.
+    pub fn vote_no_compact(
+        &mut self,
+        poll_id: u64,
+        proof: CompactProof,
+        public_inputs: Vec<String>,
+    ) -> bool {
+        let proof = proof.to_proof().unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_inputs(&public_inputs).unwrap_or_else(|e| reject(e));
+        self.internal_vote(poll_id, proof, inputs, false)
+    }
+
+    /// [`vote_no`](Self::vote_no) with proof and inputs in the alt_bn128
+    /// precompile layout (`near-zk call-args`)
+    ///
+    /// `proof` is 256 bytes; `public_inputs` is 32 little-endian bytes per input.
+    /// Nothing is parsed from decimal, so this is the cheapest way to vote.
+    pub fn vote_no_bytes(
+        &mut self,
+        poll_id: u64,
+        proof: Base64VecU8,
+        public_inputs: Base64VecU8,
+    ) -> bool {
+        let proof = Proof::from_precompile_slice(&proof.0).unwrap_or_else(|e| reject(e.into()));
+        let inputs = parse_le_inputs(&public_inputs.0).unwrap_or_else(|e| reject(e));
+        self.internal_vote(poll_id, proof, inputs, false)
     }
 
     /// Internal vote logic (used by vote_yes and vote_no)
     fn internal_vote(
         &mut self,
         poll_id: u64,
-        proof: ProofJson,
-        public_inputs: Vec<String>,
+        proof: Proof,
+        public_inputs: Vec<U256>,
         is_yes_vote: bool,
     ) -> bool {
-        // Validate inputs (3 public inputs: nullifier, voterTreeRoot, pollId)
-        require!(
-            public_inputs.len() == 3,
-            "Expected 3 public inputs: [nullifier, voterTreeRoot, pollId]"
-        );
+        // Nullifier, voter root and poll id are required by the layout at init
+        let inputs = self.layout.bind(&public_inputs).unwrap_or_else(|e| reject(e));
+        let nullifier = inputs.nullifier().expect("layout has a nullifier");
+        let voter_root = inputs.root().expect("layout has a root");
+        let input_poll_id = inputs.external_nullifier().expect("layout has a poll id");
 
         // Get poll (clone needed for SDK 5.x store::LookupMap)
         let mut poll = self.polls.get(&poll_id).expect("Poll not found").clone();
@@ -266,22 +355,17 @@ impl VotingContract {
             "Poll has ended"
         );
 
-        // Parse nullifier
-        let nullifier_u256 = U256::from_dec_str(&public_inputs[0])
-            .expect("Invalid nullifier");
-        let nullifier_bytes = nullifier_u256.to_be_bytes();
+        let nullifier_bytes = nullifier.to_be_bytes();
 
         // Verify voter root matches
         require!(
-            public_inputs[1] == poll.voter_root,
+            parse_field_element(&poll.voter_root) == Ok(voter_root),
             "Voter root mismatch - you may not be eligible for this poll"
         );
 
         // Verify poll ID matches
-        let proof_poll_id: u64 = public_inputs[2].parse()
-            .expect("Invalid poll ID in proof");
         require!(
-            proof_poll_id == poll_id,
+            input_poll_id == U256::from(poll_id),
             "Poll ID mismatch"
         );
 
@@ -292,8 +376,9 @@ impl VotingContract {
         );
 
         // Verify the ZK proof (proves voter eligibility)
-        let is_valid = self.verifier.verify_json(&proof, &public_inputs);
-        require!(is_valid, "Invalid vote proof");
+        if let Err(e) = self.verifier.verify_checked(inputs.as_slice(), &proof) {
+            reject(e);
+        }
 
         // Record the vote (determined by method called, NOT public input)
         if is_yes_vote {
@@ -310,7 +395,7 @@ impl VotingContract {
 
         VotingEvent::VoteCast {
             poll_id,
-            nullifier: public_inputs[0].clone(),
+            nullifier: nullifier.to_string(),
         }.emit();
 
         true
@@ -387,7 +472,7 @@ impl VotingContract {
 
     /// Check if user has voted (by nullifier)
     pub fn has_voted(&self, poll_id: u64, nullifier: String) -> bool {
-        let nullifier_u256 = U256::from_dec_str(&nullifier)
+        let nullifier_u256 = parse_field_element(&nullifier)
             .expect("Invalid nullifier");
         self.nullifiers.contains(&(poll_id, nullifier_u256.to_be_bytes()))
     }
@@ -397,3 +482,8 @@ impl VotingContract {
         self.next_poll_id
     }
 }
+
+/// Panic with the reason a vote proof was rejected
+fn reject(e: VerifyError) -> ! {
+    env::panic_str(&format!("Invalid vote proof: {}", e))
+}